@@ -6,7 +6,9 @@
 //! It is primarily used for testing purposes.
 use crate::aptos_test_utils::error::AptosError;
 use aptos_crypto::bls12381::Signature;
-use aptos_crypto::hash::{CryptoHash, TransactionAccumulatorHasher};
+use aptos_crypto::hash::{
+    CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher, ACCUMULATOR_PLACEHOLDER_HASH,
+};
 use aptos_crypto::HashValue;
 use aptos_executor::block_executor::BlockExecutor;
 use aptos_executor_test_helpers::gen_block_id;
@@ -22,8 +24,9 @@ use aptos_types::aggregate_signature::PartialSignatures;
 use aptos_types::block_info::BlockInfo;
 use aptos_types::block_metadata::BlockMetadata;
 use aptos_types::chain_id::ChainId;
-use aptos_types::ledger_info::LedgerInfoWithSignatures;
-use aptos_types::proof::{AccumulatorProof, SparseMerkleProof};
+use aptos_types::contract_event::ContractEvent;
+use aptos_types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+use aptos_types::proof::{AccumulatorProof, MerkleTreeInternalNode, SparseMerkleProof};
 use aptos_types::state_proof::StateProof;
 use aptos_types::state_store::state_key::StateKey;
 use aptos_types::state_store::state_value::StateValue;
@@ -256,9 +259,37 @@ impl AptosWrapper {
             HashValue::zero(),
         );
 
+        let li = self.sign_ledger_info(ledger_info, self.signers_per_block)?;
+
+        // Save block to persistent storage
+        self.executor()
+            .commit_blocks(vec![block_id], li)
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        self.db()
+            .reader
+            .get_state_proof(from_version)
+            .map_err(|e| AptosError::Internal { source: e.into() })
+    }
+
+    /// Signs a ledger info with the first validators of the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `ledger_info` - The ledger info to sign.
+    /// * `nbr_signers` - The number of validators signing it.
+    ///
+    /// # Returns
+    ///
+    /// * `LedgerInfoWithSignatures` - The ledger info along with the aggregated signatures.
+    fn sign_ledger_info(
+        &self,
+        ledger_info: LedgerInfo,
+        nbr_signers: usize,
+    ) -> Result<LedgerInfoWithSignatures, AptosError> {
         let partial_sig = PartialSignatures::new(
             self.signers()
-                .get(..self.signers_per_block)
+                .get(..nbr_signers)
                 .ok_or(AptosError::UnexpectedNone("ValidatorSigner".to_string()))?
                 .iter()
                 .map(|signer| {
@@ -278,26 +309,16 @@ impl AptosWrapper {
 
         let validator_verifier = ValidatorVerifier::new_with_quorum_voting_power(
             validator_consensus_info,
-            self.signers_per_block as u128,
+            nbr_signers as u128,
         )
-            .expect("Incorrect quorum size.");
+        .expect("Incorrect quorum size.");
 
-        let li = LedgerInfoWithSignatures::new(
+        Ok(LedgerInfoWithSignatures::new(
             ledger_info,
             validator_verifier
                 .aggregate_signatures(&partial_sig)
                 .map_err(|e| AptosError::Internal { source: e.into() })?,
-        );
-
-        // Save block to persistent storage
-        self.executor()
-            .commit_blocks(vec![block_id], li)
-            .map_err(|e| AptosError::Internal { source: e.into() })?;
-
-        self.db()
-            .reader
-            .get_state_proof(from_version)
-            .map_err(|e| AptosError::Internal { source: e.into() })
+        ))
     }
 
     /// Generates a new state proof for a given version.
@@ -420,24 +441,41 @@ impl AptosWrapper {
     ///
     /// This method only transfers small amounts to ensure there is no gas issue. However, calling this method too frequently
     /// could result in transactions not passing due to insufficient funds.
+    ///
+    /// With fewer than two accounts, no transfer is possible and the block only contains its metadata.
     pub fn generate_traffic(&mut self) -> Result<(), AptosError> {
+        self.generate_traffic_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Same as `generate_traffic`, but picks the transfers from a seed, so that the block is
+    /// the same for a given chain and seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed to pick the senders and receivers of the transfers from.
+    pub fn generate_traffic_seeded(&mut self, seed: [u8; 32]) -> Result<(), AptosError> {
+        self.generate_traffic_with_rng(&mut rand::rngs::StdRng::from_seed(seed))
+    }
+
+    fn generate_traffic_with_rng<R: rand::Rng>(&mut self, rng: &mut R) -> Result<(), AptosError> {
         let (block_id, block_meta) = self.gen_block_id_and_metadata();
         let mut block_txs = vec![block_meta];
-        for _ in 0..10 {
+        let nbr_transfers = if self.accounts().len() < 2 { 0 } else { 10 };
+        for _ in 0..nbr_transfers {
             let sender = self
                 .accounts()
-                .choose(&mut rand::thread_rng())
+                .choose(rng)
                 .ok_or(AptosError::UnexpectedNone("random sender".to_string()))?;
             let mut receiver = self
                 .accounts()
-                .choose(&mut rand::thread_rng())
+                .choose(rng)
                 .ok_or(AptosError::UnexpectedNone("random receiver".to_string()))?;
 
             // Ensure receiver is different from sender
             while receiver.address() == sender.address() {
                 receiver = self
                     .accounts()
-                    .choose(&mut rand::thread_rng())
+                    .choose(rng)
                     .ok_or(AptosError::UnexpectedNone("random receiver".to_string()))?;
             }
 
@@ -485,6 +523,25 @@ impl AptosWrapper {
             ))
     }
 
+    /// Returns the latest `LedgerInfoWithSignatures` generated while executing a block, signed
+    /// again by a given number of validators rather than by the signers of the block. The
+    /// chain itself can not commit a block below quorum, so this is how ledger infos with too
+    /// few signers are produced.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_signers` - The number of validators signing the ledger info.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LedgerInfoWithSignatures>` - The latest `LedgerInfoWithSignatures`, or `Err` if there are less validators than signers.
+    pub fn get_latest_li_signed_by(
+        &self,
+        nbr_signers: usize,
+    ) -> Result<LedgerInfoWithSignatures, AptosError> {
+        self.sign_ledger_info(self.get_latest_li()?.ledger_info().clone(), nbr_signers)
+    }
+
     /// Returns the latest `LedgerInfoWithSignatures` generated while executing a block, serialized with bcs.
     ///
     /// # Returns
@@ -521,14 +578,28 @@ impl AptosWrapper {
         )
             .map_err(|e| AptosError::Internal { source: e.into() })?;
 
+        self.get_latest_proof(&account_0_resource_path)
+    }
+
+    /// Returns a `SparseMerkleProofAssets` for any state key, e.g. the one of a published
+    /// module.
+    ///
+    /// # Arguments
+    ///
+    /// * `state_key` - The state key for which to get the `SparseMerkleProofAssets`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SparseMerkleProofAssets>` - The `SparseMerkleProofAssets` for the state key.
+    pub fn get_latest_proof(
+        &self,
+        state_key: &StateKey,
+    ) -> Result<SparseMerkleProofAssets, AptosError> {
         // Get the state proof for the current version
         let (state_value, state_proof) = self
             .db()
             .reader
-            .get_state_value_with_proof_by_version(
-                &account_0_resource_path,
-                *self.current_version(),
-            )
+            .get_state_value_with_proof_by_version(state_key, *self.current_version())
             .map_err(|e| AptosError::Internal { source: e.into() })?;
 
         // Get the transaction with proof for the current version
@@ -545,7 +616,7 @@ impl AptosWrapper {
 
         Ok(SparseMerkleProofAssets {
             state_proof,
-            key: account_0_resource_path.hash(),
+            key: state_key.hash(),
             state_value,
             root_hash: txn_info
                 .state_checkpoint_hash()
@@ -557,6 +628,99 @@ impl AptosWrapper {
             transaction_version,
         })
     }
+
+    /// Returns the `TransactionInfo` at a specified version, along with the proof of its
+    /// inclusion in the transaction accumulator of the latest ledger info.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the transaction to get.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(TransactionInfo, AccumulatorProof<TransactionAccumulatorHasher>)>` - The `TransactionInfo`
+    ///   and its proof if the version exists.
+    pub fn get_transaction_with_proof(
+        &self,
+        version: u64,
+    ) -> Result<(TransactionInfo, AccumulatorProof<TransactionAccumulatorHasher>), AptosError> {
+        let txn_w_proof = self
+            .db()
+            .reader
+            .get_transaction_by_version(version, *self.current_version(), false)
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        Ok((
+            txn_w_proof.proof.transaction_info,
+            txn_w_proof.proof.ledger_info_to_transaction_info_proof,
+        ))
+    }
+
+    /// Returns the events emitted by the transaction at a specified version.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ContractEvent>>` - The events, in the order they were emitted.
+    pub fn get_events(&self, version: u64) -> Result<Vec<ContractEvent>, AptosError> {
+        let txn_w_proof = self
+            .db()
+            .reader
+            .get_transaction_by_version(version, *self.current_version(), true)
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        txn_w_proof
+            .events
+            .ok_or(AptosError::UnexpectedNone("events".to_string()))
+    }
+
+    /// Returns an event emitted by the transaction at a specified version, along with the
+    /// proof of its inclusion in the event accumulator of the transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the transaction.
+    /// * `index` - The index of the event among the events of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(ContractEvent, AccumulatorProof<EventAccumulatorHasher>)>` - The event and its
+    ///   proof if the transaction emitted it.
+    pub fn get_event_with_proof(
+        &self,
+        version: u64,
+        index: u64,
+    ) -> Result<(ContractEvent, AccumulatorProof<EventAccumulatorHasher>), AptosError> {
+        let events = self.get_events(version)?;
+        let event = events
+            .get(index as usize)
+            .cloned()
+            .ok_or(AptosError::UnexpectedNone(format!("event {index}")))?;
+
+        // The accumulator is built level by level, the missing right node of a level being
+        // a placeholder, as the event root hash of the transaction is
+        let mut level = events.iter().map(CryptoHash::hash).collect::<Vec<_>>();
+        let mut position = index as usize;
+        let mut siblings = vec![];
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                level.push(*ACCUMULATOR_PLACEHOLDER_HASH);
+            }
+            siblings.push(level[position ^ 1]);
+            level = level
+                .chunks_exact(2)
+                .map(|nodes| {
+                    MerkleTreeInternalNode::<EventAccumulatorHasher>::new(nodes[0], nodes[1]).hash()
+                })
+                .collect();
+            position /= 2;
+        }
+
+        Ok((event, AccumulatorProof::new(siblings)))
+    }
 }
 
 /// Generates a specified number of local accounts.
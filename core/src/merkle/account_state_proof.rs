@@ -0,0 +1,224 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Account State Proof Module
+//!
+//! This module provides the `AccountStateProof`, bundling the
+//! proofs needed to authenticate a leaf of the state tree against
+//! a ledger info, so that they can not be given out of step with
+//! each other.
+//!
+//! ## Encoding
+//!
+//! The proof is encoded as the bcs serialization, as of
+//! `aptos-node-v1.14.0`, of a `TransactionInfoWithProof` followed by
+//! the `SparseMerkleProof` of the leaf in the state checkpoint of its
+//! transaction. This is the layout of the `AccountStateProof` of
+//! Aptos' predecessors, whose fields are serialized in that order.
+
+use crate::crypto::hash::HASH_LENGTH;
+use crate::merkle::sparse_proof::SparseMerkleProof;
+use crate::merkle::transaction_proof::TransactionAccumulatorProof;
+use crate::serde_error;
+use crate::types::error::TypesError;
+use crate::types::transaction::{TransactionInfo, TRANSACTION_INFO_V0_SIZE};
+use crate::types::utils::{read_leb128, ENUM_VARIANT_LEN};
+use getset::Getters;
+
+/// Length in bytes of the serialized `TransactionInfo`, behind its variant.
+const TRANSACTION_INFO_SIZE: usize = ENUM_VARIANT_LEN + TRANSACTION_INFO_V0_SIZE;
+
+/// `ProofLayout` tells how the proofs are given to the inclusion
+/// program.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofLayout {
+    /// The transaction, its accumulator proof and the sparse Merkle
+    /// proof are given separately.
+    Split = 0,
+    /// The proofs are given as a single `AccountStateProof`.
+    Combined = 1,
+    /// Only transactions are proven, several of them under the same
+    /// ledger info, without any account.
+    Transactions = 2,
+    /// An event emitted by a transaction is proven against the
+    /// event root hash of the transaction, rather than a leaf
+    /// against its state checkpoint.
+    Event = 3,
+    /// Several leaves are proven against the state checkpoint of a
+    /// single transaction, and committed as a single root.
+    Batch = 4,
+}
+
+impl ProofLayout {
+    /// Converts the `ProofLayout` to the byte read by the program.
+    ///
+    /// # Returns
+    ///
+    /// The `u8` representing the `ProofLayout`.
+    pub const fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Creates a `ProofLayout` from the byte read by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte: u8` - The byte from which to create the `ProofLayout`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the byte represents a known
+    /// `ProofLayout`, and `Err` otherwise.
+    pub fn from_u8(byte: u8) -> Result<Self, TypesError> {
+        match byte {
+            0 => Ok(ProofLayout::Split),
+            1 => Ok(ProofLayout::Combined),
+            2 => Ok(ProofLayout::Transactions),
+            3 => Ok(ProofLayout::Event),
+            4 => Ok(ProofLayout::Batch),
+            _ => Err(serde_error!("ProofLayout", "Invalid variant")),
+        }
+    }
+}
+
+/// `AccountStateProof` bundles the proof of a transaction in the
+/// transaction accumulator with the proof of a leaf in the state
+/// tree at that transaction.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct AccountStateProof {
+    /// Proof of the transaction in the accumulator of the ledger info.
+    transaction_proof: TransactionAccumulatorProof,
+    /// The transaction, whose state checkpoint roots the sparse Merkle proof.
+    transaction: TransactionInfo,
+    /// Proof of the leaf in the state tree.
+    sparse_merkle_proof: SparseMerkleProof,
+}
+
+impl AccountStateProof {
+    /// Splits the `AccountStateProof` into its proofs.
+    ///
+    /// # Returns
+    ///
+    /// The `TransactionInfo`, its `TransactionAccumulatorProof` and the
+    /// `SparseMerkleProof` of the leaf.
+    pub fn into_parts(
+        self,
+    ) -> (
+        TransactionInfo,
+        TransactionAccumulatorProof,
+        SparseMerkleProof,
+    ) {
+        (
+            self.transaction,
+            self.transaction_proof,
+            self.sparse_merkle_proof,
+        )
+    }
+
+    /// Converts the `AccountStateProof` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `AccountStateProof`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.transaction_proof.to_bytes();
+        bytes.extend_from_slice(&self.transaction.to_bytes());
+        bytes.extend_from_slice(&self.sparse_merkle_proof.to_bytes());
+        bytes
+    }
+
+    /// Creates an `AccountStateProof` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the
+    ///   `AccountStateProof`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `AccountStateProof` could be
+    /// successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        // The accumulator proof is its number of siblings followed by the siblings
+        let (num_siblings, len) =
+            read_leb128(bytes).map_err(|e| serde_error!("AccountStateProof", e))?;
+        let transaction_proof_len = usize::try_from(num_siblings)
+            .ok()
+            .and_then(|num_siblings| num_siblings.checked_mul(HASH_LENGTH))
+            .and_then(|siblings_len| siblings_len.checked_add(len))
+            .ok_or_else(|| serde_error!("AccountStateProof", "Too many siblings"))?;
+        let transaction_end = transaction_proof_len + TRANSACTION_INFO_SIZE;
+        if bytes.len() < transaction_end {
+            return Err(serde_error!(
+                "AccountStateProof",
+                "Not enough data for TransactionInfoWithProof"
+            ));
+        }
+
+        Ok(Self {
+            transaction_proof: TransactionAccumulatorProof::from_bytes(
+                &bytes[..transaction_proof_len],
+            )?,
+            transaction: TransactionInfo::from_bytes(
+                &bytes[transaction_proof_len..transaction_end],
+            )?,
+            sparse_merkle_proof: SparseMerkleProof::from_bytes(&bytes[transaction_end..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_proof_layout_round_trip() {
+        for layout in [
+            ProofLayout::Split,
+            ProofLayout::Combined,
+            ProofLayout::Transactions,
+            ProofLayout::Event,
+            ProofLayout::Batch,
+        ] {
+            assert_eq!(ProofLayout::from_u8(layout.to_u8()).unwrap(), layout);
+        }
+        assert!(ProofLayout::from_u8(5).is_err());
+    }
+
+    #[test]
+    fn test_account_state_proof_truncated() {
+        // A single sibling is announced, but there is no transaction
+        let mut bytes = vec![1];
+        bytes.extend([0; HASH_LENGTH]);
+
+        assert!(AccountStateProof::from_bytes(&bytes).is_err());
+        assert!(AccountStateProof::from_bytes(&[]).is_err());
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_bytes_conversion_account_state_proof() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use aptos_types::proof::TransactionInfoWithProof;
+
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+        let proof_assets = aptos_wrapper.get_latest_proof_account(2).unwrap();
+
+        let transaction_info_with_proof = TransactionInfoWithProof::new(
+            proof_assets.transaction_proof().clone(),
+            proof_assets.transaction().clone(),
+        );
+        let mut aptos_bytes = bcs::to_bytes(&transaction_info_with_proof).unwrap();
+        aptos_bytes.extend(bcs::to_bytes(proof_assets.state_proof()).unwrap());
+
+        let account_state_proof = AccountStateProof::from_bytes(&aptos_bytes).unwrap();
+        assert_eq!(account_state_proof.to_bytes(), aptos_bytes);
+        assert_eq!(
+            account_state_proof.sparse_merkle_proof().to_bytes(),
+            bcs::to_bytes(proof_assets.state_proof()).unwrap()
+        );
+    }
+}
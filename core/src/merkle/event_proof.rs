@@ -0,0 +1,164 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Event Proof Module
+//!
+//! This module provides the structures and functions
+//! necessary for handling Event Accumulator Proofs
+//! from the Aptos chain.
+//!
+//! ## Usage
+//!
+//! The `EventAccumulatorProof` structure is used to
+//! authenticate whether a given event was emitted by a
+//! transaction. Each transaction accumulates the events it
+//! emitted in a Merkle accumulator, whose root hash is the
+//! event root hash of its `TransactionInfo`. Unlike a leaf of
+//! the state tree, proven against a state checkpoint, an event
+//! is thus proven against the transaction that emitted it.
+use crate::crypto::hash::{hash_data, prefixed_sha3, HashValue};
+use crate::merkle::node::EventAccumulatorHasher;
+use crate::merkle::transaction_proof::{
+    accumulator_siblings_from_bytes, accumulator_siblings_to_bytes, verify_accumulator_proof,
+};
+use crate::types::error::TypesError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A proof that can be used to authenticate an event in
+/// the event accumulator of a transaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventAccumulatorProof {
+    /// All siblings in this proof, including the default ones. Siblings
+    /// are ordered from the bottom level to the root level.
+    siblings: Vec<HashValue>,
+}
+
+impl EventAccumulatorProof {
+    /// Verifies an event whose hash is `event_hash` and index is `event_index` was emitted
+    /// by the transaction whose event root hash is `expected_root_hash`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_root_hash: HashValue` - The event root hash of the transaction.
+    /// * `event_hash: HashValue` - The hash of the event to verify, as returned by `contract_event_hash`.
+    /// * `event_index: u64` - The index of the event among the events of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the event is in the Event Accumulator and the proof is valid, and `Err` otherwise.
+    pub fn verify(
+        &self,
+        expected_root_hash: HashValue,
+        event_hash: HashValue,
+        event_index: u64,
+    ) -> Result<()> {
+        verify_accumulator_proof::<EventAccumulatorHasher>(
+            &self.siblings,
+            expected_root_hash,
+            event_hash,
+            event_index,
+        )
+    }
+
+    /// Converts the `EventAccumulatorProof` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `EventAccumulatorProof`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        accumulator_siblings_to_bytes(&self.siblings)
+    }
+
+    /// Creates an `EventAccumulatorProof` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `EventAccumulatorProof`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `EventAccumulatorProof` could be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypesError> {
+        Ok(Self {
+            siblings: accumulator_siblings_from_bytes("EventAccumulatorProof", bytes)?,
+        })
+    }
+}
+
+/// Computes the hash of a contract event, as the leaves of the event accumulator hash it.
+///
+/// The event is not deserialized: its hash only depends on its bytes, so any event type
+/// can be proven.
+///
+/// # Arguments
+///
+/// * `event_bytes: &[u8]` - The `ContractEvent`, serialized with bcs.
+///
+/// # Returns
+///
+/// The `HashValue` of the event.
+pub fn contract_event_hash(event_bytes: &[u8]) -> HashValue {
+    HashValue::new(hash_data(
+        &prefixed_sha3(b"ContractEvent"),
+        vec![event_bytes],
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::hash::CryptoHash;
+    use crate::merkle::node::MerkleInternalNode;
+
+    #[test]
+    fn test_verify_event_accumulator_proof() {
+        let first_event = contract_event_hash(&[1; 8]);
+        let second_event = contract_event_hash(&[2; 8]);
+        let root_hash =
+            MerkleInternalNode::<EventAccumulatorHasher>::new(first_event, second_event).hash();
+
+        let proof = EventAccumulatorProof {
+            siblings: vec![first_event],
+        };
+        let proof = EventAccumulatorProof::from_bytes(&proof.to_bytes()).unwrap();
+        proof.verify(root_hash, second_event, 1).unwrap();
+        // The sibling is on the other side of the first event
+        assert!(proof.verify(root_hash, second_event, 0).is_err());
+        assert!(proof.verify(root_hash, first_event, 1).is_err());
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_event_accumulator() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use aptos_crypto::hash::CryptoHash as AptosCryptoHash;
+
+        let mut aptos_wrapper = AptosWrapper::new(40, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let version = (0..=*aptos_wrapper.current_version())
+            .rev()
+            .find(|version| !aptos_wrapper.get_events(*version).unwrap().is_empty())
+            .unwrap();
+        let (transaction, _) = aptos_wrapper.get_transaction_with_proof(version).unwrap();
+        let event_index = aptos_wrapper.get_events(version).unwrap().len() as u64 - 1;
+        let (event, event_proof) = aptos_wrapper
+            .get_event_with_proof(version, event_index)
+            .unwrap();
+
+        let event_bytes = bcs::to_bytes(&event).unwrap();
+        let event_hash = contract_event_hash(&event_bytes);
+        assert_eq!(event_hash.as_ref(), AptosCryptoHash::hash(&event).as_ref());
+
+        let aptos_proof_bytes = bcs::to_bytes(&event_proof).unwrap();
+        let proof = EventAccumulatorProof::from_bytes(&aptos_proof_bytes).unwrap();
+        assert_eq!(proof.to_bytes(), aptos_proof_bytes);
+
+        let expected_root_hash =
+            HashValue::from_slice(transaction.event_root_hash().as_ref()).unwrap();
+        proof
+            .verify(expected_root_hash, event_hash, event_index)
+            .unwrap()
+    }
+}
@@ -7,11 +7,15 @@
 //!
 //! ## Sub-modules
 //!
+//! - `account_state_proof`: This sub-module contains the `AccountStateProof` structure and associated methods. It bundles the proofs of a transaction and of a leaf of the state tree at that transaction.
+//! - `event_proof`: This sub-module contains the `EventAccumulatorProof` structure and associated methods. It is used to represent and verify proofs of the events emitted by a transaction.
 //! - `node`: This sub-module contains the `SparseMerkleNode` structure and associated methods. It is used to represent nodes in the Sparse Merkle Tree and the Transaction Accumulator.
 //! - `sparse_proof`: This sub-module contains the `SparseMerkleProof` structure and associated methods. It is used to represent and verify proofs in the Sparse Merkle Tree.
 //! - `transaction_proof`: This sub-module contains the `TransactionAccumulatorProof` structure and associated methods. It is used to represent and verify proofs in the Transaction Accumulator.
 //!
 //! For more detailed information, users should refer to the specific documentation for each sub-module.
+pub mod account_state_proof;
+pub mod event_proof;
 pub mod node;
 pub mod sparse_proof;
 pub mod transaction_proof;
\ No newline at end of file
@@ -42,9 +42,9 @@
 //! The `NodeHasher` trait provides a method for hashing (`hash`),
 //! which takes in the left and right child nodes and returns a `HashValue`.
 //!
-//! The `SparseMerkleInternalHasher` and `TransactionAccumulatorHasher`
-//! structures implement the `NodeHasher` trait, each providing
-//! a different prefix for hashing.
+//! The `SparseMerkleInternalHasher`, `TransactionAccumulatorHasher`
+//! and `EventAccumulatorHasher` structures implement the `NodeHasher`
+//! trait, each providing a different prefix for hashing.
 //!
 use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue, HASH_LENGTH};
 use crate::serde_error;
@@ -257,6 +257,24 @@ impl NodeHasher for TransactionAccumulatorHasher {
     }
 }
 
+/// `EventAccumulatorHasher` is a structure representing
+/// the hasher for the event accumulator of a transaction
+/// in order to prove an event emitted by it.
+#[derive(Clone, Debug, Default)]
+pub struct EventAccumulatorHasher {}
+
+impl NodeHasher for EventAccumulatorHasher {
+    /// Returns the prefix used for hashing in the context of
+    /// an Event Accumulator.
+    ///
+    /// # Returns
+    ///
+    /// A static string slice representing the prefix.
+    fn prefix(&self) -> &'static str {
+        "EventAccumulator"
+    }
+}
+
 #[cfg(all(test, feature = "aptos"))]
 mod test {
     #[test]
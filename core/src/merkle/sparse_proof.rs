@@ -34,6 +34,11 @@ use bytes::{Buf, BufMut, BytesMut};
 use getset::Getters;
 use serde::{Deserialize, Serialize};
 
+/// Hash of an empty subtree of the Sparse Merkle Tree, as
+/// found in the siblings of a proof.
+pub const SPARSE_MERKLE_PLACEHOLDER_HASH: HashValue =
+    HashValue::new(*b"SPARSE_MERKLE_PLACEHOLDER_HASH\0\0");
+
 /// `SparseMerkleProof` is a structure representing a proof
 /// in a Sparse Merkle Tree.
 ///
@@ -58,6 +63,38 @@ pub struct SparseMerkleProof {
 }
 
 impl SparseMerkleProof {
+    /// Creates a new `SparseMerkleProof` with the given
+    /// `leaf` and `siblings`.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaf: Option<SparseMerkleLeafNode>` - The leaf of the proof.
+    /// * `siblings: Vec<HashValue>` - The siblings of the proof, ordered
+    ///   from the bottom level to the root level.
+    ///
+    /// # Returns
+    ///
+    /// A new `SparseMerkleProof` instance.
+    pub const fn new(leaf: Option<SparseMerkleLeafNode>, siblings: Vec<HashValue>) -> Self {
+        Self { leaf, siblings }
+    }
+
+    /// Checks that the proof is not deeper than the number
+    /// of bits of a key, as each sibling consumes one bit of
+    /// the key of the element to verify.
+    ///
+    /// Empty subtrees are part of the siblings as
+    /// `SPARSE_MERKLE_PLACEHOLDER_HASH`, so the depth does not
+    /// depend on the occupancy of the neighborhood of the leaf.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the depth of the proof is consistent
+    /// with the length of a key.
+    pub fn has_consistent_depth(&self) -> bool {
+        self.siblings.len() <= HASH_LENGTH * 8
+    }
+
     /// Verifies an element whose key is `element_key` and
     /// value is authenticated by `element_hash` exists in
     /// the Sparse Merkle Tree using the provided proof.
@@ -81,7 +118,7 @@ impl SparseMerkleProof {
         element_hash: HashValue,
     ) -> Result<HashValue> {
         ensure!(
-            self.siblings.len() <= HASH_LENGTH * 8,
+            self.has_consistent_depth(),
             "Sparse Merkle Tree proof has more than {} ({}) siblings.",
             256,
             self.siblings.len(),
@@ -127,6 +164,48 @@ impl SparseMerkleProof {
         Ok(reconstructed_root)
     }
 
+    /// Reconstructs the hashes of the path from the leaf of an element up to the root
+    /// of the Sparse Merkle Tree, without checking them against an expected root. The
+    /// leaf is built from `element_key` and `element_hash`, so the path of any element
+    /// can be traced, even one the proof does not authenticate.
+    ///
+    /// # Arguments
+    ///
+    /// * `element_key: HashValue` - The key of the element to trace.
+    /// * `element_hash: HashValue` - The hash of the element to trace.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the hash of the leaf followed by the hash of each level,
+    /// from the bottom level to the root, or `Err` if the proof has more siblings than
+    /// the bits of a key.
+    pub fn trace_by_hash(
+        &self,
+        element_key: HashValue,
+        element_hash: HashValue,
+    ) -> Result<Vec<HashValue>> {
+        ensure!(
+            self.has_consistent_depth(),
+            "Sparse Merkle Tree proof has more than {} ({}) siblings.",
+            256,
+            self.siblings.len(),
+        );
+
+        let leaf_hash = SparseMerkleLeafNode::new(element_key, element_hash).hash();
+        let mut trace = Vec::with_capacity(self.siblings.len() + 1);
+        trace.push(leaf_hash);
+        for level in self.siblings.iter().rev().zip(
+            element_key
+                .iter_bits()
+                .rev()
+                .skip(HASH_LENGTH * 8 - self.siblings.len()),
+        ) {
+            trace.push(accumulator_update(trace[trace.len() - 1], level));
+        }
+
+        Ok(trace)
+    }
+
     /// Converts the `SparseMerkleProof` to a byte vector.
     ///
     /// # Returns
@@ -164,6 +243,12 @@ impl SparseMerkleProof {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
         let mut buf = bytes;
 
+        if !buf.has_remaining() {
+            return Err(serde_error!(
+                "SparseMerkleProof",
+                "Not enough data for leaf"
+            ));
+        }
         let leaf = match buf.get_u8() {
             1 => {
                 let node = SparseMerkleLeafNode::from_bytes(
@@ -231,7 +316,10 @@ mod test {
     use crate::merkle::node::{
         MerkleInternalNode, SparseMerkleInternalHasher, SparseMerkleLeafNode,
     };
-    use crate::merkle::sparse_proof::SparseMerkleProof;
+    use crate::merkle::sparse_proof::{SparseMerkleProof, SPARSE_MERKLE_PLACEHOLDER_HASH};
+    use proptest::collection::vec;
+    use proptest::prelude::{any, ProptestConfig};
+    use proptest::proptest;
 
     #[test]
     fn test_verify_proof_simple() {
@@ -283,6 +371,167 @@ mod test {
             .unwrap();
     }
 
+    /// Computes the root of a tree from a leaf and its siblings, ordered from
+    /// the bottom level to the root level.
+    fn root_hash(leaf_node: &SparseMerkleLeafNode, siblings: &[HashValue]) -> HashValue {
+        siblings
+            .iter()
+            .rev()
+            .zip(
+                leaf_node
+                    .key()
+                    .iter_bits()
+                    .rev()
+                    .skip(HASH_LENGTH * 8 - siblings.len()),
+            )
+            .fold(leaf_node.hash(), |acc_hash, (sibling_hash, bit)| {
+                if bit {
+                    MerkleInternalNode::<SparseMerkleInternalHasher>::new(*sibling_hash, acc_hash)
+                        .hash()
+                } else {
+                    MerkleInternalNode::<SparseMerkleInternalHasher>::new(acc_hash, *sibling_hash)
+                        .hash()
+                }
+            })
+    }
+
+    fn key_with_first_byte(first_byte: u8) -> HashValue {
+        let mut key = [0; HASH_LENGTH];
+        key[0] = first_byte;
+        HashValue::new(key)
+    }
+
+    #[test]
+    fn test_verify_proof_with_leaf_sibling() {
+        // The leaf shares its subtree with a leaf of a different key, 0b01...
+        let leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b0000_0000),
+            HashValue::new(hash_data(&[], vec!["a".as_bytes()])),
+        );
+        let sibling_leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b0100_0000),
+            HashValue::new(hash_data(&[], vec!["b".as_bytes()])),
+        );
+        let siblings = vec![
+            sibling_leaf_node.hash(),
+            HashValue::new(hash_data(&[], vec!["cd".as_bytes()])),
+        ];
+        let proof = SparseMerkleProof::new(Some(leaf_node), siblings.clone());
+
+        proof
+            .verify_by_hash(
+                root_hash(&leaf_node, &siblings),
+                leaf_node.key(),
+                leaf_node.value_hash(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_trace_proof_with_leaf_sibling() {
+        let leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b0000_0000),
+            HashValue::new(hash_data(&[], vec!["a".as_bytes()])),
+        );
+        let sibling_leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b0100_0000),
+            HashValue::new(hash_data(&[], vec!["b".as_bytes()])),
+        );
+        let siblings = vec![
+            sibling_leaf_node.hash(),
+            HashValue::new(hash_data(&[], vec!["cd".as_bytes()])),
+        ];
+        let proof = SparseMerkleProof::new(Some(leaf_node), siblings.clone());
+
+        let trace = proof
+            .trace_by_hash(leaf_node.key(), leaf_node.value_hash())
+            .unwrap();
+        assert_eq!(
+            trace,
+            vec![
+                leaf_node.hash(),
+                root_hash(&leaf_node, &siblings[1..]),
+                root_hash(&leaf_node, &siblings),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_proof_with_empty_sibling() {
+        // The leaf is alone in the left half of the tree, 0b0...
+        let leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b0000_0000),
+            HashValue::new(hash_data(&[], vec!["a".as_bytes()])),
+        );
+        let siblings = vec![
+            SPARSE_MERKLE_PLACEHOLDER_HASH,
+            HashValue::new(hash_data(&[], vec!["right".as_bytes()])),
+        ];
+        let proof = SparseMerkleProof::new(Some(leaf_node), siblings.clone());
+
+        proof
+            .verify_by_hash(
+                root_hash(&leaf_node, &siblings),
+                leaf_node.key(),
+                leaf_node.value_hash(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_proof_deeper_than_key() {
+        let leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b0000_0000),
+            HashValue::new(hash_data(&[], vec!["a".as_bytes()])),
+        );
+        let proof = SparseMerkleProof::new(
+            Some(leaf_node),
+            vec![SPARSE_MERKLE_PLACEHOLDER_HASH; HASH_LENGTH * 8 + 1],
+        );
+
+        let root_hash = HashValue::new([0; HASH_LENGTH]);
+        assert!(!proof.has_consistent_depth());
+        assert!(proof
+            .verify_by_hash(root_hash, leaf_node.key(), leaf_node.value_hash())
+            .is_err());
+        assert!(SparseMerkleProof::new(Some(leaf_node), vec![]).has_consistent_depth());
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_sparse_merkle_placeholder_hash() {
+        assert_eq!(
+            SPARSE_MERKLE_PLACEHOLDER_HASH.to_vec(),
+            aptos_crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH.to_vec()
+        );
+    }
+
+    // The number of cases can be raised with `PROPTEST_CASES`, as the fuzz workflow does
+    proptest! {
+        #![proptest_config(ProptestConfig::default())]
+        #[test]
+        fn test_fuzz_sparse_merkle_proof_from_bytes(bytes in vec(any::<u8>(), 0..512)) {
+            let _ = SparseMerkleProof::from_bytes(&bytes);
+        }
+
+        #[test]
+        fn test_fuzz_truncated_sparse_merkle_proof(
+            leaf in any::<[u8; 64]>(),
+            siblings in vec(any::<[u8; 32]>(), 0..8),
+            cut in any::<usize>(),
+        ) {
+            let leaf = SparseMerkleLeafNode::new(
+                HashValue::new(leaf[..32].try_into().unwrap()),
+                HashValue::new(leaf[32..].try_into().unwrap()),
+            );
+            let siblings = siblings.into_iter().map(HashValue::new).collect();
+            let bytes = SparseMerkleProof::new(Some(leaf), siblings).to_bytes();
+
+            let cut = cut % bytes.len();
+            assert!(SparseMerkleProof::from_bytes(&bytes[..cut]).is_err());
+        }
+    }
+
     #[cfg(feature = "aptos")]
     #[test]
     fn test_aptos_data() {
@@ -17,7 +17,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::crypto::hash::{CryptoHash, HashValue, HASH_LENGTH};
 use crate::merkle::node::MerkleInternalNode;
-use crate::merkle::node::TransactionAccumulatorHasher;
+use crate::merkle::node::{NodeHasher, TransactionAccumulatorHasher};
 use crate::serde_error;
 use crate::types::error::TypesError;
 use crate::types::utils::{read_leb128, write_leb128};
@@ -55,49 +55,12 @@ impl TransactionAccumulatorProof {
         element_hash: HashValue,
         element_index: u64,
     ) -> Result<()> {
-        ensure!(
-            self.siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
-            "Accumulator proof has more than {} ({}) siblings.",
-            MAX_ACCUMULATOR_PROOF_DEPTH,
-            self.siblings.len()
-        );
-
-        let actual_root_hash = self
-            .siblings
-            .iter()
-            .fold(
-                (element_hash, element_index),
-                // `index` denotes the index of the ancestor of the element at the current level.
-                |(hash, index), sibling_hash| {
-                    (
-                        if index % 2 == 0 {
-                            // the current node is a left child.
-                            MerkleInternalNode::<TransactionAccumulatorHasher>::new(
-                                hash,
-                                *sibling_hash,
-                            )
-                                .hash()
-                        } else {
-                            // the current node is a right child.
-                            MerkleInternalNode::<TransactionAccumulatorHasher>::new(
-                                *sibling_hash,
-                                hash,
-                            )
-                                .hash()
-                        },
-                        // The index of the parent at its level.
-                        index / 2,
-                    )
-                },
-            )
-            .0;
-        ensure!(
-            actual_root_hash == expected_root_hash,
-            "{}: Root hashes do not match.",
-            "AccumulatorProof",
-        );
-
-        Ok(())
+        verify_accumulator_proof::<TransactionAccumulatorHasher>(
+            &self.siblings,
+            expected_root_hash,
+            element_hash,
+            element_index,
+        )
     }
 
     /// Converts the `TransactionAccumulatorProof` to a byte vector.
@@ -106,12 +69,7 @@ impl TransactionAccumulatorProof {
     ///
     /// A `Vec<u8>` representing the `TransactionAccumulatorProof`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&write_leb128(self.siblings.len() as u64));
-        for sibling in &self.siblings {
-            bytes.put_slice(sibling.as_ref());
-        }
-        bytes.to_vec()
+        accumulator_siblings_to_bytes(&self.siblings)
     }
 
     /// Creates a `TransactionAccumulatorProof` from a byte slice.
@@ -124,36 +82,108 @@ impl TransactionAccumulatorProof {
     ///
     /// A `Result` which is `Ok` if the `TransactionAccumulatorProof` could be successfully created, and `Err` otherwise.
     pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypesError> {
-        let mut buf = BytesMut::from(bytes);
-        let (len, read_bytes) = read_leb128(&buf).map_err(|_| {
-            serde_error!("TransactionAccumulatorProof", "Not enough data for length")
-        })?;
-        buf.advance(read_bytes);
-        let mut siblings = Vec::with_capacity(len as usize);
-        for _ in 0..len {
-            if buf.remaining() < HASH_LENGTH {
-                return Err(serde_error!(
-                    "TransactionAccumulatorProof",
-                    "Not enough bytes to read HashValue"
-                ));
-            }
-            let mut hash_value = [0u8; HASH_LENGTH];
-            buf.copy_to_slice(&mut hash_value);
-            siblings.push(
-                HashValue::from_slice(hash_value)
-                    .map_err(|e| serde_error!("TransactionAccumulatorProof", e))?,
-            );
-        }
+        Ok(Self {
+            siblings: accumulator_siblings_from_bytes("TransactionAccumulatorProof", bytes)?,
+        })
+    }
+}
 
-        if buf.remaining() != 0 {
+/// Verifies that an element is in a Merkle accumulator, whose internal nodes are hashed
+/// with `H`, by folding its siblings from the bottom level to the root level.
+///
+/// # Arguments
+///
+/// * `siblings: &[HashValue]` - The siblings of the element, including the default ones.
+/// * `expected_root_hash: HashValue` - The expected root hash of the accumulator.
+/// * `element_hash: HashValue` - The hash of the element to verify.
+/// * `element_index: u64` - The index of the element to verify.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the siblings lead from the element to the expected root hash, and `Err` otherwise.
+pub(crate) fn verify_accumulator_proof<H: NodeHasher + Default>(
+    siblings: &[HashValue],
+    expected_root_hash: HashValue,
+    element_hash: HashValue,
+    element_index: u64,
+) -> Result<()> {
+    ensure!(
+        siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
+        "Accumulator proof has more than {} ({}) siblings.",
+        MAX_ACCUMULATOR_PROOF_DEPTH,
+        siblings.len()
+    );
+
+    let actual_root_hash = siblings
+        .iter()
+        .fold(
+            (element_hash, element_index),
+            // `index` denotes the index of the ancestor of the element at the current level.
+            |(hash, index), sibling_hash| {
+                (
+                    if index % 2 == 0 {
+                        // the current node is a left child.
+                        MerkleInternalNode::<H>::new(hash, *sibling_hash).hash()
+                    } else {
+                        // the current node is a right child.
+                        MerkleInternalNode::<H>::new(*sibling_hash, hash).hash()
+                    },
+                    // The index of the parent at its level.
+                    index / 2,
+                )
+            },
+        )
+        .0;
+    ensure!(
+        actual_root_hash == expected_root_hash,
+        "{}: Root hashes do not match.",
+        "AccumulatorProof",
+    );
+
+    Ok(())
+}
+
+/// Serializes the siblings of an accumulator proof as bcs does, prefixed by their number.
+pub(crate) fn accumulator_siblings_to_bytes(siblings: &[HashValue]) -> Vec<u8> {
+    let mut bytes = BytesMut::new();
+    bytes.put_slice(&write_leb128(siblings.len() as u64));
+    for sibling in siblings {
+        bytes.put_slice(sibling.as_ref());
+    }
+    bytes.to_vec()
+}
+
+/// Deserializes the siblings of an accumulator proof written by
+/// `accumulator_siblings_to_bytes`, naming `structure` in the errors.
+pub(crate) fn accumulator_siblings_from_bytes(
+    structure: &str,
+    bytes: &[u8],
+) -> std::result::Result<Vec<HashValue>, TypesError> {
+    let mut buf = BytesMut::from(bytes);
+    let (len, read_bytes) =
+        read_leb128(&buf).map_err(|_| serde_error!(structure, "Not enough data for length"))?;
+    buf.advance(read_bytes);
+    let mut siblings = Vec::with_capacity(len as usize);
+    for _ in 0..len {
+        if buf.remaining() < HASH_LENGTH {
             return Err(serde_error!(
-                "TransactionAccumulatorProof",
-                "Unexpected data after completing deserialization"
+                structure,
+                "Not enough bytes to read HashValue"
             ));
         }
+        let mut hash_value = [0u8; HASH_LENGTH];
+        buf.copy_to_slice(&mut hash_value);
+        siblings.push(HashValue::from_slice(hash_value).map_err(|e| serde_error!(structure, e))?);
+    }
 
-        Ok(Self { siblings })
+    if buf.remaining() != 0 {
+        return Err(serde_error!(
+            structure,
+            "Unexpected data after completing deserialization"
+        ));
     }
+
+    Ok(siblings)
 }
 
 #[cfg(all(test, feature = "aptos"))]
@@ -133,7 +133,10 @@ impl BlockInfo {
     /// A `Result` which is `Ok` if the `BlockInfo` could
     /// be successfully created, and `Err` otherwise.
     pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let epoch_state_size = bytes.len() - 4 * U64_SIZE - 2 * HASH_LENGTH - 1;
+        let epoch_state_size = bytes
+            .len()
+            .checked_sub(4 * U64_SIZE + 2 * HASH_LENGTH + 1)
+            .ok_or_else(|| serde_error!("BlockInfo", "Not enough data for BlockInfo"))?;
 
         let epoch = bytes.get_u64_le();
         let round = bytes.get_u64_le();
@@ -102,7 +102,10 @@ impl EpochState {
     /// A `Result` which is `Ok` if the `EpochState` could
     /// be successfully created, and `Err` otherwise.
     pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let validator_verifier_size = bytes.len() - U64_SIZE;
+        let validator_verifier_size = bytes
+            .len()
+            .checked_sub(U64_SIZE)
+            .ok_or_else(|| serde_error!("EpochState", "Not enough data for epoch"))?;
 
         let epoch = bytes.get_u64_le();
 
@@ -0,0 +1,375 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Leaf Module
+//!
+//! This module provides the kinds of leaves of the state tree the
+//! inclusion program can prove, along with the hashing scheme of
+//! their values.
+//!
+//! Aptos stores resources and published modules under distinct
+//! state keys. A resource leaf is proven from the hash of its state
+//! value, while a module leaf is proven from its bytecode, which the
+//! program hashes itself. A module published once storage slots were
+//! charged is stored along with their metadata, which is then hashed
+//! along with the bytecode.
+//!
+//! ## State keys
+//!
+//! The key of a leaf is the hash of its state key. For a resource or
+//! a module, it is computed by `access_path_state_key` from the
+//! address and the path of the access path, so that the program can
+//! check a preimage given along with the key. The first byte of the
+//! path tells a module from a resource, so that the program requires
+//! the preimage of the key of a module leaf and checks its kind
+//! against it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::hash::{hash_data, prefixed_sha3, HashValue};
+use crate::serde_error;
+use crate::types::error::TypesError;
+use crate::types::utils::{read_leb128, write_leb128};
+use crate::types::ACCOUNT_ADDRESS_SIZE;
+
+/// Tag prefixed by Aptos to the encoding of the state keys of access
+/// paths.
+const ACCESS_PATH_STATE_KEY_TAG: u8 = 0;
+
+/// Tag of the variant of the state values stored without metadata.
+const LEGACY_STATE_VALUE_TAG: u8 = 0;
+
+/// Tag of the variant of the state values stored along with the
+/// metadata of their storage slot.
+const STATE_VALUE_WITH_METADATA_TAG: u8 = 1;
+
+/// Tag of the `Code` variant of the `Path` of an access path, under
+/// which modules are published.
+const CODE_PATH_TAG: u8 = 0;
+
+/// Tags of the `Resource` and `ResourceGroup` variants of the `Path`
+/// of an access path.
+const RESOURCE_PATH_TAGS: [u8; 2] = [1, 2];
+
+/// `LeafKind` selects the semantics of the leaf proven by the
+/// inclusion program.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeafKind {
+    /// The leaf holds a resource, given by the hash of its state value.
+    Resource = 0,
+    /// The leaf holds the bytecode of a published module.
+    Module = 1,
+}
+
+impl LeafKind {
+    /// Converts the `LeafKind` to the byte read and committed by the program.
+    ///
+    /// # Returns
+    ///
+    /// The `u8` representing the `LeafKind`.
+    pub const fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Creates a `LeafKind` from the byte read or committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte: u8` - The byte from which to create the `LeafKind`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the byte represents a known
+    /// `LeafKind`, and `Err` otherwise.
+    pub fn from_u8(byte: u8) -> Result<Self, TypesError> {
+        match byte {
+            0 => Ok(LeafKind::Resource),
+            1 => Ok(LeafKind::Module),
+            _ => Err(serde_error!("LeafKind", "Invalid variant")),
+        }
+    }
+}
+
+/// Computes the hash of a serialized state value, as Aptos does for
+/// the leaves of its state tree.
+///
+/// # Arguments
+///
+/// * `state_value: &[u8]` - The serialized state value.
+///
+/// # Returns
+///
+/// The `HashValue` of the state value.
+pub fn state_value_hash(state_value: &[u8]) -> HashValue {
+    HashValue::new(hash_data(&prefixed_sha3(b"StateValue"), vec![state_value]))
+}
+
+/// Computes the hash of the state value holding the bytecode of a
+/// module, as Aptos does for the leaves of its state tree.
+///
+/// The module is taken to be stored as a state value without
+/// metadata, so the hash covers the bcs serialization of the bytecode
+/// behind the variant of such values. Use
+/// `module_value_hash_with_metadata` for a module stored along with
+/// the metadata of its storage slot.
+///
+/// # Arguments
+///
+/// * `bytecode: &[u8]` - The bytecode of the module.
+///
+/// # Returns
+///
+/// The `HashValue` of the state value of the module.
+pub fn module_value_hash(bytecode: &[u8]) -> HashValue {
+    module_value_hash_with_metadata(bytecode, None)
+}
+
+/// Same as `module_value_hash`, but for a module stored along with
+/// the metadata of its storage slot, if any.
+///
+/// # Arguments
+///
+/// * `bytecode: &[u8]` - The bytecode of the module.
+/// * `metadata: Option<&[u8]>` - The metadata of the state value,
+///   serialized with bcs, as returned by `split_module_state_value`.
+///
+/// # Returns
+///
+/// The `HashValue` of the state value of the module.
+pub fn module_value_hash_with_metadata(bytecode: &[u8], metadata: Option<&[u8]>) -> HashValue {
+    let tag = match metadata {
+        None => LEGACY_STATE_VALUE_TAG,
+        Some(_) => STATE_VALUE_WITH_METADATA_TAG,
+    };
+    let mut state_value = vec![tag];
+    state_value.extend_from_slice(&write_leb128(bytecode.len() as u64));
+    state_value.extend_from_slice(bytecode);
+    // The metadata follows the bytes of the value, whatever its own variant
+    state_value.extend_from_slice(metadata.unwrap_or_default());
+
+    state_value_hash(&state_value)
+}
+
+/// Splits a serialized state value holding a module into its bytecode
+/// and the metadata of its storage slot, if any.
+///
+/// # Arguments
+///
+/// * `state_value: &[u8]` - The `StateValue`, serialized with bcs.
+///
+/// # Returns
+///
+/// A `Result` containing the bytecode and the serialized metadata, or
+/// `Err` if the bytes are not a state value of a known variant.
+pub fn split_module_state_value(state_value: &[u8]) -> Result<(&[u8], Option<&[u8]>), TypesError> {
+    let (&tag, bytes) = state_value
+        .split_first()
+        .ok_or_else(|| serde_error!("StateValue", "Not enough data for the variant"))?;
+    let (len, bytes_read) = read_leb128(bytes)
+        .map_err(|e| serde_error!("StateValue", format!("Failed to read length: {e}")))?;
+    let bytes = &bytes[bytes_read..];
+    let bytecode = bytes
+        .get(..len as usize)
+        .ok_or_else(|| serde_error!("StateValue", "Not enough data for the bytes"))?;
+    let metadata = &bytes[bytecode.len()..];
+
+    match tag {
+        LEGACY_STATE_VALUE_TAG if metadata.is_empty() => Ok((bytecode, None)),
+        STATE_VALUE_WITH_METADATA_TAG if !metadata.is_empty() => Ok((bytecode, Some(metadata))),
+        LEGACY_STATE_VALUE_TAG | STATE_VALUE_WITH_METADATA_TAG => Err(serde_error!(
+            "StateValue",
+            "Unexpected metadata for the variant"
+        )),
+        _ => Err(serde_error!("StateValue", "Unknown variant")),
+    }
+}
+
+/// Computes the key of the leaf of an access path as Aptos does, i.e.
+/// the hash of its state key.
+///
+/// # Arguments
+///
+/// * `address: &[u8; ACCOUNT_ADDRESS_SIZE]` - The address of the access path.
+/// * `path: &[u8]` - The path of the access path, i.e. the bcs
+///   serialization of the tag of the resource or the id of the module.
+///
+/// # Returns
+///
+/// The `HashValue` to use as the key of the leaf.
+pub fn access_path_state_key(address: &[u8; ACCOUNT_ADDRESS_SIZE], path: &[u8]) -> HashValue {
+    HashValue::new(hash_data(
+        &prefixed_sha3(b"StateKeyInner"),
+        vec![
+            &[ACCESS_PATH_STATE_KEY_TAG],
+            address,
+            &write_leb128(path.len() as u64),
+            path,
+        ],
+    ))
+}
+
+/// Returns the kind of leaf held under the path of an access path.
+///
+/// # Arguments
+///
+/// * `path: &[u8]` - The path of the access path, i.e. the bcs
+///   serialization of its `Path`.
+///
+/// # Returns
+///
+/// The `LeafKind` of the leaf, or `None` if the path is not of a
+/// known variant.
+pub fn access_path_leaf_kind(path: &[u8]) -> Option<LeafKind> {
+    match path.first()? {
+        &CODE_PATH_TAG => Some(LeafKind::Module),
+        tag if RESOURCE_PATH_TAGS.contains(tag) => Some(LeafKind::Resource),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_leaf_kind_bytes() {
+        for kind in [LeafKind::Resource, LeafKind::Module] {
+            assert_eq!(LeafKind::from_u8(kind.to_u8()).unwrap(), kind);
+        }
+
+        assert!(LeafKind::from_u8(2).is_err());
+    }
+
+    #[test]
+    fn test_split_module_state_value() {
+        let bytecode = [0xa1, 0x1c, 0xeb, 0x0b];
+        let metadata = [0; 16];
+
+        let legacy = [&[0, 4][..], &bytecode].concat();
+        assert_eq!(
+            split_module_state_value(&legacy).unwrap(),
+            (&bytecode[..], None)
+        );
+        let with_metadata = [&[1, 4][..], &bytecode, &metadata].concat();
+        assert_eq!(
+            split_module_state_value(&with_metadata).unwrap(),
+            (&bytecode[..], Some(&metadata[..]))
+        );
+        assert_ne!(
+            module_value_hash_with_metadata(&bytecode, Some(&metadata)),
+            module_value_hash(&bytecode)
+        );
+
+        assert!(split_module_state_value(&[&[0, 4][..], &bytecode, &metadata].concat()).is_err());
+        assert!(split_module_state_value(&[1, 4, 0xa1]).is_err());
+        assert!(split_module_state_value(&[2, 0]).is_err());
+        assert!(split_module_state_value(&[]).is_err());
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_module_value_hash_with_metadata() {
+        use aptos_crypto::hash::CryptoHash;
+        use aptos_types::state_store::state_value::StateValue;
+
+        // A value along with the deposit and the creation time of its slot
+        let bytecode = vec![0xa1, 0x1c, 0xeb, 0x0b, 7, 0, 0, 10];
+        let metadata = [&50u64.to_le_bytes()[..], &1_700_000_000u64.to_le_bytes()].concat();
+        let bytes = [&[1, 8][..], &bytecode, &[0], &metadata].concat();
+        let state_value: StateValue = bcs::from_bytes(&bytes).unwrap();
+
+        let (split_bytecode, split_metadata) = split_module_state_value(&bytes).unwrap();
+        assert_eq!(split_bytecode, bytecode);
+        assert_eq!(
+            module_value_hash_with_metadata(split_bytecode, split_metadata).to_vec(),
+            state_value.hash().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_module_value_hash_covers_length() {
+        // The length prefix keeps a module from colliding with its own prefix
+        assert_ne!(module_value_hash(&[1, 2]), module_value_hash(&[1, 2, 0]));
+        assert_ne!(module_value_hash(&[]), module_value_hash(&[0]));
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_module_value_hash() {
+        use aptos_crypto::hash::CryptoHash;
+        use aptos_types::state_store::state_value::StateValue;
+
+        let bytecode = vec![0xa1, 0x1c, 0xeb, 0x0b, 7, 0, 0, 10];
+        let state_value = StateValue::new_legacy(bytecode.clone().into());
+
+        assert_eq!(
+            module_value_hash(&bytecode).to_vec(),
+            state_value.hash().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_access_path_state_key_covers_path() {
+        let address = [1; ACCOUNT_ADDRESS_SIZE];
+
+        assert_ne!(
+            access_path_state_key(&address, &[1, 2]),
+            access_path_state_key(&address, &[1, 2, 0])
+        );
+        assert_ne!(
+            access_path_state_key(&address, &[1]),
+            access_path_state_key(&[2; ACCOUNT_ADDRESS_SIZE], &[1])
+        );
+    }
+
+    #[test]
+    fn test_access_path_leaf_kind() {
+        assert_eq!(access_path_leaf_kind(&[0, 1]), Some(LeafKind::Module));
+        assert_eq!(access_path_leaf_kind(&[1, 1]), Some(LeafKind::Resource));
+        assert_eq!(access_path_leaf_kind(&[2, 1]), Some(LeafKind::Resource));
+        assert_eq!(access_path_leaf_kind(&[3, 1]), None);
+        assert_eq!(access_path_leaf_kind(&[]), None);
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_access_path_leaf_kind_aptos() {
+        use aptos_sdk::move_types::identifier::Identifier;
+        use aptos_sdk::move_types::language_storage::ModuleId;
+        use aptos_sdk::move_types::move_resource::MoveStructType;
+        use aptos_types::access_path::AccessPath;
+        use aptos_types::account_address::AccountAddress;
+        use aptos_types::account_config::AccountResource;
+
+        let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap());
+        let code_path = AccessPath::code_access_path(module_id).path;
+        let resource_path = AccessPath::resource_path_vec(AccountResource::struct_tag()).unwrap();
+
+        assert_eq!(access_path_leaf_kind(&code_path), Some(LeafKind::Module));
+        assert_eq!(
+            access_path_leaf_kind(&resource_path),
+            Some(LeafKind::Resource)
+        );
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_access_path_state_key() {
+        use aptos_crypto::hash::CryptoHash;
+        use aptos_sdk::move_types::move_resource::MoveStructType;
+        use aptos_types::access_path::AccessPath;
+        use aptos_types::account_address::AccountAddress;
+        use aptos_types::account_config::AccountResource;
+        use aptos_types::state_store::state_key::StateKey;
+
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let state_key = StateKey::resource(&address, &AccountResource::struct_tag()).unwrap();
+        let path = AccessPath::resource_path_vec(AccountResource::struct_tag()).unwrap();
+
+        assert_eq!(
+            access_path_state_key(&address.into_bytes(), &path).to_vec(),
+            state_key.hash().to_vec()
+        );
+    }
+}
@@ -137,15 +137,19 @@ impl LedgerInfo {
     /// A `Result` which is `Ok` if the `LedgerInfo` could be
     /// successfully created, and `Err` otherwise.
     pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
+        let block_info_size = bytes
+            .len()
+            .checked_sub(HASH_LENGTH)
+            .ok_or_else(|| serde_error!("LedgerInfo", "Not enough data for consensus data hash"))?;
         let commit_info = BlockInfo::from_bytes(
             bytes
                 .chunk()
-                .get(..bytes.len() - HASH_LENGTH)
+                .get(..block_info_size)
                 .ok_or_else(|| serde_error!("LedgerInfo", "Not enough data for BlockInfo"))?,
         )
             .map_err(|e| serde_error!("LedgerInfo", e))?;
 
-        bytes.advance(bytes.len() - HASH_LENGTH); // Advance the buffer to get the hash
+        bytes.advance(block_info_size); // Advance the buffer to get the hash
 
         let consensus_data_hash =
             HashValue::from_slice(bytes.chunk().get(..HASH_LENGTH).ok_or_else(|| {
@@ -222,7 +226,10 @@ impl LedgerInfoWithV0 {
 
     pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
         let ledger_info_size = LedgerInfo::estimate_size_from_bytes(bytes)?;
-        let agg_sig_size = bytes.len() - ledger_info_size;
+        let agg_sig_size = bytes
+            .len()
+            .checked_sub(ledger_info_size)
+            .ok_or_else(|| serde_error!("LedgerInfoWithV0", "Not enough data for LedgerInfo"))?;
 
         let ledger_info =
             LedgerInfo::from_bytes(bytes.chunk().get(..ledger_info_size).ok_or_else(|| {
@@ -287,7 +294,9 @@ impl LedgerInfoWithSignatures {
     /// A `Result` which is `Ok` if the `LedgerInfoWithSignatures`
     /// could be successfully created, and `Err` otherwise.
     pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let ledger_v0_size = bytes.len() - ENUM_VARIANT_LEN;
+        let ledger_v0_size = bytes.len().checked_sub(ENUM_VARIANT_LEN).ok_or_else(|| {
+            serde_error!("LedgerInfoWithSignatures", "Not enough data for variant")
+        })?;
         let li_w_sig = match bytes.get_u8() {
             0 => {
                 let ledger_info_with_v0 = LedgerInfoWithV0::from_bytes(
@@ -340,11 +349,24 @@ impl Deref for LedgerInfoWithSignatures {
     }
 }
 
-#[cfg(all(test, feature = "aptos"))]
+#[cfg(test)]
 mod test {
-    use proptest::prelude::ProptestConfig;
+    use proptest::collection::vec;
+    use proptest::prelude::{any, ProptestConfig};
     use proptest::proptest;
 
+    // The number of cases can be raised with `PROPTEST_CASES`, as the fuzz workflow does
+    proptest! {
+        #![proptest_config(ProptestConfig::default())]
+        #[test]
+        fn test_fuzz_ledger_info_w_sig_from_bytes(bytes in vec(any::<u8>(), 0..1024)) {
+            use super::*;
+
+            let _ = LedgerInfoWithSignatures::from_bytes(&bytes);
+        }
+    }
+
+    #[cfg(feature = "aptos")]
     #[test]
     fn test_ledger_info_hash() {
         use super::*;
@@ -366,6 +388,7 @@ mod test {
         assert_eq!(intern_li_hash.to_vec(), aptos_li_hash.to_vec());
     }
 
+    #[cfg(feature = "aptos")]
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(10))]
         #[test]
@@ -394,6 +417,7 @@ mod test {
         }
     }
 
+    #[cfg(feature = "aptos")]
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(10))]
         #[test]
@@ -13,9 +13,17 @@
 //! - `epoch_state`: This sub-module contains the `EpochState`
 //!   structure and associated methods. It is used to represent
 //!   the epoch state in the blockchain.
+//! - `leaf`: This sub-module contains the `LeafKind` enum and
+//!   the hashing scheme of module leaves. It is used to select
+//!   the kind of state tree leaf proven by the inclusion program.
 //! - `ledger_info`: This sub-module contains the `LedgerInfo`
 //!   structure and associated methods. It is used to represent
 //!   the ledger information from the blockchain.
+//! - `output`: This sub-module contains the constants describing
+//!   the public values committed by the light client programs.
+//! - `status`: This sub-module contains the status codes
+//!   committed by the light client programs. They are used to
+//!   report inputs that cannot be proven without aborting.
 //! - `transaction`: This sub-module contains the `Transaction`
 //!   structure and associated methods. It is used to represent
 //!   the transactions in the blockchain.
@@ -44,7 +52,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub mod block_info;
 pub mod epoch_state;
 pub mod error;
+pub mod leaf;
 pub mod ledger_info;
+pub mod output;
+pub mod status;
 pub mod transaction;
 pub mod trusted_state;
 pub mod utils;
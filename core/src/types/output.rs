@@ -0,0 +1,143 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Output Module
+//!
+//! This module provides the constants describing the layout
+//! of the public values committed by the light client programs.
+//!
+//! Each program commits a distinct magic as its first value so
+//! that a verifier can tell which program produced a given set
+//! of public values, preventing cross-program confusion.
+
+use crate::crypto::hash::{hash_data, prefixed_sha3, HashValue, HASH_LENGTH};
+
+/// Version of the layout of the public values committed by the inclusion program.
+///
+/// - `0`: validator verifier hash, state root, block id, key and value hash.
+/// - `1`: the inclusion magic and an `InclusionStatus`, followed when the inclusion was proven
+///   by the values of version `0` and, in order:
+///   - the `LeafKind` of the proven leaf;
+///   - the number of leaves of the transaction accumulator of the ledger info, as a `u64`;
+///   - the epoch of the signed ledger info, as a `u64`;
+///   - the index of the candidate validator verifier the signatures verified under, as a
+///     `u8`;
+///   - the hash of the proven transaction;
+///   - `1` and the address and the path hashing to the key when a preimage of the key was
+///     given, or `0`, a zero address and an empty path otherwise;
+///   - `1` if the signatures were not verified, by a program built with its insecure
+///     `skip-signatures` feature, or `0` otherwise;
+///   - the timestamp of the signed ledger info, in microseconds, as a `u64`.
+pub const INCLUSION_OUTPUT_VERSION: u8 = 1;
+
+/// Length in bytes of the magic committed by the programs.
+pub const OUTPUT_MAGIC_LEN: usize = 4;
+
+/// Magic committed first by the inclusion program.
+pub const INCLUSION_OUTPUT_MAGIC: [u8; OUTPUT_MAGIC_LEN] = *b"INCL";
+
+/// Magic committed first by the inclusion program when it proves several transactions
+/// under a single ledger info rather than an account. It is followed by an
+/// `InclusionStatus` and, when the transactions were proven, the validator verifier hash,
+/// the block id and the hashes of the transactions.
+pub const TRANSACTIONS_OUTPUT_MAGIC: [u8; OUTPUT_MAGIC_LEN] = *b"TXNS";
+
+/// Magic committed first by the inclusion program when it proves an event emitted by a
+/// transaction, against the event root hash of the transaction rather than a leaf against
+/// its state checkpoint. It is followed by an `InclusionStatus` and, when the event was
+/// proven, the validator verifier hash, the block id, the hash of the transaction and the
+/// hash of the event.
+pub const EVENT_OUTPUT_MAGIC: [u8; OUTPUT_MAGIC_LEN] = *b"EVNT";
+
+/// Magic committed first by the inclusion program when it proves several leaves against the
+/// state checkpoint of a single transaction. It is followed by an `InclusionStatus` and,
+/// when the leaves were proven, the validator verifier hash, the block id, the state root,
+/// the number of leaves as a `u64` and their `inclusion_accumulator_root`.
+pub const BATCH_OUTPUT_MAGIC: [u8; OUTPUT_MAGIC_LEN] = *b"BTCH";
+
+/// Magic committed first by the epoch change program.
+pub const EPOCH_CHANGE_OUTPUT_MAGIC: [u8; OUTPUT_MAGIC_LEN] = *b"EPCH";
+
+/// Magic committed first by the signature program. It is followed by a `SignatureStatus`
+/// and, when the signatures were verified, the validator verifier hash and the block id.
+pub const SIGNATURE_OUTPUT_MAGIC: [u8; OUTPUT_MAGIC_LEN] = *b"SIGN";
+
+/// Folds the keys and value hashes of several proven leaves into a single commitment, for
+/// verifiers that would rather check one hash than a list of leaves. It is meant to be
+/// committed along with the number of leaves.
+///
+/// The leaves are folded in the canonical order of their keys, ascending, whatever the
+/// order they were proven in, so that the commitment only depends on the set of leaves.
+/// Starting from zeros, each leaf is hashed along with the commitment of the leaves before
+/// it. Two leaves sharing a key are not a set, and are not folded.
+///
+/// # Arguments
+///
+/// * `leaves: &[([u8; HASH_LENGTH], [u8; HASH_LENGTH])]` - The key and the value hash of
+///   each leaf, in any order.
+///
+/// # Returns
+///
+/// The `HashValue` committing to the leaves, or `None` if two of them share a key.
+pub fn inclusion_accumulator_root(
+    leaves: &[([u8; HASH_LENGTH], [u8; HASH_LENGTH])],
+) -> Option<HashValue> {
+    let mut leaves = leaves.to_vec();
+    leaves.sort_unstable();
+    if leaves.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return None;
+    }
+
+    let tag = prefixed_sha3(b"InclusionAccumulator");
+    let root = leaves
+        .iter()
+        .fold([0; HASH_LENGTH], |root, (key, value_hash)| {
+            hash_data(&tag, vec![&root, key, value_hash])
+        });
+
+    Some(HashValue::new(root))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_inclusion_accumulator_root() {
+        let leaves = [([1; 32], [2; 32]), ([3; 32], [4; 32]), ([5; 32], [6; 32])];
+        let root = inclusion_accumulator_root(&leaves).unwrap();
+
+        // Each leaf is folded into the commitment of the leaves with smaller keys
+        let tag = prefixed_sha3(b"InclusionAccumulator");
+        let first = hash_data(&tag, vec![&[0; 32], &[1; 32], &[2; 32]]);
+        let second = hash_data(&tag, vec![&first, &[3; 32], &[4; 32]]);
+        let third = hash_data(&tag, vec![&second, &[5; 32], &[6; 32]]);
+        assert_eq!(root.as_ref(), &third);
+
+        assert_eq!(inclusion_accumulator_root(&[]).unwrap().as_ref(), &[0; 32]);
+        assert_ne!(inclusion_accumulator_root(&leaves[..2]).unwrap(), root);
+        let mut other_value = leaves;
+        other_value[2].1 = [7; 32];
+        assert_ne!(inclusion_accumulator_root(&other_value).unwrap(), root);
+
+        // A key given twice, whatever its value hashes
+        let mut duplicate_key = leaves;
+        duplicate_key[2].0 = [1; 32];
+        assert!(inclusion_accumulator_root(&duplicate_key).is_none());
+        assert!(inclusion_accumulator_root(&[leaves[0], leaves[0]]).is_none());
+    }
+
+    #[test]
+    fn test_inclusion_accumulator_root_ordering() {
+        let sorted = [([1; 32], [6; 32]), ([3; 32], [4; 32]), ([5; 32], [2; 32])];
+        let root = inclusion_accumulator_root(&sorted).unwrap();
+
+        for shuffled in [
+            [sorted[2], sorted[0], sorted[1]],
+            [sorted[1], sorted[2], sorted[0]],
+            [sorted[2], sorted[1], sorted[0]],
+        ] {
+            assert_eq!(inclusion_accumulator_root(&shuffled).unwrap(), root);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Status Module
+//!
+//! This module provides the status codes committed by the
+//! light client programs.
+//!
+//! Instead of aborting on inputs that are well-formed but cannot
+//! be proven, the programs commit a status as the first element
+//! of their public values. When the status does not represent a
+//! success, no other output is committed.
+
+use crate::serde_error;
+use crate::types::error::TypesError;
+
+/// `InclusionStatus` is the outcome of the inclusion program.
+///
+/// Only transactions carrying a state checkpoint hash can be used
+/// to prove an account inclusion, as the sparse Merkle proof is
+/// verified against that hash. On Aptos, those are the transactions
+/// closing a block (`StateCheckpoint` or `BlockEpilogue`) and the
+/// ones triggering a reconfiguration. Other transactions, such as
+/// user transactions in the middle of a block, produce
+/// `InclusionStatus::NotACheckpoint`.
+///
+/// The transaction must also be at or below the version of the ledger
+/// info anchoring it, or `InclusionStatus::NewerThanLedgerInfo` is
+/// produced.
+///
+/// When the signatures are verified on a ledger info distinct from
+/// the one the transaction proof is verified against, both must share
+/// the same transaction accumulator root, version and epoch, or
+/// `InclusionStatus::LedgerInfoMismatch` is produced.
+///
+/// A sparse Merkle proof can not be deeper than the number of bits
+/// of the key it proves, or `InclusionStatus::InvalidProofDepth` is
+/// produced.
+///
+/// When two candidate validator verifiers are given and the
+/// signatures verify under neither of them,
+/// `InclusionStatus::NoMatchingValidatorVerifier` is produced.
+///
+/// When a single validator verifier is given and the signers of the
+/// ledger info do not reach its quorum voting power,
+/// `InclusionStatus::InsufficientVotingPower` is produced. When that
+/// validator verifier, or each of two candidates, can never reach its
+/// quorum, `InclusionStatus::UnsatisfiableQuorum` is produced instead.
+/// A single unreachable candidate among two is skipped.
+///
+/// When several transactions are proven under a single ledger info
+/// but none is given, `InclusionStatus::EmptyBatch` is produced
+/// rather than an inclusion attesting to nothing. When several leaves
+/// are proven together and two of them share a key,
+/// `InclusionStatus::DuplicateKey` is produced, as they are committed
+/// as a set.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InclusionStatus {
+    /// The account is included in the state checkpoint of the transaction.
+    Included = 0,
+    /// The transaction does not carry a state checkpoint hash.
+    NotACheckpoint = 1,
+    /// The transaction version is above the version of the ledger info
+    /// it is verified against.
+    NewerThanLedgerInfo = 2,
+    /// The signed ledger info does not share the transaction accumulator
+    /// root, the version or the epoch of the ledger info used to verify
+    /// the transaction proof.
+    LedgerInfoMismatch = 3,
+    /// The sparse Merkle proof has more siblings than the key has bits.
+    InvalidProofDepth = 4,
+    /// The signatures verify under none of the candidate validator
+    /// verifiers.
+    NoMatchingValidatorVerifier = 5,
+    /// The signers of the ledger info do not reach the quorum voting
+    /// power of the validator verifier.
+    InsufficientVotingPower = 6,
+    /// The quorum voting power of a validator verifier exceeds its
+    /// total voting power, so that no ledger info can be signed by it.
+    UnsatisfiableQuorum = 7,
+    /// No transaction was given to be proven under the ledger info.
+    EmptyBatch = 8,
+    /// Two of the leaves proven together share a key.
+    DuplicateKey = 9,
+}
+
+impl InclusionStatus {
+    /// Converts the `InclusionStatus` to the byte committed by the program.
+    ///
+    /// # Returns
+    ///
+    /// The `u8` representing the `InclusionStatus`.
+    pub const fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Creates an `InclusionStatus` from the byte committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte: u8` - The byte from which to create the `InclusionStatus`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the byte represents a known
+    /// `InclusionStatus`, and `Err` otherwise.
+    pub fn from_u8(byte: u8) -> Result<Self, TypesError> {
+        match byte {
+            0 => Ok(InclusionStatus::Included),
+            1 => Ok(InclusionStatus::NotACheckpoint),
+            2 => Ok(InclusionStatus::NewerThanLedgerInfo),
+            3 => Ok(InclusionStatus::LedgerInfoMismatch),
+            4 => Ok(InclusionStatus::InvalidProofDepth),
+            5 => Ok(InclusionStatus::NoMatchingValidatorVerifier),
+            6 => Ok(InclusionStatus::InsufficientVotingPower),
+            7 => Ok(InclusionStatus::UnsatisfiableQuorum),
+            8 => Ok(InclusionStatus::EmptyBatch),
+            9 => Ok(InclusionStatus::DuplicateKey),
+            _ => Err(serde_error!("InclusionStatus", "Invalid variant")),
+        }
+    }
+}
+
+/// `EpochChangeStatus` is the outcome of the epoch change program.
+///
+/// When the trusted state can not be ratcheted with the given
+/// `EpochChangeProof`, the program commits the reason of the
+/// failure instead of aborting. A valid proof that stays in the
+/// trusted epoch produces `EpochChangeStatus::NoEpochChange`.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpochChangeStatus {
+    /// The trusted state was ratcheted to the next epoch.
+    Ratcheted = 0,
+    /// The proof does not carry any ledger info.
+    EmptyProof = 1,
+    /// The proof is behind the trusted state.
+    StaleProof = 2,
+    /// The proof does not lead from the trusted epoch to the next one.
+    EpochMismatch = 3,
+    /// The signatures of a ledger info of the proof could not be verified.
+    InvalidSignatures = 4,
+    /// The proof is valid but does not cross an epoch boundary, so there
+    /// is nothing to ratchet.
+    NoEpochChange = 5,
+    /// The validator verifier of the trusted state is not the one the
+    /// program was expected to start from.
+    StartValidatorMismatch = 6,
+    /// The trusted state is a waypoint, which carries no validator
+    /// verifier nor epoch to ratchet from.
+    WaypointTrustedState = 7,
+}
+
+impl EpochChangeStatus {
+    /// Converts the `EpochChangeStatus` to the byte committed by the program.
+    ///
+    /// # Returns
+    ///
+    /// The `u8` representing the `EpochChangeStatus`.
+    pub const fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Creates an `EpochChangeStatus` from the byte committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte: u8` - The byte from which to create the `EpochChangeStatus`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the byte represents a known
+    /// `EpochChangeStatus`, and `Err` otherwise.
+    pub fn from_u8(byte: u8) -> Result<Self, TypesError> {
+        match byte {
+            0 => Ok(EpochChangeStatus::Ratcheted),
+            1 => Ok(EpochChangeStatus::EmptyProof),
+            2 => Ok(EpochChangeStatus::StaleProof),
+            3 => Ok(EpochChangeStatus::EpochMismatch),
+            4 => Ok(EpochChangeStatus::InvalidSignatures),
+            5 => Ok(EpochChangeStatus::NoEpochChange),
+            6 => Ok(EpochChangeStatus::StartValidatorMismatch),
+            7 => Ok(EpochChangeStatus::WaypointTrustedState),
+            _ => Err(serde_error!("EpochChangeStatus", "Invalid variant")),
+        }
+    }
+}
+
+/// `SignatureStatus` is the outcome of the signature program.
+///
+/// When the ledger info is not signed by the given validator
+/// verifier, e.g. because its signers do not reach the quorum voting
+/// power, `SignatureStatus::InvalidSignatures` is produced.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The ledger info is signed by the validator verifier.
+    Verified = 0,
+    /// The signatures of the ledger info could not be verified under
+    /// the validator verifier.
+    InvalidSignatures = 1,
+}
+
+impl SignatureStatus {
+    /// Converts the `SignatureStatus` to the byte committed by the program.
+    ///
+    /// # Returns
+    ///
+    /// The `u8` representing the `SignatureStatus`.
+    pub const fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Creates a `SignatureStatus` from the byte committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte: u8` - The byte from which to create the `SignatureStatus`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the byte represents a known
+    /// `SignatureStatus`, and `Err` otherwise.
+    pub fn from_u8(byte: u8) -> Result<Self, TypesError> {
+        match byte {
+            0 => Ok(SignatureStatus::Verified),
+            1 => Ok(SignatureStatus::InvalidSignatures),
+            _ => Err(serde_error!("SignatureStatus", "Invalid variant")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_inclusion_status_bytes() {
+        for status in [
+            InclusionStatus::Included,
+            InclusionStatus::NotACheckpoint,
+            InclusionStatus::NewerThanLedgerInfo,
+            InclusionStatus::LedgerInfoMismatch,
+            InclusionStatus::InvalidProofDepth,
+            InclusionStatus::NoMatchingValidatorVerifier,
+            InclusionStatus::InsufficientVotingPower,
+            InclusionStatus::UnsatisfiableQuorum,
+            InclusionStatus::EmptyBatch,
+            InclusionStatus::DuplicateKey,
+        ] {
+            assert_eq!(InclusionStatus::from_u8(status.to_u8()).unwrap(), status);
+        }
+
+        assert!(InclusionStatus::from_u8(10).is_err());
+    }
+
+    #[test]
+    fn test_epoch_change_status_bytes() {
+        for status in [
+            EpochChangeStatus::Ratcheted,
+            EpochChangeStatus::EmptyProof,
+            EpochChangeStatus::StaleProof,
+            EpochChangeStatus::EpochMismatch,
+            EpochChangeStatus::InvalidSignatures,
+            EpochChangeStatus::NoEpochChange,
+            EpochChangeStatus::StartValidatorMismatch,
+            EpochChangeStatus::WaypointTrustedState,
+        ] {
+            assert_eq!(EpochChangeStatus::from_u8(status.to_u8()).unwrap(), status);
+        }
+
+        assert!(EpochChangeStatus::from_u8(8).is_err());
+    }
+
+    #[test]
+    fn test_signature_status_bytes() {
+        for status in [
+            SignatureStatus::Verified,
+            SignatureStatus::InvalidSignatures,
+        ] {
+            assert_eq!(SignatureStatus::from_u8(status.to_u8()).unwrap(), status);
+        }
+
+        assert!(SignatureStatus::from_u8(2).is_err());
+    }
+}
@@ -40,6 +40,17 @@ impl TransactionInfo {
         }
     }
 
+    /// Returns the root hash of the accumulator of the events emitted by the transaction.
+    ///
+    /// # Returns
+    ///
+    /// The event root hash of the `TransactionInfo`.
+    pub const fn event_root_hash(&self) -> HashValue {
+        match self {
+            TransactionInfo::V0(info) => info.event_root_hash,
+        }
+    }
+
     /// Converts the `TransactionInfo` to a byte vector.
     ///
     /// # Returns
@@ -68,6 +79,12 @@ impl TransactionInfo {
     /// could be successfully created, and `Err` otherwise.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
         let mut buf = BytesMut::from(bytes);
+        if !buf.has_remaining() {
+            return Err(serde_error!(
+                "TransactionInfo",
+                "Not enough data for variant"
+            ));
+        }
         let tx_info = match buf.get_u8() {
             0 => {
                 let tx_info_v0 = TransactionInfoV0::from_bytes(
@@ -320,6 +337,10 @@ impl ExecutionStatus {
 
 #[cfg(test)]
 mod test {
+    use proptest::collection::vec;
+    use proptest::prelude::{any, ProptestConfig};
+    use proptest::proptest;
+
     #[test]
     fn test_bytes_conversion_execution_status() {
         use crate::types::transaction::ExecutionStatus;
@@ -337,6 +358,17 @@ mod test {
         assert_eq!(execution_status_ser_bcs, execution_status_to_bytes);
     }
 
+    // The number of cases can be raised with `PROPTEST_CASES`, as the fuzz workflow does
+    proptest! {
+        #![proptest_config(ProptestConfig::default())]
+        #[test]
+        fn test_fuzz_transaction_info_from_bytes(bytes in vec(any::<u8>(), 0..512)) {
+            use crate::types::transaction::TransactionInfo;
+
+            let _ = TransactionInfo::from_bytes(&bytes);
+        }
+    }
+
     #[cfg(feature = "aptos")]
     #[test]
     fn test_bytes_conversion_transaction_info() {
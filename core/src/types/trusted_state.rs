@@ -12,8 +12,9 @@
 use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
 use crate::serde_error;
 use crate::types::epoch_state::EpochState;
-use crate::types::error::TypesError;
+use crate::types::error::{TypesError, VerifyError};
 use crate::types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+use crate::types::status::EpochChangeStatus;
 use crate::types::utils::{read_leb128, write_leb128};
 use crate::types::waypoint::{Waypoint, WAYPOINT_SIZE};
 use crate::types::Version;
@@ -142,7 +143,9 @@ impl TrustedState {
     /// containing one `LedgerInfoWithSignatures` that
     /// represents an epoch transition from
     /// trusted_state.epoch -> trusted_state.epoch +1, and
-    /// verifies it.
+    /// verifies it. A proof staying in the trusted epoch is
+    /// verified against its validator set, and returns
+    /// `TrustedStateChange::Version` or `TrustedStateChange::NoChange`.
     ///
     /// # Arguments
     ///
@@ -198,8 +201,61 @@ impl TrustedState {
                 latest_epoch_change_li: epoch_change_li,
             })
         } else {
-            Err(format_err!("Received proof is not for an epoch change"))
+            // The proof only gets us further into our current epoch, check that its latest
+            // ledger info belongs to it.
+            let new_waypoint = Waypoint::new_any(latest_li.ledger_info());
+            if new_waypoint.version() == self.version() {
+                ensure!(
+                    new_waypoint == self.waypoint(),
+                    "LedgerInfo doesn't match verified state"
+                );
+                return Ok(TrustedStateChange::NoChange);
+            }
+
+            self.verify(latest_li)?;
+            let new_state = match self {
+                Self::EpochWaypoint(_waypoint) => {
+                    unimplemented!("This LC doesn't support epoch waypoints")
+                }
+                Self::EpochState { epoch_state, .. } => TrustedState::EpochState {
+                    waypoint: new_waypoint,
+                    epoch_state: epoch_state.clone(),
+                },
+            };
+
+            Ok(TrustedStateChange::Version { new_state })
+        }
+    }
+
+    /// Classifies an error returned by `verify_and_ratchet_inner` into
+    /// the `EpochChangeStatus` committed by the epoch change program.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch_change_proof: &EpochChangeProof` - The epoch change proof that failed to verify.
+    /// * `error: &anyhow::Error` - The error returned for that proof.
+    ///
+    /// # Returns
+    ///
+    /// The `EpochChangeStatus` describing the failure.
+    pub fn ratchet_failure_status(
+        &self,
+        epoch_change_proof: &EpochChangeProof,
+        error: &anyhow::Error,
+    ) -> EpochChangeStatus {
+        let Some(latest_li) = epoch_change_proof.ledger_info_with_sigs.last() else {
+            return EpochChangeStatus::EmptyProof;
+        };
+        if error.downcast_ref::<VerifyError>().is_some() {
+            return EpochChangeStatus::InvalidSignatures;
+        }
+        if latest_li.ledger_info().version() < self.version()
+            || self.is_ledger_info_stale(latest_li.ledger_info())
+        {
+            return EpochChangeStatus::StaleProof;
         }
+
+        EpochChangeStatus::EpochMismatch
     }
 
     /// Converts the `TrustedState` to a byte vector.
@@ -436,7 +492,8 @@ impl EpochChangeProof {
         // Total length for LedgerInfoWithSignatures
         let total_len = bytes.remaining() - 1;
 
-        if total_len % len as usize != 0 {
+        // An empty proof is well-formed, it is rejected when verified
+        if len != 0 && total_len % len as usize != 0 {
             return Err(serde_error!(
                 "EpochChangeProof",
                 "Invalid data length for ledger_info_with_sigs"
@@ -574,4 +631,20 @@ mod test {
             aptos_trusted_state_hash.to_vec()
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes_conversion_empty_epoch_change_proof() {
+        use super::*;
+
+        let epoch_change_proof = EpochChangeProof {
+            ledger_info_with_sigs: vec![],
+            more: false,
+        };
+        let bytes = epoch_change_proof.to_bytes();
+
+        let intern_epoch_change_proof = EpochChangeProof::from_bytes(&bytes).unwrap();
+
+        assert!(intern_epoch_change_proof.ledger_info_with_sigs.is_empty());
+        assert_eq!(bytes, intern_epoch_change_proof.to_bytes());
+    }
+}
@@ -21,7 +21,8 @@ pub const VALIDATOR_CONSENSUS_INFO_SIZE: usize =
 /// `ValidatorConsensusInfo` contains all the necessary
 /// information about a validator to assess its participation
 /// in the consensus.
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
 pub struct ValidatorConsensusInfo {
     address: AccountAddress,
     public_key: PublicKey, // bls12-381
@@ -218,6 +219,16 @@ impl ValidatorVerifier {
         }
     }
 
+    /// Returns whether the quorum voting power of the `ValidatorVerifier` can be reached,
+    /// e.g. not by validators of which none has any voting power.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the quorum voting power is at most the total voting power.
+    pub fn is_quorum_reachable(&self) -> bool {
+        self.quorum_voting_power() <= self.total_voting_power()
+    }
+
     /// Returns the voting power for this address.
     ///
     /// # Arguments
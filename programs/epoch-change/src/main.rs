@@ -3,36 +3,78 @@ sp1_zkvm::entrypoint!(main);
 
 
 use aptos_lc_core::crypto::hash::CryptoHash;
+use aptos_lc_core::types::output::EPOCH_CHANGE_OUTPUT_MAGIC;
+use aptos_lc_core::types::status::EpochChangeStatus;
 use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState, TrustedStateChange};
 
 pub fn main() {
+    // Commit the program magic, so that the outputs can not be mistaken for another program's
+    sp1_zkvm::io::commit(&EPOCH_CHANGE_OUTPUT_MAGIC);
+
     let trusted_state_bytes = sp1_zkvm::io::read_vec();
     let epoch_change_proof = sp1_zkvm::io::read_vec();
+    let expected_start_validator_hash: Option<[u8; 32]> = sp1_zkvm::io::read();
     let trusted_state = TrustedState::from_bytes(&trusted_state_bytes)
         .expect("TrustedState::from_bytes: could not create trusted state");
     let epoch_change_proof = EpochChangeProof::from_bytes(&epoch_change_proof)
         .expect("EpochChangeProof::from_bytes: could not create epoch change proof");
-    let trusted_state_change = trusted_state
-        .verify_and_ratchet_inner(&epoch_change_proof)
-        .expect("TrustedState::verify_and_ratchet_inner: could not ratchet");
-    let validator_verifier_hash = match trusted_state_change {
+    // The start epoch is the trusted one, not the one of the last ledger info of the proof,
+    // which a proof skipping epochs would otherwise hide. A waypoint carries neither.
+    let (prev_epoch_validator_verifier_hash, start_epoch) = match &trusted_state {
+        TrustedState::EpochState { epoch_state, .. } => {
+            (epoch_state.verifier().hash(), epoch_state.epoch)
+        }
+        TrustedState::EpochWaypoint(_) => {
+            sp1_zkvm::io::commit(&EpochChangeStatus::WaypointTrustedState.to_u8());
+            return;
+        }
+    };
+    // A consumer pinning the chain it follows only accepts its own validator verifier as start
+    if let Some(expected_start_validator_hash) = expected_start_validator_hash {
+        if *prev_epoch_validator_verifier_hash.as_ref() != expected_start_validator_hash {
+            sp1_zkvm::io::commit(&EpochChangeStatus::StartValidatorMismatch.to_u8());
+            return;
+        }
+    }
+    // If the trusted state can not be ratcheted, only commit the reason and stop there.
+    let trusted_state_change = match trusted_state.verify_and_ratchet_inner(&epoch_change_proof) {
+        Ok(trusted_state_change) => trusted_state_change,
+        Err(err) => {
+            let status = trusted_state.ratchet_failure_status(&epoch_change_proof, &err);
+            sp1_zkvm::io::commit(&status.to_u8());
+            return;
+        }
+    };
+    let (validator_verifier_hash, end_epoch) = match trusted_state_change {
         TrustedStateChange::Epoch {
             latest_epoch_change_li,
             ..
-        } => latest_epoch_change_li
-            .ledger_info()
-            .next_epoch_state()
-            .expect("Expected epoch state")
-            .verifier()
-            .hash(),
-        _ => panic!("Expected epoch change"),
-    };
-    let prev_epoch_validator_verifier_hash = match &trusted_state {
-        TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().hash(),
-        _ => panic!("Expected epoch change for current trusted state"),
+        } => {
+            let next_epoch_state = latest_epoch_change_li
+                .ledger_info()
+                .next_epoch_state()
+                .expect("Expected epoch state");
+            (next_epoch_state.verifier().hash(), next_epoch_state.epoch)
+        }
+        // The proof is valid, but there is no epoch boundary to ratchet over
+        TrustedStateChange::Version { .. } | TrustedStateChange::NoChange => {
+            sp1_zkvm::io::commit(&EpochChangeStatus::NoEpochChange.to_u8());
+            return;
+        }
     };
+    // Only a single epoch change can be proven at once
+    if end_epoch != start_epoch + 1 {
+        sp1_zkvm::io::commit(&EpochChangeStatus::EpochMismatch.to_u8());
+        return;
+    }
+
 
+    sp1_zkvm::io::commit(&EpochChangeStatus::Ratcheted.to_u8());
 
     sp1_zkvm::io::commit(prev_epoch_validator_verifier_hash.as_ref());
     sp1_zkvm::io::commit(validator_verifier_hash.as_ref());
+
+    // Commit the epochs before and after the ratchet
+    sp1_zkvm::io::commit(&start_epoch);
+    sp1_zkvm::io::commit(&end_epoch);
 }
@@ -1,71 +1,519 @@
 #![no_main]
 
 use aptos_lc_core::crypto::hash::{CryptoHash, HashValue};
+use aptos_lc_core::merkle::account_state_proof::{AccountStateProof, ProofLayout};
+use aptos_lc_core::merkle::event_proof::{contract_event_hash, EventAccumulatorProof};
 use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
 use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorProof;
+use aptos_lc_core::types::error::VerifyError;
+use aptos_lc_core::types::leaf::{
+    access_path_leaf_kind, access_path_state_key, module_value_hash_with_metadata, LeafKind,
+};
 use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::output::{
+    inclusion_accumulator_root, BATCH_OUTPUT_MAGIC, EVENT_OUTPUT_MAGIC, INCLUSION_OUTPUT_MAGIC,
+    TRANSACTIONS_OUTPUT_MAGIC,
+};
+use aptos_lc_core::types::status::InclusionStatus;
 use aptos_lc_core::types::transaction::TransactionInfo;
 use aptos_lc_core::types::validator::ValidatorVerifier;
 
 sp1_zkvm::entrypoint!(main);
 
 pub fn main() {
-    let sparse_merkle_proof_bytes = sp1_zkvm::io::read_vec();
+    // The proofs are either given separately, or bundled in an `AccountStateProof` so that
+    // they can not be out of step with each other. The outputs do not depend on the layout.
+    let proof_layout = ProofLayout::from_u8(sp1_zkvm::io::read())
+        .expect("from_u8: could not deserialize ProofLayout");
+    // Proving transactions alone commits distinct outputs
+    if proof_layout == ProofLayout::Transactions {
+        return prove_transactions();
+    }
+    // As are events, proven against a transaction rather than the state
+    if proof_layout == ProofLayout::Event {
+        return prove_event();
+    }
+    // And are several leaves, committed as a single root
+    if proof_layout == ProofLayout::Batch {
+        return prove_batch();
+    }
+
+    // Commit the program magic, so that the outputs can not be mistaken for another program's
+    sp1_zkvm::io::commit(&INCLUSION_OUTPUT_MAGIC);
+
+    // The sparse Merkle proof, or the whole `AccountStateProof`
+    let proof_bytes = sp1_zkvm::io::read_vec();
+    let split_proof_bytes = match proof_layout {
+        ProofLayout::Split => Some((sp1_zkvm::io::read_vec(), sp1_zkvm::io::read_vec())),
+        ProofLayout::Combined
+        | ProofLayout::Transactions
+        | ProofLayout::Event
+        | ProofLayout::Batch => None,
+    };
     let key: [u8; 32] = sp1_zkvm::io::read();
     let leaf_value_hash: [u8; 32] = sp1_zkvm::io::read();
+    let leaf_kind =
+        LeafKind::from_u8(sp1_zkvm::io::read()).expect("from_u8: could not deserialize LeafKind");
+    // A module leaf is proven from its bytecode, hashed here rather than trusted
+    let leaf_value_hash = match leaf_kind {
+        LeafKind::Resource => HashValue::new(leaf_value_hash),
+        // Along with the metadata of its storage slot, if it was stored with some
+        LeafKind::Module => {
+            let bytecode = sp1_zkvm::io::read_vec();
+            let metadata: Option<Vec<u8>> = sp1_zkvm::io::read();
+            module_value_hash_with_metadata(&bytecode, metadata.as_deref())
+        }
+    };
+    // The address and the path of the access path of the key, for verifiers needing to know
+    // which resource the proof is about
+    let key_preimage: Option<([u8; 32], Vec<u8>)> = sp1_zkvm::io::read();
+    if let Some((address, path)) = &key_preimage {
+        assert_eq!(
+            access_path_state_key(address, path).as_ref(),
+            &key,
+            "key_preimage: does not hash to the key"
+        );
+    }
+    // The kind of the leaf is tied to its key through the path of the preimage, lest the
+    // value of a resource be proven as the bytecode of a module
+    if leaf_kind == LeafKind::Module {
+        assert!(
+            key_preimage.is_some(),
+            "key_preimage: required for a module leaf"
+        );
+    }
+    if let Some((_, path)) = &key_preimage {
+        assert_eq!(
+            access_path_leaf_kind(path),
+            Some(leaf_kind),
+            "key_preimage: the path does not hold a leaf of the given kind"
+        );
+    }
 
-    let transaction_bytes = sp1_zkvm::io::read_vec();
     let transaction_index: u64 = sp1_zkvm::io::read();
-    let transaction_proof = sp1_zkvm::io::read_vec();
     let ledger_info_bytes = sp1_zkvm::io::read_vec();
+    let signed_ledger_info_bytes: Option<Vec<u8>> = sp1_zkvm::io::read();
 
     let verified_validator_verifier = sp1_zkvm::io::read_vec();
+    // Around an epoch boundary, the ledger info may be signed by either of two sets
+    let alternative_validator_verifier: Option<Vec<u8>> = sp1_zkvm::io::read();
 
     let validator_verifier = ValidatorVerifier::from_bytes(&verified_validator_verifier)
         .expect("validator_verifier: could not create ValidatorVerifier from bytes");
+    let alternative_validator_verifier = alternative_validator_verifier.map(|bytes| {
+        ValidatorVerifier::from_bytes(&bytes)
+            .expect("validator_verifier: could not create alternative ValidatorVerifier from bytes")
+    });
+    // A validator verifier whose quorum exceeds its voting power can never sign a ledger
+    // info, which is told apart from signatures falling short of the quorum. A candidate
+    // among two is only skipped, as the signatures may verify under the other one.
+    if alternative_validator_verifier.is_none() && !validator_verifier.is_quorum_reachable() {
+        sp1_zkvm::io::commit(&InclusionStatus::UnsatisfiableQuorum.to_u8());
+        return;
+    }
 
-    // Verify transaction inclusion in the LedgerInfoWithSignatures
-    let transaction = TransactionInfo::from_bytes(&transaction_bytes)
-        .expect("from_bytes: could not deserialize TransactionInfo");
-    let transaction_hash = transaction.hash();
-    let transaction_proof = TransactionAccumulatorProof::from_bytes(&transaction_proof)
-        .expect("from_bytes: could not deserialize TransactionAccumulatorProof");
     let latest_li = LedgerInfoWithSignatures::from_bytes(&ledger_info_bytes)
         .expect("from_bytes: could not deserialize LedgerInfo");
+    // The signatures may be carried by a ledger info fetched separately from the one the
+    // transaction proof is verified against. It can only be trusted for the transaction
+    // if both share the same transaction accumulator root, version and epoch.
+    let signed_li = signed_ledger_info_bytes.map(|bytes| {
+        LedgerInfoWithSignatures::from_bytes(&bytes)
+            .expect("from_bytes: could not deserialize signed LedgerInfo")
+    });
+    if let Some(signed_li) = &signed_li {
+        if signed_li.ledger_info().transaction_accumulator_hash()
+            != latest_li.ledger_info().transaction_accumulator_hash()
+            || signed_li.ledger_info().version() != latest_li.ledger_info().version()
+            || signed_li.ledger_info().epoch() != latest_li.ledger_info().epoch()
+        {
+            sp1_zkvm::io::commit(&InclusionStatus::LedgerInfoMismatch.to_u8());
+            return;
+        }
+    }
+    let signed_li = signed_li.as_ref().unwrap_or(&latest_li);
+
+    // Verify the signatures of the LedgerInfoWithSignatures first. Nothing else can be
+    // trusted without them, and invalid inputs fail before verifying any other proof.
+    #[cfg(not(feature = "skip-signatures"))]
+    let (validator_verifier, validator_verifier_index) = match alternative_validator_verifier {
+        None => {
+            // Too few signers is an outcome of the chain rather than a malformed input
+            match signed_li.verify_signatures(&validator_verifier) {
+                Err(VerifyError::TooLittleVotingPower { .. }) => {
+                    sp1_zkvm::io::commit(&InclusionStatus::InsufficientVotingPower.to_u8());
+                    return;
+                }
+                result => result.expect("verify_signatures: could not verify signatures"),
+            }
+            (validator_verifier, 0u8)
+        }
+        Some(alternative_validator_verifier) => {
+            let verifies = |validator_verifier: &ValidatorVerifier| {
+                validator_verifier.is_quorum_reachable()
+                    && signed_li.verify_signatures(validator_verifier).is_ok()
+            };
+            if verifies(&validator_verifier) {
+                (validator_verifier, 0u8)
+            } else if verifies(&alternative_validator_verifier) {
+                (alternative_validator_verifier, 1u8)
+            } else if !validator_verifier.is_quorum_reachable()
+                && !alternative_validator_verifier.is_quorum_reachable()
+            {
+                sp1_zkvm::io::commit(&InclusionStatus::UnsatisfiableQuorum.to_u8());
+                return;
+            } else {
+                sp1_zkvm::io::commit(&InclusionStatus::NoMatchingValidatorVerifier.to_u8());
+                return;
+            }
+        }
+    };
+    // INSECURE: the ledger info is trusted as given, which is only meant to test the other
+    // checks locally. This is committed, so that such proofs are rejected by the host.
+    #[cfg(feature = "skip-signatures")]
+    let (validator_verifier, validator_verifier_index) = {
+        let _ = alternative_validator_verifier;
+        (validator_verifier, 0u8)
+    };
+    // A transaction newer than the signed ledger info can not be anchored to it
+    if transaction_index > signed_li.ledger_info().version() {
+        sp1_zkvm::io::commit(&InclusionStatus::NewerThanLedgerInfo.to_u8());
+        return;
+    }
+
+    println!("cycle-tracker-report-start: verify_proofs");
+    let (transaction, transaction_proof, sparse_merkle_proof) = match split_proof_bytes {
+        Some((transaction_bytes, transaction_proof_bytes)) => (
+            TransactionInfo::from_bytes(&transaction_bytes)
+                .expect("from_bytes: could not deserialize TransactionInfo"),
+            TransactionAccumulatorProof::from_bytes(&transaction_proof_bytes)
+                .expect("from_bytes: could not deserialize TransactionAccumulatorProof"),
+            SparseMerkleProof::from_bytes(&proof_bytes)
+                .expect("from_bytes: could not deserialize SparseMerkleProof"),
+        ),
+        None => AccountStateProof::from_bytes(&proof_bytes)
+            .expect("from_bytes: could not deserialize AccountStateProof")
+            .into_parts(),
+    };
+
+    // Verify transaction inclusion in the LedgerInfoWithSignatures
+    let transaction_hash = transaction.hash();
     let expected_root_hash = latest_li.ledger_info().transaction_accumulator_hash();
 
     transaction_proof
         .verify(expected_root_hash, transaction_hash, transaction_index)
         .expect("verify: could not verify proof");
-    latest_li
-        .verify_signatures(&validator_verifier)
-        .expect("verify_signatures: could not verify signatures");
-    let sparse_merkle_proof = SparseMerkleProof::from_bytes(&sparse_merkle_proof_bytes)
-        .expect("from_bytes: could not deserialize SparseMerkleProof");
-    let sparse_expected_root_hash = transaction
-        .state_checkpoint()
-        .expect("state_checkpoint: could not get state checkpoint");
+    // Each sibling consumes a bit of the key, whether it is an empty subtree or not
+    if !sparse_merkle_proof.has_consistent_depth() {
+        sp1_zkvm::io::commit(&InclusionStatus::InvalidProofDepth.to_u8());
+        return;
+    }
+    // Only transactions carrying a state checkpoint can be used to prove an account
+    // inclusion. For any other transaction, only commit the reason and stop there.
+    let Some(sparse_expected_root_hash) = transaction.state_checkpoint() else {
+        sp1_zkvm::io::commit(&InclusionStatus::NotACheckpoint.to_u8());
+        return;
+    };
     let reconstructed_root_hash = sparse_merkle_proof
         .verify_by_hash(
             sparse_expected_root_hash,
             HashValue::from_slice(key).expect("key: could not use input to create HashValue"),
-            HashValue::from_slice(leaf_value_hash)
-                .expect("leaf_value_hash: could not use input to create HashValue"),
+            leaf_value_hash,
         )
         .expect("verify_by_hash: could not verify proof");
+    // Only commit what the proof actually covers, rather than echoing the inputs
+    let proved_leaf = sparse_merkle_proof
+        .leaf()
+        .expect("leaf: verified proof should carry a leaf");
+    println!("cycle-tracker-report-end: verify_proofs");
+
+    sp1_zkvm::io::commit(&InclusionStatus::Included.to_u8());
 
     sp1_zkvm::io::commit(validator_verifier.hash().as_ref());
 
     // Commit the state root hash
     sp1_zkvm::io::commit(reconstructed_root_hash.as_ref());
 
-    // Commit current block id
-    let block_hash = latest_li.ledger_info().block_id();
+    // Commit the block id of the signed ledger info
+    let block_hash = signed_li.ledger_info().block_id();
     sp1_zkvm::io::commit(block_hash.as_ref());
 
-    // Commit key
-    sp1_zkvm::io::commit(&key);
+    // Commit the key of the proved leaf
+    sp1_zkvm::io::commit(proved_leaf.key().as_ref());
+
+    // Commit the value hash of the proved leaf
+    sp1_zkvm::io::commit(proved_leaf.value_hash().as_ref());
+
+    // Commit the kind of the proved leaf
+    sp1_zkvm::io::commit(&leaf_kind.to_u8());
+
+    // Commit the number of leaves of the accumulator, one per transaction up to the signed
+    // ledger info, to be checked against the height of the chain
+    let accumulator_leaves: u64 = signed_li.ledger_info().version() + 1;
+    sp1_zkvm::io::commit(&accumulator_leaves);
+
+    // Commit the epoch of the validator verifier, so that verifiers can reject proofs
+    // signed by the validators of a stale epoch
+    sp1_zkvm::io::commit(&signed_li.ledger_info().epoch());
+
+    // Commit the index of the candidate validator verifier the signatures verified under
+    sp1_zkvm::io::commit(&validator_verifier_index);
+
+    // Commit the hash of the transaction, so that the proof can be tied to it
+    sp1_zkvm::io::commit(transaction_hash.as_ref());
+
+    // Commit the preimage of the key, checked above, or zeros if none was given
+    let (has_key_preimage, (address, path)) = match key_preimage {
+        Some(key_preimage) => (1u8, key_preimage),
+        None => (0u8, ([0; 32], vec![])),
+    };
+    sp1_zkvm::io::commit(&has_key_preimage);
+    sp1_zkvm::io::commit(&address);
+    sp1_zkvm::io::commit(&path);
+
+    // Commit whether the signatures were skipped, so that such proofs can not be mistaken
+    // for production ones
+    sp1_zkvm::io::commit(&u8::from(cfg!(feature = "skip-signatures")));
+
+    // Commit the timestamp of the signed ledger info, so that verifiers can reject proofs
+    // anchored to a stale one
+    sp1_zkvm::io::commit(&signed_li.ledger_info().timestamp_usecs());
+}
+
+/// Proves that several transactions are in the accumulator of a single ledger info, whose
+/// signatures are only verified once. They are verified even with the `skip-signatures`
+/// feature, as the outputs of this mode can not tell they were skipped.
+fn prove_transactions() {
+    sp1_zkvm::io::commit(&TRANSACTIONS_OUTPUT_MAGIC);
+
+    // Each transaction comes with its index and its proof in the accumulator
+    let transactions: Vec<(Vec<u8>, u64, Vec<u8>)> = sp1_zkvm::io::read();
+    let ledger_info_bytes = sp1_zkvm::io::read_vec();
+    let verified_validator_verifier = sp1_zkvm::io::read_vec();
+
+    // An empty list would otherwise be committed as included
+    if transactions.is_empty() {
+        sp1_zkvm::io::commit(&InclusionStatus::EmptyBatch.to_u8());
+        return;
+    }
+
+    let Some((latest_li, validator_verifier)) = verify_latest_li(
+        &ledger_info_bytes,
+        &verified_validator_verifier,
+        transactions
+            .iter()
+            .map(|(_, transaction_index, _)| *transaction_index),
+    ) else {
+        return;
+    };
+
+    println!("cycle-tracker-report-start: verify_proofs");
+    let expected_root_hash = latest_li.ledger_info().transaction_accumulator_hash();
+    let transaction_hashes: Vec<[u8; 32]> = transactions
+        .iter()
+        .map(|(transaction_bytes, transaction_index, proof_bytes)| {
+            let transaction_hash = TransactionInfo::from_bytes(transaction_bytes)
+                .expect("from_bytes: could not deserialize TransactionInfo")
+                .hash();
+            TransactionAccumulatorProof::from_bytes(proof_bytes)
+                .expect("from_bytes: could not deserialize TransactionAccumulatorProof")
+                .verify(expected_root_hash, transaction_hash, *transaction_index)
+                .expect("verify: could not verify proof");
+            *transaction_hash.as_ref()
+        })
+        .collect();
+    println!("cycle-tracker-report-end: verify_proofs");
+
+    commit_included(&validator_verifier, &latest_li);
+
+    // Commit the hashes of the proven transactions, in the order they were given
+    sp1_zkvm::io::commit(&transaction_hashes);
+}
+
+/// Proves that an event was emitted by a transaction of the accumulator of a ledger info.
+/// Unlike a state value, proven against the state checkpoint of a transaction, the event is
+/// proven against the event root hash of the transaction that emitted it. Like for several
+/// transactions, the signatures are verified even with the `skip-signatures` feature.
+fn prove_event() {
+    sp1_zkvm::io::commit(&EVENT_OUTPUT_MAGIC);
+
+    let event_bytes = sp1_zkvm::io::read_vec();
+    let event_index: u64 = sp1_zkvm::io::read();
+    let event_proof_bytes = sp1_zkvm::io::read_vec();
+    let transaction_bytes = sp1_zkvm::io::read_vec();
+    let transaction_index: u64 = sp1_zkvm::io::read();
+    let transaction_proof_bytes = sp1_zkvm::io::read_vec();
+    let ledger_info_bytes = sp1_zkvm::io::read_vec();
+    let verified_validator_verifier = sp1_zkvm::io::read_vec();
+
+    let Some((latest_li, validator_verifier)) = verify_latest_li(
+        &ledger_info_bytes,
+        &verified_validator_verifier,
+        [transaction_index],
+    ) else {
+        return;
+    };
+
+    println!("cycle-tracker-report-start: verify_proofs");
+    let transaction = TransactionInfo::from_bytes(&transaction_bytes)
+        .expect("from_bytes: could not deserialize TransactionInfo");
+    let transaction_hash = transaction.hash();
+    TransactionAccumulatorProof::from_bytes(&transaction_proof_bytes)
+        .expect("from_bytes: could not deserialize TransactionAccumulatorProof")
+        .verify(
+            latest_li.ledger_info().transaction_accumulator_hash(),
+            transaction_hash,
+            transaction_index,
+        )
+        .expect("verify: could not verify proof");
+
+    // The event is hashed here rather than trusted, so that its hash commits to its bytes
+    let event_hash = contract_event_hash(&event_bytes);
+    EventAccumulatorProof::from_bytes(&event_proof_bytes)
+        .expect("from_bytes: could not deserialize EventAccumulatorProof")
+        .verify(transaction.event_root_hash(), event_hash, event_index)
+        .expect("verify: could not verify event proof");
+    println!("cycle-tracker-report-end: verify_proofs");
+
+    commit_included(&validator_verifier, &latest_li);
+
+    // Commit the hash of the transaction that emitted the event, and the hash of the event
+    sp1_zkvm::io::commit(transaction_hash.as_ref());
+    sp1_zkvm::io::commit(event_hash.as_ref());
+}
+
+/// Proves that several resources are in the state checkpoint of a single transaction of the
+/// accumulator of a ledger info. Rather than each leaf, the program commits their number and
+/// the root folding them. Like for several transactions, the signatures are verified even
+/// with the `skip-signatures` feature.
+fn prove_batch() {
+    sp1_zkvm::io::commit(&BATCH_OUTPUT_MAGIC);
+
+    // Each leaf comes with its key, the hash of its value and its sparse Merkle proof
+    let leaves: Vec<([u8; 32], [u8; 32], Vec<u8>)> = sp1_zkvm::io::read();
+    let transaction_bytes = sp1_zkvm::io::read_vec();
+    let transaction_index: u64 = sp1_zkvm::io::read();
+    let transaction_proof_bytes = sp1_zkvm::io::read_vec();
+    let ledger_info_bytes = sp1_zkvm::io::read_vec();
+    let verified_validator_verifier = sp1_zkvm::io::read_vec();
+
+    // An empty batch would otherwise be committed as included
+    if leaves.is_empty() {
+        sp1_zkvm::io::commit(&InclusionStatus::EmptyBatch.to_u8());
+        return;
+    }
+
+    let Some((latest_li, validator_verifier)) = verify_latest_li(
+        &ledger_info_bytes,
+        &verified_validator_verifier,
+        [transaction_index],
+    ) else {
+        return;
+    };
+
+    println!("cycle-tracker-report-start: verify_proofs");
+    let transaction = TransactionInfo::from_bytes(&transaction_bytes)
+        .expect("from_bytes: could not deserialize TransactionInfo");
+    TransactionAccumulatorProof::from_bytes(&transaction_proof_bytes)
+        .expect("from_bytes: could not deserialize TransactionAccumulatorProof")
+        .verify(
+            latest_li.ledger_info().transaction_accumulator_hash(),
+            transaction.hash(),
+            transaction_index,
+        )
+        .expect("verify: could not verify proof");
+    let Some(state_root_hash) = transaction.state_checkpoint() else {
+        sp1_zkvm::io::commit(&InclusionStatus::NotACheckpoint.to_u8());
+        return;
+    };
+
+    let mut proved_leaves = Vec::with_capacity(leaves.len());
+    for (key, leaf_value_hash, proof_bytes) in &leaves {
+        let sparse_merkle_proof = SparseMerkleProof::from_bytes(proof_bytes)
+            .expect("from_bytes: could not deserialize SparseMerkleProof");
+        if !sparse_merkle_proof.has_consistent_depth() {
+            sp1_zkvm::io::commit(&InclusionStatus::InvalidProofDepth.to_u8());
+            return;
+        }
+        sparse_merkle_proof
+            .verify_by_hash(
+                state_root_hash,
+                HashValue::from_slice(key).expect("key: could not use input to create HashValue"),
+                HashValue::new(*leaf_value_hash),
+            )
+            .expect("verify_by_hash: could not verify proof");
+        // Only fold what the proof actually covers, rather than the inputs
+        let proved_leaf = sparse_merkle_proof
+            .leaf()
+            .expect("leaf: verified proof should carry a leaf");
+        proved_leaves.push((
+            *proved_leaf.key().as_ref(),
+            *proved_leaf.value_hash().as_ref(),
+        ));
+    }
+    // The leaves are committed in the canonical order of their keys, ascending, whatever
+    // the order they were given in
+    let accumulator_root = inclusion_accumulator_root(&proved_leaves);
+    println!("cycle-tracker-report-end: verify_proofs");
+    // As a set, so that a leaf given twice is not folded twice
+    let Some(accumulator_root) = accumulator_root else {
+        sp1_zkvm::io::commit(&InclusionStatus::DuplicateKey.to_u8());
+        return;
+    };
+
+    commit_included(&validator_verifier, &latest_li);
+
+    // Commit the state root hash the leaves were proven against
+    sp1_zkvm::io::commit(state_root_hash.as_ref());
+
+    // Commit the number of proven leaves, and the root folding them
+    sp1_zkvm::io::commit(&(proved_leaves.len() as u64));
+    sp1_zkvm::io::commit(accumulator_root.as_ref());
+}
+
+/// Verifies the signatures of the ledger info the modes proving several values are anchored
+/// to, and that it is not older than the transactions of the given indices. On a failure,
+/// the status telling why is committed and `None` is returned.
+fn verify_latest_li(
+    ledger_info_bytes: &[u8],
+    validator_verifier_bytes: &[u8],
+    transaction_indices: impl IntoIterator<Item = u64>,
+) -> Option<(LedgerInfoWithSignatures, ValidatorVerifier)> {
+    let validator_verifier = ValidatorVerifier::from_bytes(validator_verifier_bytes)
+        .expect("validator_verifier: could not create ValidatorVerifier from bytes");
+    if !validator_verifier.is_quorum_reachable() {
+        sp1_zkvm::io::commit(&InclusionStatus::UnsatisfiableQuorum.to_u8());
+        return None;
+    }
+    let latest_li = LedgerInfoWithSignatures::from_bytes(ledger_info_bytes)
+        .expect("from_bytes: could not deserialize LedgerInfo");
+
+    match latest_li.verify_signatures(&validator_verifier) {
+        Err(VerifyError::TooLittleVotingPower { .. }) => {
+            sp1_zkvm::io::commit(&InclusionStatus::InsufficientVotingPower.to_u8());
+            return None;
+        }
+        result => result.expect("verify_signatures: could not verify signatures"),
+    }
+    let version = latest_li.ledger_info().version();
+    if transaction_indices
+        .into_iter()
+        .any(|transaction_index| transaction_index > version)
+    {
+        sp1_zkvm::io::commit(&InclusionStatus::NewerThanLedgerInfo.to_u8());
+        return None;
+    }
+
+    Some((latest_li, validator_verifier))
+}
+
+/// Commits the `Included` status, followed by the hash of the validator verifier and the
+/// block id of the ledger info, as the modes proving several values start their outputs.
+fn commit_included(validator_verifier: &ValidatorVerifier, latest_li: &LedgerInfoWithSignatures) {
+    sp1_zkvm::io::commit(&InclusionStatus::Included.to_u8());
+
+    sp1_zkvm::io::commit(validator_verifier.hash().as_ref());
 
-    // Commit leaf value hash
-    sp1_zkvm::io::commit(&leaf_value_hash);
+    // Commit the block id of the ledger info
+    sp1_zkvm::io::commit(latest_li.ledger_info().block_id().as_ref());
 }
@@ -0,0 +1,35 @@
+#![no_main]
+
+use aptos_lc_core::crypto::hash::CryptoHash;
+use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::output::SIGNATURE_OUTPUT_MAGIC;
+use aptos_lc_core::types::status::SignatureStatus;
+use aptos_lc_core::types::validator::ValidatorVerifier;
+
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    // Commit the program magic, so that the outputs can not be mistaken for another program's
+    sp1_zkvm::io::commit(&SIGNATURE_OUTPUT_MAGIC);
+
+    let ledger_info_bytes = sp1_zkvm::io::read_vec();
+    let verified_validator_verifier = sp1_zkvm::io::read_vec();
+
+    let validator_verifier = ValidatorVerifier::from_bytes(&verified_validator_verifier)
+        .expect("validator_verifier: could not create ValidatorVerifier from bytes");
+    let ledger_info = LedgerInfoWithSignatures::from_bytes(&ledger_info_bytes)
+        .expect("from_bytes: could not deserialize LedgerInfo");
+
+    // If the signatures do not verify, only commit the status and stop there.
+    if ledger_info.verify_signatures(&validator_verifier).is_err() {
+        sp1_zkvm::io::commit(&SignatureStatus::InvalidSignatures.to_u8());
+        return;
+    }
+    sp1_zkvm::io::commit(&SignatureStatus::Verified.to_u8());
+
+    sp1_zkvm::io::commit(validator_verifier.hash().as_ref());
+
+    // Commit the block id of the signed ledger info
+    let block_hash = ledger_info.ledger_info().block_id();
+    sp1_zkvm::io::commit(block_hash.as_ref());
+}
@@ -1,7 +1,107 @@
-use sp1_helper::build_program_with_args;
+use std::fs;
+use std::path::Path;
+
+use sp1_helper::{build_program_with_args, BuildArgs};
+
+/// Target the guests are built for, which also names the ELF files they are embedded from.
+const GUEST_TARGET: &str = "riscv32im-succinct-zkvm-elf";
+
+/// `e_machine` of the ELF files built for RISC-V.
+const EM_RISCV: u16 = 0xf3;
+
+/// Bit of `e_flags` set in the ELF files using the compressed instructions.
+const EF_RISCV_RVC: u32 = 0x1;
+
+/// Suffix of the ELF of the inclusion program built with its insecure `skip-signatures`
+/// feature, kept apart from the production one.
+const SKIP_SIGNATURES_SUFFIX: &str = "-skip-signatures";
 
 fn main() {
     build_program_with_args("../programs/epoch-change", Default::default());
-    build_program_with_args("../programs/inclusion", Default::default())
+    build_program_with_args("../programs/inclusion", Default::default());
+    build_program_with_args("../programs/signature", Default::default());
+
+    // Only built on request, so that the production ELF is never replaced by it
+    if std::env::var_os("CARGO_FEATURE_SKIP_SIGNATURES").is_some() {
+        build_program_with_args(
+            "../programs/inclusion",
+            BuildArgs {
+                features: vec!["skip-signatures".to_string()],
+                elf_name: format!("{GUEST_TARGET}{SKIP_SIGNATURES_SUFFIX}"),
+                ..Default::default()
+            },
+        );
+        check_guest_target(
+            &Path::new("../programs/inclusion/elf")
+                .join(format!("{GUEST_TARGET}{SKIP_SIGNATURES_SUFFIX}")),
+        );
+    }
+
+    for program in ["epoch-change", "inclusion", "signature"] {
+        check_guest_target(
+            &Path::new("../programs")
+                .join(program)
+                .join("elf")
+                .join(GUEST_TARGET),
+        );
+    }
+    println!("cargo:rustc-env=APTOS_LC_GUEST_TARGET={GUEST_TARGET}");
+}
+
+/// Fails the build if the embedded ELF of a guest was not built for `GUEST_TARGET`, as its
+/// proofs would not be reproducible.
+fn check_guest_target(path: &Path) {
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    // A missing ELF is reported by `include_bytes!`, and an empty one is loaded at runtime
+    let elf = match fs::read(path) {
+        Ok(elf) if !elf.is_empty() => elf,
+        _ => return,
+    };
+    match detect_target(&elf) {
+        Ok(target) if target == GUEST_TARGET => (),
+        Ok(target) => panic!(
+            "{} was built for {target} rather than {GUEST_TARGET}",
+            path.display()
+        ),
+        Err(reason) => panic!(
+            "{} was not built for {GUEST_TARGET}: {reason}",
+            path.display()
+        ),
+    }
+}
+
+/// Returns the target an ELF was built for, as read from its header.
+fn detect_target(elf: &[u8]) -> Result<String, String> {
+    if elf.len() < 52 || elf[..4] != *b"\x7fELF" {
+        return Err("not an ELF file".to_string());
+    }
+    // The 64 bits header is longer, with `e_flags` further
+    let (arch, flags_offset) = match elf[4] {
+        1 => ("riscv32", 36),
+        2 if elf.len() >= 64 => ("riscv64", 48),
+        class => return Err(format!("unsupported ELF class {class}")),
+    };
+    if elf[5] != 1 {
+        return Err("not a little-endian ELF file".to_string());
+    }
+
+    let machine = u16::from_le_bytes([elf[18], elf[19]]);
+    if machine != EM_RISCV {
+        return Err(format!(
+            "built for the machine {machine:#x} rather than RISC-V"
+        ));
+    }
+    let flags = u32::from_le_bytes(
+        elf[flags_offset..flags_offset + 4]
+            .try_into()
+            .expect("the slice is 4 bytes long"),
+    );
+    let extensions = if flags & EF_RISCV_RVC == 0 {
+        "im"
+    } else {
+        "imc"
+    };
 
+    Ok(format!("{arch}{extensions}-succinct-zkvm-elf"))
 }
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 use aptos_lc_core::crypto::hash::HashValue;
 use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
@@ -6,6 +8,12 @@ use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
 use aptos_lc_core::types::transaction::TransactionInfo;
 use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState};
 use aptos_lc_core::types::validator::ValidatorVerifier;
+use aptos_types::account_address::AccountAddress;
+#[cfg(feature = "rpc")]
+use sp1_sdk::SP1ProofWithPublicValues;
+use crate::error::LightClientError;
+#[cfg(feature = "rpc")]
+use crate::inclusion::{account_state_key, InclusionOutput};
 use crate::inclusion::{SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets};
 use crate::types::{EpochChangeData, InclusionData};
 
@@ -82,6 +90,12 @@ pub struct AccountInclusionProofResponse {
     validator_verifier: ValidatorVerifier,
 }
 
+impl AccountInclusionProofResponse {
+    pub const fn element_hash(&self) -> &HashValue {
+        &self.element_hash
+    }
+}
+
 impl From<AccountInclusionProofResponse> for InclusionData {
     fn from(val: AccountInclusionProofResponse) -> Self {
         InclusionData {
@@ -101,4 +115,274 @@ impl From<AccountInclusionProofResponse> for InclusionData {
             ),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Error code returned by an Aptos node for a ledger version it does not have.
+#[cfg(feature = "rpc")]
+const VERSION_NOT_FOUND_ERROR_CODE: &str = "version_not_found";
+
+/// Payload of the errors returned by the endpoints of an Aptos node.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Deserialize)]
+struct AptosErrorResponse {
+    error_code: String,
+}
+
+/// Parses the address of an account, as written on explorers.
+///
+/// # Arguments
+///
+/// * `input` - The address, in hex behind a `0x` prefix with its leading zeros possibly
+///   omitted, e.g. `0x1`, or as the full 64 hex digits.
+///
+/// # Returns
+///
+/// A `Result` containing the `AccountAddress`, or `Err` if the input is not an address.
+pub fn parse_account_address(input: &str) -> Result<AccountAddress, LightClientError> {
+    AccountAddress::from_str(input).map_err(|err| LightClientError::InvalidInput {
+        input: "account address".to_string(),
+        reason: format!("'{input}' is not a valid address: {err}"),
+    })
+}
+
+/// Fetches the proof of the current state of an account from the endpoint
+/// `/v1/accounts/:address/proof` of an Aptos node.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client to query the node with.
+/// * `rpc_url` - The base URL of the node, e.g. `http://127.0.0.1:8080`.
+/// * `account` - The address of the account.
+///
+/// # Returns
+///
+/// A `Result` containing the `AccountInclusionProofResponse`, or `Err` if the node could
+/// not be queried or if its response could not be deserialized.
+#[cfg(feature = "rpc")]
+pub async fn fetch_account_inclusion_proof(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    account: AccountAddress,
+) -> Result<AccountInclusionProofResponse, LightClientError> {
+    fetch_account_inclusion_proof_at(client, rpc_url, account, None).await
+}
+
+/// Same as `fetch_account_inclusion_proof`, but for the state of the account at a given
+/// ledger version rather than the latest one.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client to query the node with.
+/// * `rpc_url` - The base URL of the node, e.g. `http://127.0.0.1:8080`.
+/// * `account` - The address of the account.
+/// * `ledger_version` - The ledger version to prove the state at, or `None` for the latest.
+///
+/// # Returns
+///
+/// A `Result` containing the `AccountInclusionProofResponse`, or `Err` if the node does
+/// not have the ledger version yet, if it could not be queried or if its response could
+/// not be deserialized.
+#[cfg(feature = "rpc")]
+pub async fn fetch_account_inclusion_proof_at(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    account: AccountAddress,
+    ledger_version: Option<u64>,
+) -> Result<AccountInclusionProofResponse, LightClientError> {
+    let endpoint = format!(
+        "{}/v1/accounts/{}/proof",
+        rpc_url.trim_end_matches('/'),
+        account.to_hex_literal()
+    );
+    let rpc_error = |err: reqwest::Error| LightClientError::RpcError {
+        endpoint: endpoint.clone(),
+        source: err.into(),
+    };
+
+    let mut request = client
+        .get(&endpoint)
+        .header("Accept", "application/octet-stream");
+    if let Some(ledger_version) = ledger_version {
+        request = request.query(&[("ledger_version", ledger_version)]);
+    }
+    let response = request.send().await.map_err(rpc_error)?;
+    let status = response.status();
+    let response_bytes = response.bytes().await.map_err(rpc_error)?;
+    if !status.is_success() {
+        if let (Some(version), true) = (ledger_version, is_version_not_found(&response_bytes)) {
+            return Err(LightClientError::VersionNotAvailable { version });
+        }
+        return Err(LightClientError::RpcError {
+            endpoint,
+            source: format!("unexpected status {status}").into(),
+        });
+    }
+
+    bcs::from_bytes(&response_bytes).map_err(|err| LightClientError::DeserializationError {
+        structure: "AccountInclusionProofResponse".to_string(),
+        source: err.into(),
+    })
+}
+
+/// Tells whether the body of an error response of an Aptos node reports a missing ledger
+/// version.
+#[cfg(feature = "rpc")]
+fn is_version_not_found(body: &[u8]) -> bool {
+    serde_json::from_slice::<AptosErrorResponse>(body)
+        .is_ok_and(|response| response.error_code == VERSION_NOT_FOUND_ERROR_CODE)
+}
+
+/// Checks that an inclusion proof commits the current on-chain state of an account, as
+/// returned by an Aptos node.
+///
+/// The proof itself is not verified, which is left to the caller.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client to query the node with.
+/// * `rpc_url` - The base URL of the node, e.g. `http://127.0.0.1:8080`.
+/// * `account` - The address of the account.
+/// * `proof` - The proof of the inclusion program for the account.
+///
+/// # Returns
+///
+/// A `Result` containing `true` if the proof commits the current state value hash of the
+/// account, `false` if it commits another value or another account, or `Err` if the node
+/// could not be queried or if the proof does not carry an `InclusionOutput`.
+#[cfg(feature = "rpc")]
+pub async fn verify_matches_onchain(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    account: AccountAddress,
+    proof: &SP1ProofWithPublicValues,
+) -> Result<bool, LightClientError> {
+    let output = InclusionOutput::from_public_values(&mut proof.public_values.clone())?;
+    // A proof for another account can not vouch for this one, whatever its value
+    if output.key() != &account_state_key(account) {
+        return Ok(false);
+    }
+
+    let onchain = fetch_account_inclusion_proof(client, rpc_url, account).await?;
+
+    Ok(output.value() == onchain.element_hash().as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "rpc")]
+    use crate::inclusion::{generate_keys, ProvingAssets, INCLUSION_ELF};
+    #[cfg(feature = "rpc")]
+    use sp1_sdk::ProverClient;
+
+    /// Base URL of the node queried by the tests.
+    #[cfg(feature = "rpc")]
+    const RPC_URL_ENV: &str = "APTOS_RPC_URL";
+
+    /// Public devnet node, queried when `APTOS_RPC_URL` is not set.
+    #[cfg(feature = "rpc")]
+    const DEVNET_RPC_URL: &str = "https://api.devnet.aptoslabs.com";
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    #[ignore = "queries a live Aptos node"]
+    async fn test_verify_matches_onchain() {
+        let rpc_url = std::env::var(RPC_URL_ENV).unwrap_or_else(|_| DEVNET_RPC_URL.to_string());
+        let client = reqwest::Client::new();
+        let account = AccountAddress::ONE;
+
+        let inclusion_data: InclusionData =
+            fetch_account_inclusion_proof(&client, &rpc_url, account)
+                .await
+                .unwrap()
+                .into();
+        let stdin = ProvingAssets::from_inclusion_data(inclusion_data)
+            .unwrap()
+            .stdin()
+            .unwrap();
+        let prover_client = ProverClient::mock();
+        let (pk, _) = generate_keys(&prover_client).unwrap();
+        let proof = prover_client.prove(&pk, stdin).run().unwrap();
+
+        assert!(verify_matches_onchain(&client, &rpc_url, account, &proof)
+            .await
+            .unwrap());
+        // The proof is for the account at 0x1 only
+        assert!(
+            !verify_matches_onchain(&client, &rpc_url, AccountAddress::TWO, &proof)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_account_address() {
+        assert_eq!(parse_account_address("0x1").unwrap(), AccountAddress::ONE);
+        assert_eq!(
+            parse_account_address(&format!("{}2", "0".repeat(63))).unwrap(),
+            AccountAddress::TWO
+        );
+
+        let too_long = format!("0x{}", "1".repeat(65));
+        for input in ["", "0x", "1", "0xg1", too_long.as_str()] {
+            assert!(matches!(
+                parse_account_address(input),
+                Err(LightClientError::InvalidInput { .. })
+            ));
+        }
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn test_is_version_not_found() {
+        assert!(is_version_not_found(
+            br#"{"message":"Ledger version not found","error_code":"version_not_found","vm_error_code":null}"#
+        ));
+        assert!(!is_version_not_found(
+            br#"{"message":"Account not found","error_code":"account_not_found","vm_error_code":null}"#
+        ));
+        assert!(!is_version_not_found(b"not found"));
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    #[ignore = "queries a live Aptos node"]
+    async fn test_prove_devnet_account_inclusion() {
+        let rpc_url = std::env::var(RPC_URL_ENV).unwrap_or_else(|_| DEVNET_RPC_URL.to_string());
+        let client = reqwest::Client::new();
+        let account = AccountAddress::ONE;
+
+        let inclusion_data: InclusionData =
+            fetch_account_inclusion_proof_at(&client, &rpc_url, account, None)
+                .await
+                .unwrap()
+                .into();
+        let stdin = ProvingAssets::from_inclusion_data(inclusion_data)
+            .unwrap()
+            .stdin()
+            .unwrap();
+        let (mut public_values, _) = ProverClient::mock()
+            .execute(INCLUSION_ELF, stdin)
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(output.key(), &account_state_key(account));
+
+        assert!(matches!(
+            fetch_account_inclusion_proof_at(&client, &rpc_url, account, Some(u64::MAX)).await,
+            Err(LightClientError::VersionNotAvailable { version: u64::MAX })
+        ));
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_fetch_account_inclusion_proof_unreachable() {
+        // Nothing listens on the discard port of the loopback interface
+        let client = reqwest::Client::new();
+
+        assert!(matches!(
+            fetch_account_inclusion_proof(&client, "http://127.0.0.1:9", AccountAddress::ONE).await,
+            Err(LightClientError::RpcError { .. })
+        ));
+    }
+}
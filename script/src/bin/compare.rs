@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use aptos_lc_script::metrics::{compare_metrics, read_metrics, MetricsComparison};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Metrics of the reference run, as written by the inclusion benchmark with
+    /// `--metrics-out`.
+    before: PathBuf,
+
+    /// Metrics of the run to compare to the reference.
+    after: PathBuf,
+
+    /// Largest accepted increase of a metric, in percent. The process exits with a
+    /// nonzero code if any metric of a number of leaves increased by more.
+    #[clap(long, value_name = "PERCENT", default_value_t = 5.0)]
+    threshold: f64,
+}
+
+/// Formats the comparison, one line per number of leaves measured by both runs, flagging
+/// the regressions above `threshold`.
+fn format_comparison(comparison: &MetricsComparison, threshold: f64) -> String {
+    comparison
+        .deltas()
+        .iter()
+        .map(|delta| {
+            let flag = if delta.is_regression(threshold) {
+                " REGRESSION"
+            } else {
+                ""
+            };
+            format!(
+                "{} leaves: proving {:+.1}%, verifying {:+.1}%, cycles {:+.1}%{flag}",
+                delta.nbr_leaves(),
+                delta.proving_time(),
+                delta.verifying_time(),
+                delta.cycles()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let read = |path: &PathBuf| {
+        read_metrics(path).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        })
+    };
+    let comparison = compare_metrics(&read(&args.before), &read(&args.after));
+
+    // The leaf sizes of a single run are reported, but do not fail the comparison
+    for nbr_leaves in comparison.only_before() {
+        eprintln!("Warning: {nbr_leaves} leaves are only measured by the first run");
+    }
+    for nbr_leaves in comparison.only_after() {
+        eprintln!("Warning: {nbr_leaves} leaves are only measured by the second run");
+    }
+
+    println!("{}", format_comparison(&comparison, args.threshold));
+    if comparison
+        .deltas()
+        .iter()
+        .any(|delta| delta.is_regression(args.threshold))
+    {
+        eprintln!("Error: a metric regressed by more than {}%", args.threshold);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_lc_script::metrics::BenchMetrics;
+
+    #[test]
+    fn test_format_comparison() {
+        let comparison = compare_metrics(
+            &[
+                BenchMetrics::new(32, 1000, 100, 2000, 1024),
+                BenchMetrics::new(128, 2000, 100, 4000, 1024),
+            ],
+            &[
+                BenchMetrics::new(32, 900, 100, 2000, 1024),
+                BenchMetrics::new(128, 3000, 100, 4000, 1024),
+            ],
+        );
+
+        assert_eq!(
+            format_comparison(&comparison, 5.0),
+            "32 leaves: proving -10.0%, verifying +0.0%, cycles +0.0%\n\
+             128 leaves: proving +50.0%, verifying +0.0%, cycles +0.0% REGRESSION"
+        );
+    }
+}
@@ -3,19 +3,45 @@
 //!
 //! You can run this script using the following command:
 //! ```shell
-//! RUST_LOG=info cargo run --release -- --execute
+//! cargo run --release -- --execute
 //! ```
 //! or
 //! ```shell
-//! RUST_LOG=info cargo run --release -- --prove
+//! cargo run --release -- --prove --log-level debug
 //! ```
 
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use clap::Parser;
-use sp1_sdk::{ProverClient, SP1Stdin};
+use serde::Serialize;
+use sp1_sdk::{
+    ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues, SP1Stdin,
+    SP1VerifyingKey,
+};
 
-use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
 use aptos_lc_core::crypto::hash::CryptoHash;
 use aptos_lc_core::types::trusted_state::TrustedState;
+use aptos_lc_script::bundle::{build_info, ProofBundle, EPOCH_CHANGE_PROGRAM_ID};
+use aptos_lc_script::client::{
+    execute_and_prove, proof_size_bytes, prover_client, public_values_digest, set_prover_threads,
+    verify_timed, ProofType,
+};
+use aptos_lc_script::epoch_change::{
+    ensure_epoch_state, generate_stdin, ratchet_trusted_state, EpochChangeOutput,
+};
+use aptos_lc_script::error::LightClientError;
+use aptos_lc_script::fixture::AptosWrapperFixture;
+use aptos_lc_script::io::{
+    check_vk_hash, dump_stdin, elf_dir_from_env, load_elf, load_or_setup_keys, load_stdin,
+    load_trusted_state, resolve_elf, save_proof, save_trusted_state, vk_to_bytes, ProofEncoding,
+};
+use aptos_lc_script::logger::{print_info, set_quiet, setup_logger, LogLevel};
+use aptos_lc_script::output::output_json;
+use aptos_lc_script::sink::OutputSink;
+use aptos_lc_script::types::EpochChangeData;
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const EPOCH_CHANGE_ELF: &[u8] = include_bytes!("../../../programs/epoch-change/elf/riscv32im-succinct-zkvm-elf");
@@ -30,11 +56,151 @@ struct Args {
     #[clap(long)]
     prove: bool,
 
+    /// Execute the program before proving it with the same inputs, printing the execution
+    /// report along with the proof.
+    #[clap(long, conflicts_with_all = ["execute", "prove"])]
+    execute_and_prove: bool,
+
+    /// Force local proving, failing if the environment selects another prover.
+    #[clap(long)]
+    local: bool,
+
+    /// Force local proving on GPU. Requires the `cuda` feature.
+    #[clap(long)]
+    gpu: bool,
+
+    /// Number of threads the local prover runs on, one per core by default. The proofs are
+    /// generated one after the other, each of them on all of these threads.
+    #[clap(long, value_name = "N")]
+    threads: Option<NonZeroUsize>,
+
+    /// Verbosity of the logs. No logger is installed when `off`.
+    #[clap(long, value_enum, value_name = "LEVEL", default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+
+    /// Only print the machine-readable results to stdout, such as the benchmark JSON, logging
+    /// the other messages instead.
+    #[clap(long)]
+    quiet: bool,
+
+    /// Print the outputs of the programs as indented JSON rather than on a single line.
+    #[clap(long)]
+    pretty: bool,
+
+    /// Write the machine-readable results to `PATH`, one per line, rather than to stdout.
+    #[clap(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Also write the results to stdout when they are written to a file with `--out`.
+    #[clap(long, requires = "out")]
+    stdout: bool,
+
+    /// Write the generated stdin to `PATH`.
+    #[clap(long, value_name = "PATH")]
+    dump_stdin: Option<PathBuf>,
+
+    /// Execute or prove the program directly from a stdin written with `--dump-stdin`.
+    #[clap(long, value_name = "PATH")]
+    stdin_in: Option<PathBuf>,
+
+    /// Write the generated proof to `PATH`.
+    #[clap(long, value_name = "PATH")]
+    save_proof: Option<PathBuf>,
+
+    /// Kind of proof to generate, e.g. `groth16` for a proof verifiable on-chain.
+    #[clap(long, value_enum, default_value_t = ProofType::Core)]
+    proof_type: ProofType,
+
+    /// Encoding of the proof written with `--save-proof`.
+    #[clap(long, value_enum, default_value_t = ProofEncoding::Bincode, requires = "save_proof")]
+    proof_encoding: ProofEncoding,
+
+    /// Write the generated proof to `PATH` as a bundle along with the hash of the verifying
+    /// key.
+    #[clap(long, value_name = "PATH", conflicts_with = "execute")]
+    bundle_out: Option<PathBuf>,
+
+    /// Verify the proof bundle written to `PATH` with `--bundle-out` against the program.
+    /// Can be used alone.
+    #[clap(long, value_name = "PATH")]
+    bundle_in: Option<PathBuf>,
+
+    /// Write the serialized verifying key of the program to `PATH`. Can be used alone.
+    #[clap(long, value_name = "PATH")]
+    export_vk: Option<PathBuf>,
+
+    /// Fail if the hash of the verifying key of the program differs from `HEX`. Can be
+    /// used alone.
+    #[clap(long, value_name = "HEX")]
+    compare_vk: Option<String>,
+
+    /// Print the versions of the crate and of SP1, and the hashes of the verifying keys of
+    /// the embedded programs, as JSON, then exit.
+    #[clap(long)]
+    build_info: bool,
+
+    /// Print the digest of the public values of the proof, as computed by the on-chain
+    /// verifiers.
+    #[clap(long)]
+    digest: bool,
+
+    /// Ratchet the trusted state written to `PATH` with `--trusted-state-out`, instead of
+    /// the one of the simulated chain.
+    #[clap(long, value_name = "PATH", conflicts_with = "stdin_in")]
+    trusted_state_in: Option<PathBuf>,
+
+    /// Write the ratcheted trusted state to `PATH` once proven, so that a later run can
+    /// resume from it with `--trusted-state-in`.
+    #[clap(long, value_name = "PATH", conflicts_with_all = ["stdin_in", "execute"])]
+    trusted_state_out: Option<PathBuf>,
+
+    /// Load the program from the ELF at `PATH` instead of the embedded one, e.g. to test
+    /// a freshly built guest.
+    #[clap(long, value_name = "PATH")]
+    elf: Option<PathBuf>,
+
+    /// Cache the keys of the program in `DIR`, so that they are only set up again when the
+    /// ELF changes.
+    #[clap(long, value_name = "DIR")]
+    key_cache: Option<PathBuf>,
+
+    /// Prove an epoch change for each of the comma-separated numbers of validators, printing
+    /// the timings of each. The signers keep the ratio of the default benchmark.
+    #[clap(
+        long,
+        value_name = "LIST",
+        value_delimiter = ',',
+        conflicts_with_all = ["execute", "stdin_in", "trusted_state_in", "trusted_state_out"]
+    )]
+    validators: Option<Vec<usize>>,
 }
 
 const NBR_VALIDATORS: usize = 130;
 const AVERAGE_SIGNERS_NBR: usize = 95;
 
+/// Returns the number of signers per block for a number of validators, in the same ratio as
+/// `AVERAGE_SIGNERS_NBR` to `NBR_VALIDATORS`, rounded up so that the signers keep a quorum.
+fn signers_nbr(nbr_validators: usize) -> Result<usize, LightClientError> {
+    let signers_nbr = (nbr_validators * AVERAGE_SIGNERS_NBR).div_ceil(NBR_VALIDATORS);
+    if signers_nbr >= nbr_validators {
+        return Err(LightClientError::InvalidInput {
+            input: "number of validators".to_string(),
+            reason: format!(
+                "{signers_nbr} signers would not be below the {nbr_validators} validators"
+            ),
+        });
+    }
+
+    Ok(signers_nbr)
+}
+
+#[derive(Serialize)]
+struct Timings {
+    nbr_validators: usize,
+    proving_time: u128,
+    verifying_time: u128,
+    proof_bytes: usize,
+}
 
 struct ProvingAssets {
     trusted_state: Vec<u8>,
@@ -44,77 +210,423 @@ struct ProvingAssets {
 
 impl ProvingAssets {
     /// Constructs a new instance of `ProvingAssets` by setting up the necessary state and proofs for the benchmark.
-    fn new() -> Self {
-        let mut aptos_wrapper = AptosWrapper::new(2, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
-
-        let trusted_state = bcs::to_bytes(aptos_wrapper.trusted_state()).unwrap();
-        let validator_verifier_hash = match TrustedState::from_bytes(&trusted_state).unwrap() {
-            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().hash().to_vec(),
-            _ => panic!("Expected epoch change for current trusted state"),
-        };
-        let trusted_state_version = *aptos_wrapper.current_version();
-
-        aptos_wrapper.generate_traffic().unwrap();
+    ///
+    /// The signers are `signers_nbr` of the `nbr_validators` validators. When a trusted
+    /// state is given, the simulated chain is first ratcheted to its epoch, and
+    /// the epoch change is proven from it rather than from the trusted state of the chain.
+    fn new(
+        trusted_state: Option<&TrustedState>,
+        nbr_validators: usize,
+    ) -> Result<Self, LightClientError> {
+        let mut fixture =
+            AptosWrapperFixture::new(2, nbr_validators, signers_nbr(nbr_validators)?)?;
+        if let Some(epoch) = trusted_state.and_then(TrustedState::epoch) {
+            while *fixture.aptos_wrapper().current_epoch() < epoch {
+                fixture.next_epoch_change_data()?;
+            }
+        }
 
-        let state_proof = aptos_wrapper
-            .new_state_proof(trusted_state_version)
-            .unwrap();
+        let validator_verifier_hash = fixture.validator_verifier()?.hash().to_vec();
+        let EpochChangeData {
+            trusted_state: chain_trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data()?;
 
-        let epoch_change_proof = &bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+        let trusted_state = trusted_state.map_or(chain_trusted_state, TrustedState::to_bytes);
+        // A waypoint would only fail once ratcheted, after the proof
+        ensure_epoch_state(&trusted_state)?;
 
-        Self {
+        Ok(Self {
             trusted_state,
             validator_verifier_hash,
-            epoch_change_proof: epoch_change_proof.clone(),
-        }
+            epoch_change_proof,
+        })
     }
 
-    fn prove(&self){
-        let client = ProverClient::new();
-        let mut stdin = SP1Stdin::new();
-        stdin.write_vec(self.trusted_state.clone());
-        stdin.write_vec(self.epoch_change_proof.clone());
-        let (pk, vk) = client.setup(EPOCH_CHANGE_ELF);
-        let _ = client
-            .prove(&pk, stdin)
+    fn stdin(&self) -> SP1Stdin {
+        generate_stdin(&self.trusted_state, &self.epoch_change_proof)
+    }
+}
+
+/// Sets up the keys of the program, through the cache when one is given.
+fn setup_keys(
+    client: &ProverClient,
+    elf: &[u8],
+    key_cache: Option<&Path>,
+) -> (SP1ProvingKey, SP1VerifyingKey) {
+    match key_cache {
+        Some(cache_dir) => load_or_setup_keys(client, elf, cache_dir).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }),
+        None => client.setup(elf),
+    }
+}
+
+fn prove(
+    client: &ProverClient,
+    pk: &SP1ProvingKey,
+    stdin: SP1Stdin,
+    proof_type: ProofType,
+    execute_first: bool,
+) -> SP1ProofWithPublicValues {
+    let proof = if execute_first {
+        let (report, _, proof) = execute_and_prove(client, pk, &stdin, proof_type, "epoch change")
+            .expect("failed to execute and generate proof");
+
+        // Record the report.
+        print_info(format_args!("Report: {}", report));
+        proof
+    } else {
+        proof_type
+            .apply(client.prove(pk, stdin))
             .run()
-            .expect("failed to generate proof");
+            .expect("failed to generate proof")
+    };
+
+    print_info(format_args!("Successfully generated proof!"));
+    proof
+}
+
+/// Writes the trusted state ratcheted by a proof, after checking it against the epoch
+/// committed by the program.
+fn write_trusted_state(
+    proving_assets: &ProvingAssets,
+    proof: &SP1ProofWithPublicValues,
+    path: &Path,
+) -> Result<u64, LightClientError> {
+    let output = EpochChangeOutput::from_public_values(&mut proof.public_values.clone())?;
+    let trusted_state = TrustedState::from_bytes(&proving_assets.trusted_state).map_err(|err| {
+        LightClientError::DeserializationError {
+            structure: "TrustedState".to_string(),
+            source: err.into(),
+        }
+    })?;
+
+    let new_state = ratchet_trusted_state(&trusted_state, &proving_assets.epoch_change_proof)?;
+    assert_eq!(
+        new_state.epoch(),
+        Some(*output.end_epoch()),
+        "Ratcheted epoch mismatch"
+    );
+    save_trusted_state(&new_state, path)?;
 
-        println!("Successfully generated proof!");
+    Ok(*output.end_epoch())
+}
+
+/// Proves and verifies an epoch change for each number of validators, with the same keys.
+///
+/// The program does not change along the sweep, so the client and the keys, which are the
+/// largest buffers, are set up once by the caller. The memory held by each count, the
+/// fixture, the stdin and the proof, is released before the next count is proven, so the
+/// peak memory is the one of the largest count rather than their sum.
+fn sweep_validators(
+    client: &ProverClient,
+    pk: &SP1ProvingKey,
+    vk: &SP1VerifyingKey,
+    validator_counts: &[usize],
+    proof_type: ProofType,
+    execute_first: bool,
+) -> Result<Vec<Timings>, LightClientError> {
+    // Checked first, so that an invalid count does not wait for the previous proofs
+    for &nbr_validators in validator_counts {
+        signers_nbr(nbr_validators)?;
     }
 
-    fn execute(&self) {
-        let client = ProverClient::new();
-        let mut stdin = SP1Stdin::new();
-        stdin.write_vec(self.trusted_state.clone());
-        stdin.write_vec(self.epoch_change_proof.clone());
+    validator_counts
+        .iter()
+        .map(|&nbr_validators| {
+            let _span = tracing::info_span!("epoch_change", nbr_validators).entered();
+            let stdin = ProvingAssets::new(None, nbr_validators)?.stdin();
 
-        let (_, report) = client.execute(EPOCH_CHANGE_ELF, stdin).run().unwrap();
+            let start_proving = Instant::now();
+            let proof = prove(client, pk, stdin, proof_type, execute_first);
+            let proving_time = start_proving.elapsed().as_millis();
+            let verifying_time = verify_timed(client, &proof, vk, "epoch change")?;
+            let proof_bytes = proof_size_bytes(&proof);
+            drop(proof);
 
-        // Record the report.
-        println!("Report: {}", report);
+            Ok(Timings {
+                nbr_validators,
+                proving_time,
+                verifying_time: verifying_time.as_millis(),
+                proof_bytes,
+            })
+        })
+        .collect()
+}
+
+fn execute(client: &ProverClient, elf: &[u8], stdin: SP1Stdin, pretty: bool) {
+    let (mut public_values, report) = client.execute(elf, stdin).run().unwrap();
+
+    // Record the report.
+    print_info(format_args!("Report: {}", report));
+    print_output(&mut public_values, pretty);
+}
+
+/// Prints the output of the program, or why it did not ratchet the trusted state.
+fn print_output(public_values: &mut SP1PublicValues, pretty: bool) {
+    match EpochChangeOutput::from_public_values(public_values) {
+        Ok(output) => print_info(output_json(&output, pretty)),
+        Err(err) => print_info(format_args!("No epoch change output: {err}")),
     }
 }
 
+fn write_bundle(proof: &SP1ProofWithPublicValues, vk: &SP1VerifyingKey, path: &Path) {
+    ProofBundle::new(EPOCH_CHANGE_PROGRAM_ID, vk, proof.clone())
+        .save(path)
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+    print_info(format_args!("Proof bundle written to {}", path.display()));
+}
 
-fn main() {
-    // Setup the logger.
-    sp1_sdk::utils::setup_logger();
+fn verify_bundle(client: &ProverClient, elf: &[u8], path: &Path) {
+    // The bundle is checked against the loaded program, which may not be the embedded one
+    let bundle = ProofBundle::load(path)
+        .and_then(|bundle| {
+            bundle.verify_with_vk(client, &client.setup(elf).1)?;
+            Ok(bundle)
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+    let output = EpochChangeOutput::from_public_values(&mut bundle.proof().public_values.clone())
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+    print_info(format_args!(
+        "Proof bundle verified for the change to epoch {}",
+        output.end_epoch()
+    ));
+}
+
+fn compare_vk(elf: &[u8], expected: &str) {
+    // The verifying key does not depend on the prover, so a mock client is enough
+    let (_, vk) = ProverClient::mock().setup(elf);
+    check_vk_hash(&vk, expected).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    });
+    print_info(format_args!("Verifying key hash matches {expected}"));
+}
+
+fn export_vk(client: &ProverClient, elf: &[u8], path: &Path) {
+    let vk_bytes = vk_to_bytes(&client.setup(elf).1).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    });
+    std::fs::write(path, vk_bytes).unwrap_or_else(|err| {
+        eprintln!("Error: could not write {}: {err}", path.display());
+        std::process::exit(1);
+    });
+    print_info(format_args!("Verifying key written to {}", path.display()));
+}
 
+/// Emits a result through the sink, exiting if it could not be written.
+fn emit(sink: &mut OutputSink, result: impl Display) {
+    sink.emit(result).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    });
+}
+
+fn main() {
     // Parse the command line arguments.
     let args = Args::parse();
 
-    if args.execute == args.prove {
-        eprintln!("Error: You must specify either --execute or --prove");
+    // Setup the logger.
+    set_quiet(args.quiet);
+    setup_logger(args.log_level);
+    let mut sink = OutputSink::from_flags(args.out.as_deref(), args.stdout).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    });
+
+    if args.build_info {
+        let build_info = build_info().unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+        emit(&mut sink, serde_json::to_string(&build_info).unwrap());
+        return;
+    }
+
+    let external_elf = args.elf.as_ref().map(|path| {
+        load_elf(path).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        })
+    });
+    let elf = match external_elf {
+        Some(elf) => Cow::Owned(elf),
+        None => resolve_elf(
+            EPOCH_CHANGE_ELF,
+            "epoch-change",
+            elf_dir_from_env().as_deref(),
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }),
+    };
+    let elf: &[u8] = &elf;
+
+    if let Some(expected) = &args.compare_vk {
+        compare_vk(elf, expected);
+    }
+
+    // `--export-vk`, `--compare-vk` and `--bundle-in` can be used without any other mode
+    let standalone = !args.execute
+        && !args.prove
+        && !args.execute_and_prove
+        && (args.export_vk.is_some() || args.compare_vk.is_some() || args.bundle_in.is_some());
+    if standalone && args.export_vk.is_none() && args.bundle_in.is_none() {
+        return;
+    }
+    if !standalone && !args.execute_and_prove && args.execute == args.prove {
+        eprintln!("Error: You must specify either --execute, --prove or --execute-and-prove");
+        std::process::exit(1);
+    }
+
+    set_prover_threads(args.threads);
+    let client = prover_client(args.local, args.gpu).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
         std::process::exit(1);
+    });
+
+    if let Some(path) = &args.export_vk {
+        export_vk(&client, elf, path);
+    }
+    if let Some(path) = &args.bundle_in {
+        verify_bundle(&client, elf, path);
+    }
+    if standalone {
+        return;
     }
-    let proving_assets = ProvingAssets::new();
 
+    let trusted_state = args.trusted_state_in.as_ref().map(|path| {
+        load_trusted_state(path).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        })
+    });
+
+    if let Some(validator_counts) = &args.validators {
+        let (pk, vk) = setup_keys(&client, elf, args.key_cache.as_deref());
+        let timings = sweep_validators(
+            &client,
+            &pk,
+            &vk,
+            validator_counts,
+            args.proof_type,
+            args.execute_and_prove,
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+        for timings in timings {
+            emit(&mut sink, serde_json::to_string(&timings).unwrap());
+        }
+        return;
+    }
+
+    let (stdin, proving_assets) = if let Some(path) = &args.stdin_in {
+        let stdin = load_stdin(path).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+        (stdin, None)
+    } else {
+        let proving_assets = ProvingAssets::new(trusted_state.as_ref(), NBR_VALIDATORS)
+            .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+        (proving_assets.stdin(), Some(proving_assets))
+    };
+
+    if let Some(path) = &args.dump_stdin {
+        dump_stdin(&stdin, path).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        });
+    }
 
     if args.execute {
         // Execute the program
-        proving_assets.execute();
+        execute(&client, elf, stdin, args.pretty);
     } else {
-        proving_assets.prove();
+        let (pk, vk) = setup_keys(&client, elf, args.key_cache.as_deref());
+        let proof = prove(&client, &pk, stdin, args.proof_type, args.execute_and_prove);
+        print_info(format_args!("Proof size: {} bytes", proof_size_bytes(&proof)));
+        print_output(&mut proof.public_values.clone(), args.pretty);
+        if args.digest {
+            let digest = public_values_digest(&proof.public_values);
+            emit(
+                &mut sink,
+                format_args!("Public values digest: 0x{}", hex::encode(digest)),
+            );
+        }
+        if let Some(path) = &args.save_proof {
+            save_proof(&proof, path, args.proof_encoding).unwrap_or_else(|err| {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            });
+            print_info(format_args!("Proof written to {}", path.display()));
+        }
+        if let Some(path) = &args.bundle_out {
+            write_bundle(&proof, &vk, path);
+        }
+        // The trusted state can only be written from generated assets
+        if let (Some(path), Some(proving_assets)) = (&args.trusted_state_out, &proving_assets) {
+            let epoch = write_trusted_state(proving_assets, &proof, path).unwrap_or_else(|err| {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            });
+            print_info(format_args!(
+                "Trusted state for epoch {epoch} written to {}",
+                path.display()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_signers_nbr() {
+        assert_eq!(signers_nbr(NBR_VALIDATORS).unwrap(), AVERAGE_SIGNERS_NBR);
+        assert_eq!(signers_nbr(4).unwrap(), 3);
+        for nbr_validators in [0, 1, 2, 3] {
+            assert!(matches!(
+                signers_nbr(nbr_validators),
+                Err(LightClientError::InvalidInput { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sweep_validators() {
+        let client = ProverClient::mock();
+        let (pk, vk) = client.setup(EPOCH_CHANGE_ELF);
+
+        let timings = sweep_validators(&client, &pk, &vk, &[4, 8], ProofType::Core, false).unwrap();
+        let validator_counts = timings
+            .iter()
+            .map(|timings| timings.nbr_validators)
+            .collect::<Vec<_>>();
+        assert_eq!(validator_counts, [4, 8]);
+
+        assert!(matches!(
+            sweep_validators(&client, &pk, &vk, &[4, 2], ProofType::Core, false),
+            Err(LightClientError::InvalidInput { .. })
+        ));
     }
 }
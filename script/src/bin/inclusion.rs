@@ -1,129 +1,364 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use serde::Serialize;
-use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1Stdin};
+use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
 
-use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
-use aptos_lc_core::crypto::hash::CryptoHash;
-use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
-use aptos_lc_core::types::trusted_state::TrustedState;
-use aptos_lc_core::types::validator::ValidatorVerifier;
+#[cfg(feature = "rpc")]
+use aptos_lc_script::aptos::fetch_account_inclusion_proof_at;
+use aptos_lc_script::aptos::parse_account_address;
+use aptos_lc_script::bundle::{build_info, ProofBundle, INCLUSION_PROGRAM_ID};
+use aptos_lc_script::client::{
+    check_prover_available, execute_and_prove, proof_size_bytes, prove_with_timeout, prover_client,
+    public_values_digest, set_prover_threads, setup_timed, verify_timed, ProofType,
+};
+use aptos_lc_script::epoch_change::EPOCH_CHANGE_ELF;
+use aptos_lc_script::error::LightClientError;
+use aptos_lc_script::fixture::AptosWrapperFixture;
 use aptos_lc_script::inclusion::{
-    SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets,
+    assert_accumulator_leaves, assert_block_in_checkpoints, assert_inclusion_output,
+    assert_transaction_hash, validate_inclusion_assets, InclusionOutput, ProvingAssets,
+    ValidatorVerifierAssets,
+};
+use aptos_lc_script::io::{
+    check_vk_hash, dump_stdin, elf_dir_from_env, inclusion_data_json_schema, load_checkpoints,
+    load_elf, load_or_setup_keys, load_stdin, load_validator_set, read_inclusion_data_json,
+    resolve_elf, save_proof, vk_to_bytes, ProofEncoding,
 };
+use aptos_lc_script::logger::{print_info, set_quiet, setup_logger, LogLevel};
+use aptos_lc_script::metrics::{write_metrics, BenchMetrics};
+use aptos_lc_script::output::{diff_public_values, output_json};
+use aptos_lc_script::sink::OutputSink;
+use aptos_lc_script::types::AccountAddress;
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const INCLUSION_ELF: &[u8] = include_bytes!("../../../programs/inclusion/elf/riscv32im-succinct-zkvm-elf");
 
 
+/// Each number of leaves must be between 1 and `fixture::MAX_PRACTICAL_NBR_ACCOUNTS`.
 const NBR_LEAVES: [usize; 5] = [32, 128, 2048, 8192, 32768];
 const NBR_VALIDATORS: usize = 130;
 const AVERAGE_SIGNERS_NBR: usize = 95;
 
-struct ProvingAssets {
-    sparse_merkle_proof_assets: SparseMerkleProofAssets,
-    transaction_proof_assets: TransactionProofAssets,
-    validator_verifier_assets: ValidatorVerifierAssets,
-    state_checkpoint_hash: [u8; 32],
+/// Set by the first interrupt of the benchmark, after which no new proof is started.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Unwraps the results the bin can not recover from.
+trait OrExit<T> {
+    /// Returns the value, or reports the error and exits with a failure code.
+    fn or_exit(self) -> T;
 }
 
-impl ProvingAssets {
-    fn from_nbr_leaves(nbr_leaves: usize) -> Self {
-        let mut aptos_wrapper =
-            AptosWrapper::new(nbr_leaves, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
-        aptos_wrapper.generate_traffic().unwrap();
+impl<T, E: Display> OrExit<T> for Result<T, E> {
+    fn or_exit(self) -> T {
+        self.unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        })
+    }
+}
 
-        let trusted_state = bcs::to_bytes(aptos_wrapper.trusted_state()).unwrap();
-        let validator_verifier = match TrustedState::from_bytes(&trusted_state).unwrap() {
-            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().clone(),
-            _ => panic!("expected epoch state"),
-        };
+/// Installs a SIGINT handler, so that the first interrupt lets the benchmark finish the
+/// current proof and write the results collected so far, and the second one exits at once.
+fn handle_interrupts() {
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        eprintln!("Interrupted, stopping after the current proof. Interrupt again to exit now.");
+    })
+    .map_err(|err| format!("could not handle interrupts: {err}"))
+    .or_exit();
+}
 
-        let proof_assets = aptos_wrapper
-            .get_latest_proof_account(nbr_leaves - 1)
-            .unwrap();
+/// Returns the index of the leaf `version_offset` positions before the latest generated one.
+fn leaf_index(nbr_leaves: usize, version_offset: usize) -> Result<usize, LightClientError> {
+    if version_offset >= nbr_leaves {
+        return Err(LightClientError::InvalidInput {
+            input: "version offset".to_string(),
+            reason: format!("{version_offset} is not below the {nbr_leaves} generated leaves"),
+        });
+    }
 
-        let sparse_merkle_proof = bcs::to_bytes(proof_assets.state_proof()).unwrap();
-        let key: [u8; 32] = *proof_assets.key().as_ref();
-        let element_hash: [u8; 32] = *proof_assets.state_value_hash().unwrap().as_ref();
+    Ok(nbr_leaves - 1 - version_offset)
+}
 
-        let transaction = bcs::to_bytes(&proof_assets.transaction()).unwrap();
-        let transaction_proof = bcs::to_bytes(&proof_assets.transaction_proof()).unwrap();
-        let latest_li = aptos_wrapper.get_latest_li_bytes().unwrap();
+/// Generates a fixture with the given number of leaves and returns the assets to prove the
+/// inclusion of `sample_account` if given, or else of the account `version_offset` positions
+/// before the latest one, signed by `signers` validators if given.
+fn proving_assets_from_nbr_leaves(
+    nbr_leaves: usize,
+    version_offset: usize,
+    signers: Option<usize>,
+    sample_account: Option<AccountAddress>,
+    validator_set: Option<&ValidatorVerifierAssets>,
+) -> Result<ProvingAssets, LightClientError> {
+    // Checked first, so that an invalid offset does not wait for the fixture
+    let leaf_idx = leaf_index(nbr_leaves, version_offset)?;
+    let mut fixture = AptosWrapperFixture::new(nbr_leaves, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR)?;
+    if let Some(signers) = signers {
+        fixture = fixture.with_ledger_info_signers(signers);
+    }
+    let leaf_idx = match sample_account {
+        Some(address) => fixture.account_index(address)?,
+        None => leaf_idx,
+    };
+    fixture.generate_traffic()?;
 
-        let sparse_merkle_proof_assets =
-            SparseMerkleProofAssets::new(sparse_merkle_proof, key, element_hash);
+    // Only transactions carrying a state checkpoint can be proven, so the fixture selects
+    // the latest one, which closes the last committed block.
+    let mut inclusion_data = fixture.inclusion_data(leaf_idx)?;
+    if let Some(validator_set) = validator_set {
+        inclusion_data.validator_verifier_assets = validator_set.clone();
+    }
+    ProvingAssets::from_inclusion_data(inclusion_data)
+}
 
-        let state_checkpoint_hash = proof_assets
-            .transaction()
-            .ensure_state_checkpoint_hash()
-            .unwrap();
+/// Fetches the assets to prove the inclusion of an account from an Aptos node, at the
+/// given ledger version or the latest one.
+#[cfg(feature = "rpc")]
+fn rpc_proving_assets(rpc_url: &str, account: &str, ledger_version: Option<u64>) -> ProvingAssets {
+    let fetch = || -> Result<ProvingAssets, LightClientError> {
+        // Checked first, so that a malformed address does not reach the node
+        let account = parse_account_address(account)?;
+        let runtime = tokio::runtime::Runtime::new().map_err(|err| LightClientError::RpcError {
+            endpoint: rpc_url.to_string(),
+            source: err.into(),
+        })?;
+        let response = runtime.block_on(fetch_account_inclusion_proof_at(
+            &reqwest::Client::new(),
+            rpc_url,
+            account,
+            ledger_version,
+        ))?;
+        ProvingAssets::from_inclusion_data(response.into())
+    };
 
-        let transaction_proof_assets = TransactionProofAssets::new(
-            transaction,
-            *proof_assets.transaction_version(),
-            transaction_proof,
-            latest_li,
-        );
+    fetch().or_exit()
+}
 
-        let validator_verifier_assets = ValidatorVerifierAssets::new(validator_verifier.to_bytes());
+/// Checks the values committed by the program against the assets used as inputs.
+fn check_output(proving_assets: &ProvingAssets, output: &InclusionOutput) {
+    // Covers the validator consistency required by P1, and P3 out-of-circuit through the
+    // state root.
+    let transaction_index = *proving_assets
+        .transaction_proof_assets()
+        .transaction_index();
+    proving_assets
+        .expected_inclusion()
+        .and_then(|expected| assert_inclusion_output(output, &expected))
+        .and_then(|()| assert_accumulator_leaves(output, transaction_index))
+        .and_then(|()| {
+            assert_transaction_hash(
+                output,
+                proving_assets.transaction_proof_assets().transaction(),
+            )
+        })
+        .or_exit();
+}
 
-        Self {
-            sparse_merkle_proof_assets,
-            transaction_proof_assets,
-            validator_verifier_assets,
-            state_checkpoint_hash: *state_checkpoint_hash.as_ref(),
-        }
+/// Checks that the block committed by the program is one of the approved checkpoints, if
+/// any were given.
+fn check_checkpoint(output: &InclusionOutput, checkpoints: Option<&HashSet<[u8; 32]>>) {
+    if let Some(checkpoints) = checkpoints {
+        assert_block_in_checkpoints(output, checkpoints).or_exit();
     }
+}
 
-    fn prove(&self) -> SP1ProofWithPublicValues{
-        let client = ProverClient::new();
-        let mut stdin = SP1Stdin::new();
+/// Sets up the keys of the program, through the cache when one is given.
+fn setup_keys(
+    client: &ProverClient,
+    elf: &[u8],
+    key_cache: Option<&Path>,
+) -> (SP1ProvingKey, SP1VerifyingKey) {
+    match key_cache {
+        Some(cache_dir) => load_or_setup_keys(client, elf, cache_dir).or_exit(),
+        None => client.setup(elf),
+    }
+}
 
-        stdin.write_vec(self.sparse_merkle_proof_assets.sparse_merkle_proof().clone());
-        stdin.write(self.sparse_merkle_proof_assets.leaf_key());
-        stdin.write(self.sparse_merkle_proof_assets.leaf_hash());
+fn prove(
+    client: &Arc<ProverClient>,
+    pk: &SP1ProvingKey,
+    stdin: SP1Stdin,
+    proof_type: ProofType,
+    execute_first: bool,
+    timeout: Option<Duration>,
+) -> SP1ProofWithPublicValues {
+    let proof = if let Some(timeout) = timeout {
+        let proof = prove_with_timeout(
+            client.clone(),
+            pk.clone(),
+            stdin,
+            proof_type,
+            "inclusion",
+            timeout,
+        );
+        proof.or_exit()
+    } else if execute_first {
+        let (report, _, proof) =
+            execute_and_prove(client, pk, &stdin, proof_type, "inclusion").or_exit();
 
-        // Tx inclusion input: Writes transaction related data to stdin.
-        stdin.write_vec(self.transaction_proof_assets.transaction().clone());
-        stdin.write(self.transaction_proof_assets.transaction_index());
-        stdin.write_vec(self.transaction_proof_assets.transaction_proof().clone());
-        stdin.write_vec(self.transaction_proof_assets.latest_li().clone());
+        // Record the report.
+        print_info(format_args!("Report: {}", report));
+        proof
+    } else {
+        proof_type.apply(client.prove(pk, stdin)).run().or_exit()
+    };
 
-        // Validator verifier: Writes validator verifier data for proof validation.
-        stdin.write_vec(self.validator_verifier_assets.validator_verifier().clone());
+    print_info(format_args!("Successfully generated proof!"));
+    proof
+}
 
-        let (pk, _) = client.setup(INCLUSION_ELF);
-        let proof = client
-            .prove(&pk, stdin)
-            .run()
-            .expect("failed to generate proof");
+/// Executes the program locally and proves it with the same inputs, with the prover of the
+/// environment, failing if the public values of the two differ.
+fn diff_proof(
+    client: &Arc<ProverClient>,
+    pk: &SP1ProvingKey,
+    elf: &[u8],
+    stdin: SP1Stdin,
+    proof_type: ProofType,
+    timeout: Option<Duration>,
+) {
+    let (executed, _) = client.execute(elf, stdin.clone()).run().unwrap();
+    let proof = prove(client, pk, stdin, proof_type, false, timeout);
 
-        println!("Successfully generated proof!");
-        proof
+    let diffs = diff_public_values(&executed, &proof.public_values);
+    if diffs.is_empty() {
+        print_info(format_args!(
+            "Public values of the execution and the proof match"
+        ));
+        return;
     }
+    for diff in &diffs {
+        eprintln!("Mismatch of the {diff}");
+    }
+    std::process::exit(1);
+}
 
-    fn execute(&self) {
-        let client = ProverClient::new();
-        let mut stdin = SP1Stdin::new();
-        stdin.write_vec(self.sparse_merkle_proof_assets.sparse_merkle_proof().clone());
-        stdin.write(self.sparse_merkle_proof_assets.leaf_key());
-        stdin.write(self.sparse_merkle_proof_assets.leaf_hash());
+fn execute(client: &ProverClient, elf: &[u8], stdin: SP1Stdin, pretty: bool) {
+    let (mut public_values, report) = client.execute(elf, stdin).run().unwrap();
 
-        // Tx inclusion input: Writes transaction related data to stdin.
-        stdin.write_vec(self.transaction_proof_assets.transaction().clone());
-        stdin.write(self.transaction_proof_assets.transaction_index());
-        stdin.write_vec(self.transaction_proof_assets.transaction_proof().clone());
-        stdin.write_vec(self.transaction_proof_assets.latest_li().clone());
+    // Record the report.
+    print_info(format_args!("Report: {}", report));
+    print_info(format_args!(
+        "Public values: {} bytes",
+        public_values.as_slice().len()
+    ));
 
-        // Validator verifier: Writes validator verifier data for proof validation.
-        stdin.write_vec(self.validator_verifier_assets.validator_verifier().clone());
+    // A loaded stdin may prove transactions rather than an account
+    match InclusionOutput::from_public_values(&mut public_values) {
+        Ok(output) => print_info(output_json(&output, pretty)),
+        Err(err) => print_info(format_args!("No inclusion output: {err}")),
+    }
+}
 
-        let (_, report) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+/// Emits a result through the sink, exiting if it could not be written.
+fn emit(sink: &mut OutputSink, result: impl Display) {
+    sink.emit(result).or_exit();
+}
 
-        // Record the report.
-        println!("Report: {}", report);
+/// Emits the description of the checks of the program on the assets, exiting if they could
+/// not be deserialized.
+fn explain(sink: &mut OutputSink, proving_assets: &ProvingAssets) {
+    let explanation = proving_assets.explain().or_exit();
+    emit(sink, explanation);
+}
+
+fn print_digest(sink: &mut OutputSink, proof: &SP1ProofWithPublicValues) {
+    let digest = public_values_digest(&proof.public_values);
+    emit(
+        sink,
+        format_args!("Public values digest: 0x{}", hex::encode(digest)),
+    );
+}
+
+fn write_proof(proof: &SP1ProofWithPublicValues, path: &Path, encoding: ProofEncoding) {
+    save_proof(proof, path, encoding).or_exit();
+    print_info(format_args!("Proof written to {}", path.display()));
+}
+
+fn write_bundle(proof: &SP1ProofWithPublicValues, vk: &SP1VerifyingKey, path: &Path) {
+    ProofBundle::new(INCLUSION_PROGRAM_ID, vk, proof.clone())
+        .save(path)
+        .or_exit();
+    print_info(format_args!("Proof bundle written to {}", path.display()));
+}
+
+fn verify_bundle(
+    client: &ProverClient,
+    elf: &[u8],
+    path: &Path,
+    checkpoints: Option<&HashSet<[u8; 32]>>,
+) {
+    // The bundle is checked against the loaded program, which may not be the embedded one
+    let bundle = ProofBundle::load(path)
+        .and_then(|bundle| {
+            bundle.verify_with_vk(client, &client.setup(elf).1)?;
+            Ok(bundle)
+        })
+        .or_exit();
+    let output =
+        InclusionOutput::from_public_values(&mut bundle.proof().public_values.clone()).or_exit();
+    check_checkpoint(&output, checkpoints);
+    print_info(format_args!(
+        "Proof bundle verified for key 0x{} and value 0x{}",
+        hex::encode(output.key()),
+        hex::encode(output.value())
+    ));
+}
+
+fn compare_vk(elf: &[u8], expected: &str) {
+    // The verifying key does not depend on the prover, so a mock client is enough
+    let (_, vk) = ProverClient::mock().setup(elf);
+    check_vk_hash(&vk, expected).or_exit();
+    print_info(format_args!("Verifying key hash matches {expected}"));
+}
+
+fn export_vk(client: &ProverClient, elf: &[u8], path: &Path) {
+    let vk_bytes = vk_to_bytes(&client.setup(elf).1).or_exit();
+    std::fs::write(path, vk_bytes)
+        .map_err(|err| format!("could not write {}: {err}", path.display()))
+        .or_exit();
+    print_info(format_args!("Verifying key written to {}", path.display()));
+}
+
+/// Proves `warmup + count` times, only recording the proving times, in milliseconds, of the
+/// last `count` proofs. Each measured proof is then handed to `on_proof`. No proof is started
+/// once `stop` is set, so fewer than `count` times may be returned.
+fn measure_proving<T>(
+    warmup: usize,
+    count: usize,
+    stop: &AtomicBool,
+    mut prove: impl FnMut() -> T,
+    mut on_proof: impl FnMut(T),
+) -> Vec<u128> {
+    // The first proofs of the process pay one-off costs, their timings are discarded
+    for _ in 0..warmup {
+        if stop.load(Ordering::SeqCst) {
+            return vec![];
+        }
+        prove();
     }
+
+    (0..count)
+        .map_while(|_| {
+            if stop.load(Ordering::SeqCst) {
+                return None;
+            }
+            let start_proving = Instant::now();
+            let proof = prove();
+            let proving_time = start_proving.elapsed().as_millis();
+            on_proof(proof);
+            Some(proving_time)
+        })
+        .collect()
 }
 
 #[derive(Serialize)]
@@ -131,6 +366,257 @@ struct Timings {
     nbr_leaves: usize,
     proving_time: u128,
     verifying_time: u128,
+    proof_bytes: usize,
+}
+
+/// Format of the benchmark results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// One JSON object per number of leaves, printed as soon as it is measured.
+    #[default]
+    Json,
+    /// An ASCII table over all the numbers of leaves, printed at the end.
+    Table,
+}
+
+const TABLE_HEADER: [&str; 4] = [
+    "leaves",
+    "proving time (ms)",
+    "verifying time (ms)",
+    "cycles",
+];
+
+/// Row of the benchmark table. Times are in milliseconds, and are medians over repeated
+/// proofs.
+struct TableRow {
+    nbr_leaves: usize,
+    proving_time: u128,
+    verifying_time: u128,
+    cycles: u64,
+}
+
+impl TableRow {
+    fn new(
+        nbr_leaves: usize,
+        proving_times: Vec<u128>,
+        verifying_times: Vec<u128>,
+        cycles: u64,
+    ) -> Self {
+        Self {
+            nbr_leaves,
+            proving_time: TimingStats::new(nbr_leaves, proving_times).median.round() as u128,
+            verifying_time: TimingStats::new(nbr_leaves, verifying_times).median.round() as u128,
+            cycles,
+        }
+    }
+}
+
+/// Formats the benchmark rows as an ASCII table, with a header row and one row per number
+/// of leaves.
+fn format_table(rows: &[TableRow]) -> String {
+    let header = TABLE_HEADER;
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.nbr_leaves.to_string(),
+                row.proving_time.to_string(),
+                row.verifying_time.to_string(),
+                row.cycles.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(width + 2))
+        .collect::<Vec<_>>()
+        .join("+");
+    let separator = format!("+{separator}+");
+    let format_row = |row: &[&str]| {
+        let row = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:>width$} "))
+            .collect::<Vec<_>>()
+            .join("|");
+        format!("|{row}|")
+    };
+
+    let mut lines = vec![separator.clone(), format_row(&header), separator.clone()];
+    for row in &cells {
+        lines.push(format_row(&row.each_ref().map(String::as_str)));
+    }
+    lines.push(separator);
+    lines.join("\n")
+}
+
+/// Statistics over the proving times, in milliseconds, of repeated proofs.
+#[derive(Serialize)]
+struct TimingStats {
+    nbr_leaves: usize,
+    count: usize,
+    min: u128,
+    max: u128,
+    mean: f64,
+    median: f64,
+    p95: u128,
+}
+
+impl TimingStats {
+    fn new(nbr_leaves: usize, mut proving_times: Vec<u128>) -> Self {
+        proving_times.sort_unstable();
+        let count = proving_times.len();
+
+        let median = if count % 2 == 0 {
+            (proving_times[count / 2 - 1] + proving_times[count / 2]) as f64 / 2.0
+        } else {
+            proving_times[count / 2] as f64
+        };
+        // Nearest-rank percentile
+        let p95_rank = (count * 95).div_ceil(100);
+
+        Self {
+            nbr_leaves,
+            count,
+            min: proving_times[0],
+            max: proving_times[count - 1],
+            mean: proving_times.iter().sum::<u128>() as f64 / count as f64,
+            median,
+            p95: proving_times[p95_rank - 1],
+        }
+    }
+}
+
+/// Number of buckets of the histograms of `--bench-cycles`.
+const CYCLE_HISTOGRAM_BUCKETS: u64 = 10;
+
+/// Width of the widest bar of a formatted histogram, in characters.
+const HISTOGRAM_BAR_WIDTH: usize = 40;
+
+/// Histogram of the cycle counts of repeated executions, over equal-width buckets from the
+/// smallest count to the largest one, along with statistics over the counts.
+#[derive(Serialize)]
+struct CycleHistogram {
+    nbr_leaves: usize,
+    count: usize,
+    min: u64,
+    max: u64,
+    mean: f64,
+    /// Range of cycle counts of a bucket, the first one starting at `min`.
+    bucket_width: u64,
+    /// Number of executions in each bucket.
+    buckets: Vec<usize>,
+}
+
+impl CycleHistogram {
+    fn new(nbr_leaves: usize, cycles: &[u64]) -> Self {
+        let min = cycles.iter().copied().min().unwrap_or(0);
+        let max = cycles.iter().copied().max().unwrap_or(0);
+        // Rounded up, so that `max` falls in the last bucket
+        let bucket_width = (max - min) / CYCLE_HISTOGRAM_BUCKETS + 1;
+        let nbr_buckets = (max - min) / bucket_width + 1;
+
+        let mut buckets = vec![0; nbr_buckets as usize];
+        for nbr_cycles in cycles {
+            buckets[((nbr_cycles - min) / bucket_width) as usize] += 1;
+        }
+
+        Self {
+            nbr_leaves,
+            count: cycles.len(),
+            min,
+            max,
+            mean: cycles.iter().sum::<u64>() as f64 / cycles.len() as f64,
+            bucket_width,
+            buckets,
+        }
+    }
+
+    /// Formats the histogram with one line per bucket, giving its range of cycle counts,
+    /// its number of executions and a bar proportional to it.
+    fn format(&self) -> String {
+        let largest = self.buckets.iter().copied().max().unwrap_or(0).max(1);
+        let width = (self.min + self.buckets.len() as u64 * self.bucket_width)
+            .to_string()
+            .len();
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(idx, nbr_executions)| {
+                let start = self.min + idx as u64 * self.bucket_width;
+                let end = start + self.bucket_width - 1;
+                let bar = "#".repeat(nbr_executions * HISTOGRAM_BAR_WIDTH / largest);
+                format!("{start:>width$} - {end:>width$} | {nbr_executions:>4} {bar}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Executes the program, returning the number of cycles it ran for.
+fn execute_cycles(client: &ProverClient, elf: &[u8], stdin: SP1Stdin) -> u64 {
+    let (_, report) = client.execute(elf, stdin).run().unwrap();
+    report.total_instruction_count()
+}
+
+/// Executes the program `count` times, each time on the inputs returned by `next_stdin`,
+/// and returns the histogram of the cycle counts. No execution is started once `stop` is
+/// set, so the histogram may cover fewer than `count` executions.
+fn bench_cycles(
+    client: &ProverClient,
+    elf: &[u8],
+    nbr_leaves: usize,
+    count: usize,
+    stop: &AtomicBool,
+    mut next_stdin: impl FnMut() -> SP1Stdin,
+) -> CycleHistogram {
+    let cycles = (0..count)
+        .map_while(|_| {
+            (!stop.load(Ordering::SeqCst)).then(|| execute_cycles(client, elf, next_stdin()))
+        })
+        .collect::<Vec<_>>();
+    CycleHistogram::new(nbr_leaves, &cycles)
+}
+
+/// Statistics over the setup times, in milliseconds, of repeated setups of a program. They
+/// are fractional, as the setups loading cached keys can take less than a millisecond.
+#[derive(Serialize)]
+struct SetupStats {
+    program: &'static str,
+    count: usize,
+    min: f64,
+    mean: f64,
+    max: f64,
+}
+
+/// Sets up the keys of a program `count` times, through the cache when one is given, and
+/// returns statistics over the setup times.
+fn measure_setup(
+    client: &ProverClient,
+    program: &'static str,
+    elf: &[u8],
+    count: usize,
+    key_cache: Option<&Path>,
+) -> Result<SetupStats, LightClientError> {
+    let setup_times = (0..count)
+        .map(|_| setup_timed(client, elf, key_cache).map(|time| time.as_secs_f64() * 1000.0))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SetupStats {
+        program,
+        count,
+        min: setup_times.iter().copied().fold(f64::INFINITY, f64::min),
+        mean: setup_times.iter().sum::<f64>() / count as f64,
+        max: setup_times.iter().copied().fold(0.0, f64::max),
+    })
 }
 
 /// The arguments for the command.
@@ -143,89 +629,780 @@ struct Args {
     #[clap(long)]
     prove: bool,
 
+    /// Execute the program before proving it with the same inputs, printing the execution
+    /// report along with the proof. The proving times then include the execution.
+    #[clap(long, conflicts_with_all = ["execute", "prove", "validate_only"])]
+    execute_and_prove: bool,
+
+    /// Execute the program locally and prove it with the same inputs, reporting the values
+    /// that differ between the public values of the execution and those of the proof.
+    #[clap(
+        long,
+        conflicts_with_all = ["execute", "prove", "execute_and_prove", "validate_only"]
+    )]
+    diff: bool,
+
+    /// Force local proving, failing if the environment selects another prover.
+    #[clap(long)]
+    local: bool,
+
+    /// Force local proving on GPU. Requires the `cuda` feature.
+    #[clap(long)]
+    gpu: bool,
+
+    /// Number of threads the local prover runs on, one per core by default. The proofs are
+    /// generated one after the other, each of them on all of these threads.
+    #[clap(long, value_name = "N")]
+    threads: Option<NonZeroUsize>,
+
+    /// Verbosity of the logs. No logger is installed when `off`.
+    #[clap(long, value_enum, value_name = "LEVEL", default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+
+    /// Only print the machine-readable results to stdout, such as the benchmark JSON, logging
+    /// the other messages instead.
+    #[clap(long)]
+    quiet: bool,
+
+    /// Print the outputs of the programs as indented JSON rather than on a single line.
+    #[clap(long)]
+    pretty: bool,
+
+    /// Write the machine-readable results to `PATH`, one per line, rather than to stdout.
+    #[clap(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Also write the results to stdout when they are written to a file with `--out`.
+    #[clap(long, requires = "out")]
+    stdout: bool,
+
+    /// Only set up the keys of the inclusion and epoch change programs, `--count` times each,
+    /// and print statistics over the setup times without proving. With `--key-cache`, the
+    /// setups following the first one of an ELF load its cached keys.
+    #[clap(
+        long,
+        conflicts_with_all = ["execute", "prove", "execute_and_prove", "diff", "validate_only"]
+    )]
+    bench_setup: bool,
+
+    /// Only execute the program `N` times for each number of leaves, on freshly generated
+    /// fixtures, and print a histogram of the cycle counts along with statistics over them.
+    #[clap(
+        long,
+        value_name = "N",
+        conflicts_with_all = [
+            "execute", "prove", "execute_and_prove", "diff", "validate_only", "explain",
+            "bench_setup", "stdin_in", "assets_stdin"
+        ]
+    )]
+    bench_cycles: Option<NonZeroUsize>,
+
+    /// Only check out-of-circuit that the generated assets would be accepted by the program.
+    #[clap(long)]
+    validate_only: bool,
+
+    /// Only describe the checks the program performs on the assets, along with the hashes
+    /// they involve, without proving.
+    #[clap(
+        long,
+        conflicts_with_all = [
+            "execute", "prove", "execute_and_prove", "diff", "validate_only", "stdin_in"
+        ]
+    )]
+    explain: bool,
+
+    /// Number of proofs to generate for each number of leaves. Statistics over the proving
+    /// times are output when greater than 1.
+    #[clap(long, default_value_t = 1)]
+    count: usize,
+
+    /// Number of proofs to generate for each number of leaves before the `--count` measured
+    /// ones. Their timings are discarded, and they are neither verified nor saved.
+    #[clap(long, value_name = "N", default_value_t = 0)]
+    warmup: usize,
+
+    /// Prove the inclusion of the leaf `N` positions before the latest generated one, for
+    /// each number of leaves. Must be below all the numbers of leaves.
+    #[clap(long, value_name = "N", default_value_t = 0)]
+    version_offset: usize,
+
+    /// Number of the validators signing the proven ledger info, rather than the signers of
+    /// the blocks. Below the quorum of 87, the program commits `InsufficientVotingPower`.
+    #[clap(long, value_name = "N")]
+    signers: Option<usize>,
+
+    /// Prove the inclusion of the account at `ADDRESS` rather than of the latest generated
+    /// leaf, e.g. to debug its proof. Only the accounts generated by the fixtures can be
+    /// proven, and the fixtures with too few leaves to hold it fail.
+    #[clap(
+        long,
+        value_name = "ADDRESS",
+        value_parser = parse_account_address,
+        conflicts_with = "version_offset"
+    )]
+    sample_account: Option<AccountAddress>,
+
+    /// Prove the inclusions under the validator set at `PATH`, the bcs serialization of a
+    /// `ValidatorVerifier`, rather than under the validators of the fixtures.
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["stdin_in", "assets_stdin"]
+    )]
+    validator_set: Option<PathBuf>,
+
+    /// Write the stdin generated for each number of leaves to `<PATH>-<NBR_LEAVES>`.
+    #[clap(long, value_name = "PATH")]
+    dump_stdin: Option<PathBuf>,
+
+    /// Write the generated proof to `PATH`, or to `<PATH>-<NBR_LEAVES>` for each number of
+    /// leaves when the assets are generated.
+    #[clap(long, value_name = "PATH")]
+    save_proof: Option<PathBuf>,
+
+    /// Kind of proof to generate, e.g. `groth16` for a proof verifiable on-chain.
+    #[clap(long, value_enum, default_value_t = ProofType::Core)]
+    proof_type: ProofType,
+
+    /// Encoding of the proof written with `--save-proof`.
+    #[clap(long, value_enum, default_value_t = ProofEncoding::Bincode, requires = "save_proof")]
+    proof_encoding: ProofEncoding,
+
+    /// Write the generated proof to `PATH` as a bundle along with the hash of the verifying
+    /// key, or to `<PATH>-<NBR_LEAVES>` for each number of leaves when the assets are
+    /// generated.
+    #[clap(long, value_name = "PATH")]
+    bundle_out: Option<PathBuf>,
+
+    /// Verify the proof bundle written to `PATH` with `--bundle-out` against the program.
+    /// Can be used alone.
+    #[clap(long, value_name = "PATH")]
+    bundle_in: Option<PathBuf>,
+
+    /// Execute or prove the program directly from a stdin written with `--dump-stdin`.
+    #[clap(long, value_name = "PATH", conflicts_with = "validate_only")]
+    stdin_in: Option<PathBuf>,
+
+    /// Read the assets from stdin, as a JSON `InclusionData`, instead of generating fixtures.
+    #[clap(long, conflicts_with_all = ["stdin_in", "dump_stdin"])]
+    assets_stdin: bool,
+
+    /// Fetch the assets of the account given with `--account` from the Aptos node at
+    /// `URL`, instead of generating fixtures.
+    #[cfg(feature = "rpc")]
+    #[clap(
+        long,
+        value_name = "URL",
+        requires = "account",
+        conflicts_with_all = [
+            "stdin_in", "dump_stdin", "assets_stdin", "validator_set", "bench_cycles"
+        ]
+    )]
+    rpc_url: Option<String>,
+
+    /// Address of the account to prove the inclusion of with `--rpc-url`, e.g. `0x1`.
+    #[cfg(feature = "rpc")]
+    #[clap(long, value_name = "ADDRESS", requires = "rpc_url")]
+    account: Option<String>,
+
+    /// Ledger version to prove the state of the account at with `--rpc-url`, the latest
+    /// one by default.
+    #[cfg(feature = "rpc")]
+    #[clap(long, value_name = "VERSION", requires = "rpc_url")]
+    ledger_version: Option<u64>,
+
+    /// Write the serialized verifying key of the program to `PATH`. Can be used alone.
+    #[clap(long, value_name = "PATH")]
+    export_vk: Option<PathBuf>,
+
+    /// Fail if the hash of the verifying key of the program differs from `HEX`. Can be
+    /// used alone.
+    #[clap(long, value_name = "HEX")]
+    compare_vk: Option<String>,
+
+    /// Print the versions of the crate and of SP1, and the hashes of the verifying keys of
+    /// the embedded programs, as JSON, then exit.
+    #[clap(long)]
+    build_info: bool,
+
+    /// Print the JSON Schema of the `InclusionData` read with `--assets-stdin`, then exit.
+    #[clap(long)]
+    json_schema: bool,
+
+    /// Fail if a proof takes more than `N` seconds. The proving may keep running briefly
+    /// in the background until the process exits.
+    #[clap(long, value_name = "N", conflicts_with = "execute_and_prove")]
+    timeout_secs: Option<u64>,
+
+    /// Load the program from the ELF at `PATH` instead of the embedded one, e.g. to test
+    /// a freshly built guest.
+    #[clap(long, value_name = "PATH")]
+    elf: Option<PathBuf>,
+
+    /// Cache the keys of the program in `DIR`, so that they are only set up again when the
+    /// ELF changes.
+    #[clap(long, value_name = "DIR")]
+    key_cache: Option<PathBuf>,
+
+    /// Print the digest of the public values of each proof, as computed by the on-chain
+    /// verifiers.
+    #[clap(long)]
+    digest: bool,
+
+    /// Fail if the block committed by a proof is not in the JSON array of hex block ids at
+    /// `PATH`.
+    #[clap(long, value_name = "PATH")]
+    checkpoints: Option<PathBuf>,
+
+    /// Format of the benchmark results of the proofs over the generated fixtures.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    /// Also write the median timings and the cycles of the proofs over the generated
+    /// fixtures to `PATH`, one JSON object per number of leaves, to be compared between
+    /// runs with the `compare` binary.
+    #[clap(long, value_name = "PATH")]
+    metrics_out: Option<PathBuf>,
 }
 
 
 fn main() {
-    // Setup the logger.
-    sp1_sdk::utils::setup_logger();
-
     // Parse the command line arguments.
     let args = Args::parse();
 
-    if args.execute == args.prove {
-        eprintln!("Error: You must specify either --execute or --prove");
+    // Setup the logger.
+    set_quiet(args.quiet);
+    setup_logger(args.log_level);
+    let mut sink = OutputSink::from_flags(args.out.as_deref(), args.stdout).or_exit();
+
+    if args.build_info {
+        let build_info = build_info().or_exit();
+        emit(&mut sink, serde_json::to_string(&build_info).unwrap());
+        return;
+    }
+    if args.json_schema {
+        emit(&mut sink, inclusion_data_json_schema());
+        return;
+    }
+
+    let external_elf = args.elf.as_ref().map(|path| load_elf(path).or_exit());
+    let elf = match external_elf {
+        Some(elf) => Cow::Owned(elf),
+        None => resolve_elf(INCLUSION_ELF, "inclusion", elf_dir_from_env().as_deref()).or_exit(),
+    };
+    let elf: &[u8] = &elf;
+
+    if let Some(expected) = &args.compare_vk {
+        compare_vk(elf, expected);
+    }
+
+    // `--export-vk`, `--compare-vk` and `--bundle-in` can be used without any other mode
+    let standalone = !args.execute
+        && !args.prove
+        && !args.execute_and_prove
+        && !args.diff
+        && !args.validate_only
+        && !args.explain
+        && !args.bench_setup
+        && args.bench_cycles.is_none()
+        && (args.export_vk.is_some() || args.compare_vk.is_some() || args.bundle_in.is_some());
+    if standalone && args.export_vk.is_none() && args.bundle_in.is_none() {
+        return;
+    }
+    if !standalone
+        && !args.validate_only
+        && !args.explain
+        && !args.execute_and_prove
+        && !args.diff
+        && !args.bench_setup
+        && args.bench_cycles.is_none()
+        && args.execute == args.prove
+    {
+        eprintln!(
+            "Error: You must specify either --execute, --prove, --execute-and-prove, --diff, --validate-only, --explain, --bench-setup or --bench-cycles"
+        );
         std::process::exit(1);
     }
 
-    for nbr_leaves in NBR_LEAVES {
-        let proving_assets = ProvingAssets::from_nbr_leaves(nbr_leaves);
-        if args.execute {
-            proving_assets.execute();
-        } else {
+    if args.count == 0 {
+        eprintln!("Error: --count must be at least 1");
+        std::process::exit(1);
+    }
 
-            let start_proving = Instant::now();
-            let mut inclusion_proof = proving_assets.prove();
-            let proving_time = start_proving.elapsed();
-
-            // Verify the consistency of the validator verifier hash post-merkle proof.
-            // This verifies the validator consistency required by P1.
-            let prev_validator_verifier_hash: [u8; 32] = inclusion_proof.public_values.read();
-            assert_eq!(
-                &prev_validator_verifier_hash,
-                ValidatorVerifier::from_bytes(
-                    proving_assets
-                        .validator_verifier_assets
-                        .validator_verifier()
-                )
-                    .unwrap()
-                    .hash()
-                    .as_ref()
-            );
+    set_prover_threads(args.threads);
+    let client = prover_client(args.local, args.gpu).map(Arc::new).or_exit();
 
-            // Verify the consistency of the final merkle root hash computed
-            // by the program against the expected one.
-            // This verifies P3 out-of-circuit.
-            let merkle_root_slice: [u8; 32] = inclusion_proof.public_values.read();
-            assert_eq!(
-                merkle_root_slice, proving_assets.state_checkpoint_hash,
-                "Merkle root hash mismatch"
+    if args.bench_setup {
+        let epoch_change_elf = resolve_elf(
+            EPOCH_CHANGE_ELF,
+            "epoch-change",
+            elf_dir_from_env().as_deref(),
+        )
+        .or_exit();
+        for (program, elf) in [("inclusion", elf), ("epoch-change", &*epoch_change_elf)] {
+            let stats = measure_setup(&client, program, elf, args.count, args.key_cache.as_deref())
+                .or_exit();
+            emit(&mut sink, serde_json::to_string(&stats).unwrap());
+        }
+        return;
+    }
+
+    let timeout = args.timeout_secs.map(Duration::from_secs);
+    let checkpoints = args
+        .checkpoints
+        .as_ref()
+        .map(|path| load_checkpoints(path).or_exit());
+    let checkpoints = checkpoints.as_ref();
+    // Checked before any proving, rather than when generating the first fixture
+    let validator_set = args
+        .validator_set
+        .as_ref()
+        .map(|path| load_validator_set(path).or_exit());
+    if let Some(path) = &args.export_vk {
+        export_vk(&client, elf, path);
+    }
+    if let Some(path) = &args.bundle_in {
+        verify_bundle(&client, elf, path, checkpoints);
+    }
+    if standalone {
+        return;
+    }
+
+    // Fail fast rather than after generating the assets of the first batch
+    if args.prove || args.execute_and_prove || args.diff {
+        check_prover_available(&client).or_exit();
+    }
+
+    // The keys do not depend on the inputs, so they are shared by all the proofs
+    let keys = (args.prove || args.execute_and_prove || args.diff)
+        .then(|| setup_keys(&client, elf, args.key_cache.as_deref()));
+
+    if let Some(path) = &args.stdin_in {
+        let stdin = load_stdin(path).or_exit();
+        if let (true, Some((pk, _))) = (args.diff, &keys) {
+            diff_proof(&client, pk, elf, stdin, args.proof_type, timeout);
+        } else if let Some((pk, vk)) = &keys {
+            let mut inclusion_proof = prove(
+                &client,
+                pk,
+                stdin,
+                args.proof_type,
+                args.execute_and_prove,
+                timeout,
             );
+            if args.digest {
+                print_digest(&mut sink, &inclusion_proof);
+            }
+            if let Some(path) = &args.save_proof {
+                write_proof(&inclusion_proof, path, args.proof_encoding);
+            }
+            if let Some(path) = &args.bundle_out {
+                write_bundle(&inclusion_proof, vk, path);
+            }
+            let output =
+                InclusionOutput::from_public_values(&mut inclusion_proof.public_values).or_exit();
+            check_checkpoint(&output, checkpoints);
+        } else {
+            execute(&client, elf, stdin, args.pretty);
+        }
+        return;
+    }
 
-            let block_hash: [u8; 32] = inclusion_proof.public_values.read();
-            let lates_li = proving_assets.transaction_proof_assets.latest_li();
-            let expected_block_id = LedgerInfoWithSignatures::from_bytes(lates_li)
-                .unwrap()
-                .ledger_info()
-                .block_id();
-            assert_eq!(
-                block_hash.to_vec(),
-                expected_block_id.to_vec(),
-                "Block hash mismatch"
+    #[cfg(feature = "rpc")]
+    let rpc_assets = args.rpc_url.as_deref().map(|rpc_url| {
+        let account = args
+            .account
+            .as_deref()
+            .ok_or("--account is required by --rpc-url")
+            .or_exit();
+        rpc_proving_assets(rpc_url, account, args.ledger_version)
+    });
+    #[cfg(not(feature = "rpc"))]
+    let rpc_assets = None;
+    let external_assets = if args.assets_stdin {
+        Some(
+            read_inclusion_data_json(std::io::stdin().lock())
+                .and_then(ProvingAssets::from_inclusion_data)
+                .or_exit(),
+        )
+    } else {
+        rpc_assets
+    };
+
+    if let Some(proving_assets) = external_assets {
+        let stdin = proving_assets.stdin().or_exit();
+
+        if args.validate_only {
+            validate_inclusion_assets(&proving_assets).or_exit();
+            print_info(format_args!("External assets are valid"));
+        } else if args.explain {
+            explain(&mut sink, &proving_assets);
+        } else if let (true, Some((pk, _))) = (args.diff, &keys) {
+            diff_proof(&client, pk, elf, stdin, args.proof_type, timeout);
+        } else if let Some((pk, vk)) = &keys {
+            let mut inclusion_proof = prove(
+                &client,
+                pk,
+                stdin,
+                args.proof_type,
+                args.execute_and_prove,
+                timeout,
             );
+            if args.digest {
+                print_digest(&mut sink, &inclusion_proof);
+            }
+            if let Some(path) = &args.save_proof {
+                write_proof(&inclusion_proof, path, args.proof_encoding);
+            }
+            if let Some(path) = &args.bundle_out {
+                write_bundle(&inclusion_proof, vk, path);
+            }
+            let output =
+                InclusionOutput::from_public_values(&mut inclusion_proof.public_values).or_exit();
+            check_output(&proving_assets, &output);
+            check_checkpoint(&output, checkpoints);
+            print_info(output_json(&output, args.pretty));
+        } else {
+            execute(&client, elf, stdin, args.pretty);
+        }
+        return;
+    }
+
+    // The results of the proofs measured before an interrupt are still output
+    if keys.is_some() || args.bench_cycles.is_some() {
+        handle_interrupts();
+    }
+    let mut table_rows = vec![];
+    let mut metrics = vec![];
+    for nbr_leaves in NBR_LEAVES {
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            break;
+        }
+        // Nest the spans of the library under the number of leaves of the fixture.
+        let _span = tracing::info_span!("inclusion", nbr_leaves).entered();
+        let proving_assets = proving_assets_from_nbr_leaves(
+            nbr_leaves,
+            args.version_offset,
+            args.signers,
+            args.sample_account,
+            validator_set.as_ref(),
+        )
+        .or_exit();
+        let stdin = proving_assets.stdin().or_exit();
+        if let Some(path) = &args.dump_stdin {
+            let path = PathBuf::from(format!("{}-{nbr_leaves}", path.display()));
+            dump_stdin(&stdin, &path).or_exit();
+        }
 
-            let key: [u8; 32] = inclusion_proof.public_values.read();
-            assert_eq!(
-                key.to_vec(),
-                proving_assets.sparse_merkle_proof_assets.leaf_key(),
-                "Merkle tree key mismatch"
+        if args.validate_only {
+            validate_inclusion_assets(&proving_assets).or_exit();
+            print_info(format_args!("Assets for {nbr_leaves} leaves are valid"));
+        } else if args.explain {
+            print_info(format_args!("Assets for {nbr_leaves} leaves:"));
+            explain(&mut sink, &proving_assets);
+        } else if let Some(count) = args.bench_cycles {
+            // The first execution is on the assets generated above, the others on new ones
+            let mut stdin = Some(stdin);
+            let next_stdin = || {
+                stdin.take().unwrap_or_else(|| {
+                    proving_assets_from_nbr_leaves(
+                        nbr_leaves,
+                        args.version_offset,
+                        args.signers,
+                        args.sample_account,
+                        validator_set.as_ref(),
+                    )
+                    .and_then(|proving_assets| proving_assets.stdin())
+                    .or_exit()
+                })
+            };
+            let histogram = bench_cycles(
+                &client,
+                elf,
+                nbr_leaves,
+                count.get(),
+                &INTERRUPTED,
+                next_stdin,
             );
+            if histogram.count == 0 {
+                break;
+            }
+            print_info(histogram.format());
+            emit(&mut sink, serde_json::to_string(&histogram).unwrap());
+        } else if args.execute {
+            execute(&client, elf, stdin, args.pretty);
+        } else if let (true, Some((pk, _))) = (args.diff, &keys) {
+            diff_proof(&client, pk, elf, stdin, args.proof_type, timeout);
+        } else if let Some((pk, vk)) = &keys {
+            let mut verifying_times = Vec::with_capacity(args.count);
+            // Proofs of the same type and inputs have the same size, so the last one is kept
+            let mut proof_bytes = 0;
+            // The keys are shared, so the warmup proofs only exercise the proving
+            let proving_times = measure_proving(
+                args.warmup,
+                args.count,
+                &INTERRUPTED,
+                || {
+                    prove(
+                        &client,
+                        pk,
+                        stdin.clone(),
+                        args.proof_type,
+                        args.execute_and_prove,
+                        timeout,
+                    )
+                },
+                |mut inclusion_proof| {
+                    let verifying_time =
+                        verify_timed(&client, &inclusion_proof, vk, "inclusion").or_exit();
+                    verifying_times.push(verifying_time.as_millis());
+                    proof_bytes = proof_size_bytes(&inclusion_proof);
+                    print_info(format_args!("Proof size: {proof_bytes} bytes"));
+
+                    if args.digest {
+                        print_digest(&mut sink, &inclusion_proof);
+                    }
+
+                    if let Some(path) = &args.save_proof {
+                        let path = PathBuf::from(format!("{}-{nbr_leaves}", path.display()));
+                        write_proof(&inclusion_proof, &path, args.proof_encoding);
+                    }
+                    if let Some(path) = &args.bundle_out {
+                        let path = PathBuf::from(format!("{}-{nbr_leaves}", path.display()));
+                        write_bundle(&inclusion_proof, vk, &path);
+                    }
 
-            let value: [u8; 32] = inclusion_proof.public_values.read();
-            assert_eq!(
-                value.to_vec(),
-                proving_assets.sparse_merkle_proof_assets.leaf_hash(),
-                "Merkle tree value mismatch"
+                    let output =
+                        InclusionOutput::from_public_values(&mut inclusion_proof.public_values)
+                            .or_exit();
+                    check_output(&proving_assets, &output);
+                    check_checkpoint(&output, checkpoints);
+                },
             );
+            if proving_times.is_empty() {
+                break;
+            }
 
-            let timings = Timings {
-                nbr_leaves,
-                proving_time: proving_time.as_millis(),
-                verifying_time: Duration::from_secs(0).as_millis(),
+            if args.format == OutputFormat::Table || args.metrics_out.is_some() {
+                // Proving does not report the cycles, so they come from a separate execution
+                let row = TableRow::new(
+                    nbr_leaves,
+                    proving_times.clone(),
+                    verifying_times.clone(),
+                    execute_cycles(&client, elf, stdin),
+                );
+                metrics.push(BenchMetrics::new(
+                    row.nbr_leaves,
+                    row.proving_time,
+                    row.verifying_time,
+                    row.cycles,
+                    proof_bytes,
+                ));
+                if args.format == OutputFormat::Table {
+                    table_rows.push(row);
+                    continue;
+                }
+            }
+
+            let json_output = if proving_times.len() == 1 {
+                let timings = Timings {
+                    nbr_leaves,
+                    proving_time: proving_times[0],
+                    verifying_time: verifying_times[0],
+                    proof_bytes,
+                };
+                serde_json::to_string(&timings).unwrap()
+            } else {
+                serde_json::to_string(&TimingStats::new(nbr_leaves, proving_times)).unwrap()
             };
+            emit(&mut sink, json_output);
+        }
+    }
+
+    if !table_rows.is_empty() {
+        emit(&mut sink, format_table(&table_rows));
+    }
+    if let Some(path) = &args.metrics_out {
+        write_metrics(path, &metrics).or_exit();
+        print_info(format_args!("Metrics written to {}", path.display()));
+    }
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        std::process::exit(130);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_lc_script::inclusion::account_state_key;
+
+    #[test]
+    fn test_warmup_proofs_are_not_measured() {
+        let mut nbr_proofs = 0;
+        let mut measured_proofs = vec![];
+        let proving_times = measure_proving(
+            3,
+            2,
+            &AtomicBool::new(false),
+            || {
+                nbr_proofs += 1;
+                nbr_proofs
+            },
+            |proof| measured_proofs.push(proof),
+        );
+
+        assert_eq!(nbr_proofs, 5);
+        assert_eq!(measured_proofs, vec![4, 5]);
+        assert_eq!(TimingStats::new(32, proving_times).count, 2);
+    }
+
+    #[test]
+    fn test_interrupt_flushes_measured_proofs() {
+        let stop = AtomicBool::new(false);
+        let mut nbr_proofs = 0;
+        // Interrupted during the second measured proof, which is still recorded
+        let proving_times = measure_proving(
+            1,
+            5,
+            &stop,
+            || {
+                nbr_proofs += 1;
+                if nbr_proofs == 3 {
+                    stop.store(true, Ordering::SeqCst);
+                }
+            },
+            |()| (),
+        );
+        assert_eq!(nbr_proofs, 3);
+        assert_eq!(proving_times.len(), 2);
+
+        let path = std::env::temp_dir().join("aptos-lc-test-interrupted-metrics.jsonl");
+        let row = TableRow::new(32, proving_times, vec![30, 30], 4_000_000);
+        let measured = BenchMetrics::new(
+            row.nbr_leaves,
+            row.proving_time,
+            row.verifying_time,
+            row.cycles,
+            1024,
+        );
+        write_metrics(&path, &[measured.clone()]).unwrap();
+        let metrics = aptos_lc_script::metrics::read_metrics(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(metrics, vec![measured]);
+
+        // Nothing is proven once interrupted
+        let proving_times = measure_proving::<()>(1, 5, &stop, || panic!("proof started"), |()| ());
+        assert!(proving_times.is_empty());
+    }
+
+    #[test]
+    fn test_cycle_histogram() {
+        let cycles = [1000, 1003, 1019, 1020, 1100, 1100, 1200];
+        let histogram = CycleHistogram::new(32, &cycles);
+        assert_eq!(histogram.buckets.iter().sum::<usize>(), cycles.len());
+        assert_eq!(histogram.buckets.len(), CYCLE_HISTOGRAM_BUCKETS as usize);
+        assert_eq!(histogram.bucket_width, 21);
+        assert_eq!(histogram.buckets[0], 4);
+        assert_eq!(histogram.buckets[9], 1);
+        assert_eq!(histogram.format().lines().count(), histogram.buckets.len());
+
+        // Identical counts fall in a single bucket
+        let histogram = CycleHistogram::new(32, &[500; 4]);
+        assert_eq!(histogram.buckets, vec![4]);
+        assert_eq!(histogram.mean, 500.0);
+    }
+
+    #[test]
+    fn test_bench_cycles_on_fresh_fixtures() {
+        let client = ProverClient::mock();
+        let mut nbr_fixtures = 0;
+        let next_stdin = || {
+            nbr_fixtures += 1;
+            proving_assets_from_nbr_leaves(8, 0, None, None, None)
+                .unwrap()
+                .stdin()
+                .unwrap()
+        };
+        let stop = AtomicBool::new(false);
+        let histogram = bench_cycles(&client, INCLUSION_ELF, 8, 3, &stop, next_stdin);
 
-            let json_output = serde_json::to_string(&timings).unwrap();
-            println!("{}", json_output);
+        assert_eq!(nbr_fixtures, 3);
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.buckets.iter().sum::<usize>(), 3);
+        assert!(histogram.min > 0);
+    }
+
+    #[test]
+    fn test_measure_setup() {
+        let stats =
+            measure_setup(&ProverClient::mock(), "inclusion", INCLUSION_ELF, 2, None).unwrap();
+
+        assert_eq!(stats.count, 2);
+        assert!(stats.min > 0.0);
+        assert!(stats.min <= stats.mean && stats.mean <= stats.max);
+    }
+
+    #[test]
+    fn test_prove_at_version_offset() {
+        let nbr_leaves = 8;
+        let leaf_idx = leaf_index(nbr_leaves, 5).unwrap();
+        assert_eq!(leaf_idx, 2);
+        assert!(matches!(
+            leaf_index(nbr_leaves, nbr_leaves),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+
+        let mut fixture = AptosWrapperFixture::new(nbr_leaves, 4, 3).unwrap();
+        fixture.generate_traffic().unwrap();
+        let proving_assets =
+            ProvingAssets::from_inclusion_data(fixture.inclusion_data(leaf_idx).unwrap()).unwrap();
+
+        let (mut public_values, _) = ProverClient::mock()
+            .execute(INCLUSION_ELF, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        let address = fixture.aptos_wrapper().accounts()[leaf_idx].address();
+        assert_eq!(output.key(), &account_state_key(address));
+        // The committed version is the one of the ledger info, which holds the transaction
+        let transaction_proof_assets = proving_assets.transaction_proof_assets();
+        let version = transaction_proof_assets
+            .ledger_info()
+            .unwrap()
+            .ledger_info()
+            .version();
+        assert_eq!(output.accumulator_leaves(), &Some(version + 1));
+        assert!(*transaction_proof_assets.transaction_index() <= version);
+        check_output(&proving_assets, &output);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let rows = NBR_LEAVES
+            .iter()
+            .map(|&nbr_leaves| TableRow::new(nbr_leaves, vec![1200, 1000], vec![30], 4_000_000))
+            .collect::<Vec<_>>();
+
+        let table = format_table(&rows);
+        let lines = table
+            .lines()
+            .filter(|line| line.starts_with('|'))
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 1 + NBR_LEAVES.len());
+        for column in TABLE_HEADER {
+            assert!(lines[0].contains(column));
+        }
+        for (line, nbr_leaves) in lines[1..].iter().zip(NBR_LEAVES) {
+            let cells = line
+                .split('|')
+                .map(str::trim)
+                .filter(|cell| !cell.is_empty())
+                .collect::<Vec<_>>();
+            assert_eq!(cells, [&nbr_leaves.to_string(), "1100", "30", "4000000"]);
         }
+        // All the lines are aligned
+        assert!(table.lines().all(|line| line.len() == lines[0].len()));
     }
 }
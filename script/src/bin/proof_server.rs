@@ -68,8 +68,8 @@ async fn main() -> anyhow::Result<()> {
     env_logger::init();
 
     let prover_client = Arc::new(ProverClient::default());
-    let (inclusion_pk, inclusion_vk) = inclusion::generate_keys(&prover_client);
-    let (epoch_pk, epoch_vk) = epoch_change::generate_keys(&prover_client);
+    let (inclusion_pk, inclusion_vk) = inclusion::generate_keys(&prover_client)?;
+    let (epoch_pk, epoch_vk) = epoch_change::generate_keys(&prover_client)?;
 
     let state = ServerState {
         prover_client,
@@ -151,7 +151,11 @@ async fn inclusion_proof(
             sparse_merkle_proof_assets,
             transaction_proof_assets,
             validator_verifier_assets,
-        );
+        )
+        .map_err(|err| {
+            error!("Invalid inclusion assets: {err}");
+            StatusCode::BAD_REQUEST
+        })?;
 
         let prover_client = state.prover_client.clone();
         let pk = state.inclusion_pk.clone();
@@ -0,0 +1,168 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proofs of inclusion packaged for a contract bridging Aptos state to Ethereum.
+//!
+//! ## ABI layout
+//!
+//! The values of the inclusion proven for the bridge are ABI-encoded as the static tuple
+//! `(bytes32 blockHash, bytes32 stateRoot, bytes32 key, bytes32 value,
+//! bytes32 validatorVerifierHash)`, i.e. the five 32 bytes words, in that order and without
+//! any offset or length:
+//!
+//! | Offset | Field                   | Value committed by the inclusion program       |
+//! |--------|-------------------------|------------------------------------------------|
+//! | `0`    | `blockHash`             | Identifier of the block of the ledger info     |
+//! | `32`   | `stateRoot`             | Root hash of the state tree                    |
+//! | `64`   | `key`                   | Key of the proven leaf                         |
+//! | `96`   | `value`                 | Hash of the value of the proven leaf           |
+//! | `128`  | `validatorVerifierHash` | Hash of the validator verifier of the signers  |
+//!
+//! ## Trusting the values
+//!
+//! The program does not commit this encoding: the proof only binds `public_values`, whose
+//! digest the SP1 verifier contract checks it against. `public_inputs_abi` is a convenience
+//! for off-chain callers, and a contract must not trust it on its own. It verifies the proof
+//! against `public_values` and decodes the fields from them, at the offsets of the layout of
+//! the inclusion output, after checking the magic and the `Included` status:
+//!
+//! | Offset | Field                   |
+//! |--------|-------------------------|
+//! | `0`    | Magic, `INCL`           |
+//! | `4`    | Status, `0` if included |
+//! | `5`    | `validatorVerifierHash` |
+//! | `37`   | `stateRoot`             |
+//! | `69`   | `blockHash`             |
+//! | `101`  | `key`                   |
+//! | `133`  | `value`                 |
+
+use std::time::Instant;
+
+use getset::Getters;
+use sp1_sdk::ProverClient;
+
+use crate::bundle::vk_hash;
+use crate::error::LightClientError;
+use crate::inclusion::{generate_keys, InclusionOutput, ProvingAssets};
+use crate::telemetry::record_proof;
+
+/// Length in bytes of the ABI encoding of the values proven for the bridge.
+pub const BRIDGE_INPUTS_ABI_LEN: usize = 5 * 32;
+
+/// A Groth16 proof of inclusion, along with what a bridge contract is called with.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct BridgeProof {
+    /// The proof, encoded as the SP1 verifier contract accepts it.
+    proof_bytes: Vec<u8>,
+    /// The public values committed by the program, whose digest the SP1 verifier contract
+    /// checks the proof against.
+    public_values: Vec<u8>,
+    /// The values of the inclusion, ABI-encoded as described in the module documentation.
+    /// They are not bound by the proof, see the module documentation.
+    public_inputs_abi: Vec<u8>,
+    /// Hash of the verifying key of the inclusion program, as returned by `bytes32`.
+    vkey_hash: [u8; 32],
+}
+
+/// ABI-encodes the values of an inclusion as the tuple described in the module
+/// documentation.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` of the inclusion program.
+///
+/// # Returns
+///
+/// The `BRIDGE_INPUTS_ABI_LEN` bytes of the encoding.
+pub fn encode_bridge_inputs(output: &InclusionOutput) -> Vec<u8> {
+    [
+        *output.block_hash(),
+        *output.state_hash(),
+        *output.key(),
+        *output.value(),
+        *output.validator_verifier_hash(),
+    ]
+    .concat()
+}
+
+/// Proves the inclusion of an account with a Groth16 proof, the cheapest to verify
+/// on-chain, and packages it for a bridge contract.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program and prove with.
+/// * `proving_assets` - The assets of the inclusion program.
+///
+/// # Returns
+///
+/// A `Result` containing the `BridgeProof`, or `Err` if the proving failed or if the
+/// program did not prove the inclusion.
+pub fn prove_for_bridge(
+    client: &ProverClient,
+    proving_assets: &ProvingAssets,
+) -> Result<BridgeProof, LightClientError> {
+    let _span = tracing::info_span!("prove_for_bridge", program = "inclusion").entered();
+
+    let (pk, vk) = generate_keys(client)?;
+    let start_proving = Instant::now();
+    let proof = client
+        .prove(&pk, proving_assets.stdin()?)
+        .groth16()
+        .run()
+        .map_err(|err| LightClientError::ProvingError {
+            program: "inclusion".to_string(),
+            source: err.into(),
+        })?;
+    record_proof("inclusion", start_proving.elapsed());
+    let output = InclusionOutput::from_public_values(&mut proof.public_values.clone())?;
+
+    Ok(BridgeProof {
+        proof_bytes: proof.bytes(),
+        public_values: proof.public_values.to_vec(),
+        public_inputs_abi: encode_bridge_inputs(&output),
+        vkey_hash: vk_hash(&vk),
+    })
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use super::*;
+    use crate::fixture::{generate_fixture, generate_proving_assets};
+    use aptos_lc_core::types::output::INCLUSION_OUTPUT_MAGIC;
+    use aptos_lc_core::types::status::InclusionStatus;
+
+    #[test]
+    fn test_prove_for_bridge() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let client = ProverClient::mock();
+        let bridge_proof = prove_for_bridge(&client, &proving_assets).unwrap();
+
+        // Decoded as `abi.decode(inputs, (bytes32, bytes32, bytes32, bytes32, bytes32))` does
+        let inputs = bridge_proof.public_inputs_abi();
+        assert_eq!(inputs.len(), BRIDGE_INPUTS_ABI_LEN);
+        let words = inputs.chunks_exact(32).collect::<Vec<_>>();
+        let expected = proving_assets.expected_inclusion().unwrap();
+        assert_eq!(words[0], expected.block_id());
+        assert_eq!(words[1], expected.state_root());
+        assert_eq!(words[2], expected.key());
+        assert_eq!(words[3], expected.value());
+        assert_eq!(words[4], expected.validator_verifier_hash());
+
+        // The words are read from the public values the proof binds, at the documented
+        // offsets
+        let public_values = bridge_proof.public_values();
+        assert_eq!(&public_values[..4], INCLUSION_OUTPUT_MAGIC);
+        assert_eq!(public_values[4], InclusionStatus::Included.to_u8());
+        for (offset, word) in [(69, 0), (37, 1), (101, 2), (133, 3), (5, 4)] {
+            assert_eq!(&public_values[offset..offset + 32], words[word]);
+        }
+
+        let (_, vk) = generate_keys(&client).unwrap();
+        assert_eq!(bridge_proof.vkey_hash(), &vk_hash(&vk));
+        // The encoded proof is prefixed by the first bytes of the hash of the Groth16 key
+        assert!(bridge_proof.proof_bytes().len() >= 4);
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proof bundles, shipping a proof to a verifier in a single file along with the program
+//! it was generated for and the hash of the verifying key it verifies against.
+
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    HashableKey, ProverClient, SP1ProofWithPublicValues, SP1VerifyingKey, SP1_CIRCUIT_VERSION,
+};
+
+use crate::client::verify_timed;
+use crate::error::LightClientError;
+use crate::{epoch_change, inclusion, signature};
+
+/// Identifier of the inclusion program in a `ProofBundle`.
+pub const INCLUSION_PROGRAM_ID: u8 = 0;
+/// Identifier of the epoch change program in a `ProofBundle`.
+pub const EPOCH_CHANGE_PROGRAM_ID: u8 = 1;
+/// Identifier of the signature program in a `ProofBundle`.
+pub const SIGNATURE_PROGRAM_ID: u8 = 2;
+
+/// Versions and verifying keys a binary proves with, to tie a proof to the binary that
+/// produced it when debugging a mismatch.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct BuildInfo {
+    /// Version of this crate.
+    crate_version: String,
+    /// Version of SP1 the proofs are generated with, e.g. `v2.0.0`.
+    sp1_version: String,
+    /// Hash of the verifying key of the embedded inclusion program, as returned by `bytes32`.
+    inclusion_vkey_hash: String,
+    /// Hash of the verifying key of the embedded epoch change program, as returned by
+    /// `bytes32`.
+    epoch_change_vkey_hash: String,
+}
+
+/// Returns the `BuildInfo` of the embedded programs. Both programs are set up to get their
+/// verifying keys, which takes a few seconds.
+///
+/// # Returns
+///
+/// A `Result` containing the `BuildInfo`, or `MissingElf` if a program was not built.
+pub fn build_info() -> Result<BuildInfo, LightClientError> {
+    // The verifying keys do not depend on the prover, so a mock client is enough
+    let client = ProverClient::mock();
+
+    Ok(BuildInfo {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        inclusion_vkey_hash: inclusion::generate_keys(&client)?.1.bytes32(),
+        epoch_change_vkey_hash: epoch_change::generate_keys(&client)?.1.bytes32(),
+    })
+}
+
+/// Metadata of a bundled proof, telling which toolchain produced it when it later fails
+/// to verify.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct ProofMeta {
+    /// Version of SP1 the proof was generated with, e.g. `v2.0.0`.
+    sp1_version: String,
+    /// Time the bundle was created, in seconds since the Unix epoch.
+    created_unix: u64,
+    /// Identifier of the program the proof was generated for.
+    program_id: u8,
+}
+
+/// A proof along with what a verifier needs to check it.
+///
+/// The program identifier tells how to read the public values of the proof, e.g. with
+/// `InclusionOutput::from_public_values` for `INCLUSION_PROGRAM_ID`.
+#[derive(Debug, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct ProofBundle {
+    /// Metadata recorded when the bundle was created to be saved.
+    metadata: ProofMeta,
+    /// Hash of the verifying key of the program, as returned by `bytes32`.
+    vkey_hash: [u8; 32],
+    /// The proof along with its public values.
+    proof: SP1ProofWithPublicValues,
+}
+
+impl ProofBundle {
+    /// Bundles a proof with the hash of the verifying key it verifies against, recording
+    /// its metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `program_id` - The identifier of the program the proof was generated for.
+    /// * `vk` - The verifying key of the program.
+    /// * `proof` - The proof to bundle.
+    ///
+    /// # Returns
+    ///
+    /// The `ProofBundle`.
+    pub fn new(program_id: u8, vk: &SP1VerifyingKey, proof: SP1ProofWithPublicValues) -> Self {
+        let created_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        Self {
+            metadata: ProofMeta {
+                sp1_version: proof.sp1_version.clone(),
+                created_unix,
+                program_id,
+            },
+            vkey_hash: vk_hash(vk),
+            proof,
+        }
+    }
+
+    /// Returns the identifier of the program the proof was generated for.
+    pub const fn program_id(&self) -> &u8 {
+        &self.metadata.program_id
+    }
+
+    /// Writes the bundle to a file, encoded with bincode.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the file was written, and `Err` otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LightClientError> {
+        let path = path.as_ref();
+        let bytes =
+            bincode::serialize(self).map_err(|err| LightClientError::SerializationError {
+                structure: "ProofBundle".to_string(),
+                source: err.into(),
+            })?;
+
+        fs::write(path, bytes).map_err(|err| LightClientError::IoError {
+            path: path.display().to_string(),
+            source: err,
+        })
+    }
+
+    /// Reads a bundle previously written with `save`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read from.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ProofBundle`, or `Err` if the file could not be read or
+    /// does not contain a bundle.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LightClientError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|err| LightClientError::IoError {
+            path: path.display().to_string(),
+            source: err,
+        })?;
+
+        bincode::deserialize(&bytes).map_err(|err| LightClientError::DeserializationError {
+            structure: "ProofBundle".to_string(),
+            source: err.into(),
+        })
+    }
+
+    /// Verifies the proof against the embedded program matching the bundle identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The `ProverClient` to set up the program and verify with.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the proof was verified, and `Err` if the identifier is
+    /// unknown, if the verifying key of the program does not match the bundled hash, or if
+    /// the proof could not be verified.
+    pub fn verify(&self, client: &ProverClient) -> Result<(), LightClientError> {
+        let vk = match self.metadata.program_id {
+            INCLUSION_PROGRAM_ID => inclusion::generate_keys(client)?.1,
+            EPOCH_CHANGE_PROGRAM_ID => epoch_change::generate_keys(client)?.1,
+            SIGNATURE_PROGRAM_ID => signature::generate_keys(client)?.1,
+            program_id => {
+                return Err(LightClientError::InvalidInput {
+                    input: "program_id".to_string(),
+                    reason: format!("unknown program identifier {program_id}"),
+                })
+            }
+        };
+
+        self.verify_with_vk(client, &vk)
+    }
+
+    /// Verifies the proof against a given verifying key, e.g. the one of a program loaded
+    /// from a file rather than embedded.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The `ProverClient` to verify with.
+    /// * `vk` - The verifying key to verify against.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the proof was verified, and `Err` if the verifying key
+    /// does not match the bundled hash or if the proof could not be verified. A proof
+    /// generated with another version of SP1 is only warned about, as it may still verify.
+    pub fn verify_with_vk(
+        &self,
+        client: &ProverClient,
+        vk: &SP1VerifyingKey,
+    ) -> Result<(), LightClientError> {
+        let actual = vk_hash(vk);
+        if actual != self.vkey_hash {
+            return Err(LightClientError::VerifyingKeyMismatch {
+                expected: format!("0x{}", hex::encode(self.vkey_hash)),
+                actual: format!("0x{}", hex::encode(actual)),
+            });
+        }
+
+        if self.metadata.sp1_version != SP1_CIRCUIT_VERSION {
+            tracing::warn!(
+                bundled = %self.metadata.sp1_version,
+                current = SP1_CIRCUIT_VERSION,
+                "the bundled proof was generated with another version of SP1"
+            );
+        }
+
+        verify_timed(client, &self.proof, vk, "bundled").map(|_| ())
+    }
+}
+
+/// Returns the hash of a verifying key, as the bytes behind `bytes32`.
+pub(crate) fn vk_hash(vk: &SP1VerifyingKey) -> [u8; 32] {
+    let mut hash = [0; 32];
+    // `bytes32` is always 32 bytes of hex behind a `0x` prefix
+    hex::decode_to_slice(vk.bytes32().trim_start_matches("0x"), &mut hash)
+        .expect("bytes32 should be the hex of a 32 bytes hash");
+    hash
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{Event, Level, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    use super::*;
+    use crate::fixture::{generate_fixture, generate_proving_assets};
+    use crate::inclusion::generate_keys;
+
+    /// Counts the warnings emitted while it is the default subscriber.
+    struct Warnings(Arc<Mutex<usize>>);
+
+    impl<S: Subscriber> Layer<S> for Warnings {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            if *event.metadata().level() == Level::WARN {
+                *self.0.lock().unwrap() += 1;
+            }
+        }
+    }
+
+    fn count_warnings(f: impl FnOnce()) -> usize {
+        let warnings = Arc::new(Mutex::new(0));
+        let subscriber = tracing_subscriber::registry().with(Warnings(warnings.clone()));
+        tracing::subscriber::with_default(subscriber, f);
+
+        // The subscriber was dropped along with its reference
+        Arc::try_unwrap(warnings).unwrap().into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_save_load_and_verify_bundle() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        // The mock prover only binds the public values to Plonk proofs
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .plonk()
+            .run()
+            .unwrap();
+
+        let path = std::env::temp_dir().join("aptos-lc-test-proof-bundle.bin");
+        ProofBundle::new(INCLUSION_PROGRAM_ID, &vk, proof)
+            .save(&path)
+            .unwrap();
+        let bundle = ProofBundle::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(bundle.program_id(), &INCLUSION_PROGRAM_ID);
+        assert_eq!(bundle.metadata().sp1_version(), SP1_CIRCUIT_VERSION);
+        bundle.verify(&client).unwrap();
+
+        // The verifying key of another program does not match the bundled hash
+        let (_, epoch_change_vk) = epoch_change::generate_keys(&client).unwrap();
+        assert!(matches!(
+            bundle.verify_with_vk(&client, &epoch_change_vk),
+            Err(LightClientError::VerifyingKeyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_bundle_unknown_program() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+
+        assert!(matches!(
+            ProofBundle::new(SIGNATURE_PROGRAM_ID + 1, &vk, proof).verify(&client),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_build_info_json() {
+        let info = build_info().unwrap();
+        let json = serde_json::to_string(&info).unwrap();
+
+        for field in [
+            "crate_version",
+            "sp1_version",
+            "inclusion_vkey_hash",
+            "epoch_change_vkey_hash",
+        ] {
+            assert!(json.contains(&format!("\"{field}\":")), "{json}");
+        }
+        assert_eq!(info.sp1_version(), SP1_CIRCUIT_VERSION);
+        let (_, vk) = generate_keys(&ProverClient::mock()).unwrap();
+        assert_eq!(info.inclusion_vkey_hash(), &vk.bytes32());
+    }
+
+    #[test]
+    fn test_bundle_metadata() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .plonk()
+            .run()
+            .unwrap();
+
+        let mut bundle = ProofBundle::new(INCLUSION_PROGRAM_ID, &vk, proof);
+        assert_eq!(bundle.metadata().program_id(), &INCLUSION_PROGRAM_ID);
+        assert!(bundle.metadata().created_unix() > &0);
+
+        let path = std::env::temp_dir().join("aptos-lc-test-proof-bundle-metadata.bin");
+        bundle.save(&path).unwrap();
+        let loaded = ProofBundle::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.metadata(), bundle.metadata());
+
+        assert_eq!(count_warnings(|| bundle.verify(&client).unwrap()), 0);
+        bundle.metadata.sp1_version = "v0.0.0".to_string();
+        assert_eq!(count_warnings(|| bundle.verify(&client).unwrap()), 1);
+    }
+}
@@ -0,0 +1,893 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers to construct the `ProverClient` used to execute and prove the light client programs,
+//! and to drive it.
+
+#[cfg(feature = "cuda")]
+use sp1_sdk::CudaProver;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use sp1_sdk::action::Prove;
+use sp1_sdk::network::client::NetworkClient;
+use sp1_sdk::provers::ProverType;
+use sp1_sdk::{
+    CpuProver, ExecutionReport, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1ProvingKey,
+    SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::error::LightClientError;
+use crate::io::{load_or_setup_keys, load_vk, parse_value_enum};
+use crate::telemetry::{record_cycles, record_proof, record_verification};
+
+/// Environment variable used by the SP1 SDK to select the prover behind `ProverClient::new`.
+pub const SP1_PROVER_ENV: &str = "SP1_PROVER";
+
+/// Environment variable holding the key used by the SP1 SDK to authenticate to the network.
+pub const SP1_PRIVATE_KEY_ENV: &str = "SP1_PRIVATE_KEY";
+
+/// Environment variable sizing the rayon thread pool the local SP1 prover runs on.
+pub const RAYON_NUM_THREADS_ENV: &str = "RAYON_NUM_THREADS";
+
+/// Kind of proof generated by the prover, from the fastest to generate to the cheapest to
+/// verify on-chain.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProofType {
+    /// A proof for each shard of the execution, the default of SP1.
+    #[default]
+    Core,
+    /// A single STARK proof of constant size.
+    Compressed,
+    /// A Plonk proof over BN254, verifiable on-chain.
+    Plonk,
+    /// A Groth16 proof over BN254, the cheapest to verify on-chain.
+    Groth16,
+}
+
+impl ProofType {
+    /// Sets the kind of proof generated by a proving action.
+    ///
+    /// # Arguments
+    ///
+    /// * `prove` - The proving action, as returned by `ProverClient::prove`.
+    ///
+    /// # Returns
+    ///
+    /// The proving action, generating this kind of proof.
+    pub fn apply(self, prove: Prove<'_>) -> Prove<'_> {
+        match self {
+            ProofType::Core => prove.core(),
+            ProofType::Compressed => prove.compressed(),
+            ProofType::Plonk => prove.plonk(),
+            ProofType::Groth16 => prove.groth16(),
+        }
+    }
+}
+
+impl FromStr for ProofType {
+    type Err = LightClientError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_value_enum(input, "proof type")
+    }
+}
+
+/// Creates a `ProverClient`, either following the environment or forcing local proving.
+///
+/// # Arguments
+///
+/// * `local` - Whether to force local proving on CPU, regardless of `SP1_PROVER`.
+/// * `gpu` - Whether to force local proving on GPU, regardless of `SP1_PROVER`. Takes
+///   precedence over `local`.
+///
+/// # Returns
+///
+/// A `Result` containing the `ProverClient`, or `Err` if local proving is forced
+/// while the environment selects another prover, or if GPU proving is requested
+/// without the `cuda` feature.
+pub fn prover_client(local: bool, gpu: bool) -> Result<ProverClient, LightClientError> {
+    if gpu {
+        gpu_prover_client()
+    } else if local {
+        local_prover_client()
+    } else {
+        Ok(ProverClient::new())
+    }
+}
+
+/// Sets the number of threads the local prover runs on.
+///
+/// Rayon sizes its global pool once, when it is first used, so this must be called
+/// before the `ProverClient` is constructed. The proofs are generated one after the
+/// other, each of them on all of these threads. Without a number of threads, the
+/// environment is left untouched and the SDK defaults to one thread per core.
+///
+/// # Arguments
+///
+/// * `threads` - The number of threads, if any.
+pub fn set_prover_threads(threads: Option<NonZeroUsize>) {
+    if let Some(value) = rayon_num_threads(threads) {
+        std::env::set_var(RAYON_NUM_THREADS_ENV, value);
+    }
+}
+
+/// Value of `RAYON_NUM_THREADS_ENV` to run the local prover on `threads` threads, if any.
+fn rayon_num_threads(threads: Option<NonZeroUsize>) -> Option<String> {
+    threads.map(|threads| threads.to_string())
+}
+
+/// Creates a `ProverClient` that always proves on the local machine, on CPU.
+///
+/// Contrary to `ProverClient::new`, this never reaches the Succinct network. As a
+/// misconfigured environment is most likely a mistake, an error is returned if
+/// `SP1_PROVER` selects another prover.
+///
+/// # Returns
+///
+/// A `Result` containing the `ProverClient`, or `Err` if `SP1_PROVER` is set to
+/// something else than `local`.
+pub fn local_prover_client() -> Result<ProverClient, LightClientError> {
+    check_local_environment(std::env::var(SP1_PROVER_ENV).ok().as_deref())?;
+
+    Ok(local_prover())
+}
+
+/// Creates a `ProverClient` that always proves on the local GPU, through the SP1 CUDA prover.
+///
+/// The same environment checks as for `local_prover_client` apply.
+///
+/// # Returns
+///
+/// A `Result` containing the `ProverClient`, or `Err` if `SP1_PROVER` is set to
+/// something else than `local` or if the crate was built without the `cuda` feature.
+pub fn gpu_prover_client() -> Result<ProverClient, LightClientError> {
+    check_local_environment(std::env::var(SP1_PROVER_ENV).ok().as_deref())?;
+
+    gpu_prover()
+}
+
+/// Checks that the prover behind a `ProverClient` can be reached, to fail fast before a
+/// batch of proofs rather than midway through it.
+///
+/// Local provers are always available. The network prover is pinged by fetching the
+/// nonce of the account authenticated by `SP1_PRIVATE_KEY`, which does not request any
+/// proof.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to check.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the prover is available, and `Err` otherwise.
+pub fn check_prover_available(client: &ProverClient) -> Result<(), LightClientError> {
+    check_available(client.prover.id(), ping_network)
+}
+
+/// Executes a program and then proves it from the same `SP1Stdin`, to get both the
+/// diagnostics of the execution and the proof in one pass.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to execute and prove with.
+/// * `pk` - The proving key of the program, which also carries its ELF.
+/// * `stdin` - The inputs of the program.
+/// * `proof_type` - The kind of proof to generate.
+/// * `program` - The name of the program, used in the errors.
+///
+/// # Returns
+///
+/// A `Result` containing the `ExecutionReport` and the public values of the execution,
+/// along with the proof, or `Err` if the execution or the proving failed.
+pub fn execute_and_prove(
+    client: &ProverClient,
+    pk: &SP1ProvingKey,
+    stdin: &SP1Stdin,
+    proof_type: ProofType,
+    program: &str,
+) -> Result<(ExecutionReport, SP1PublicValues, SP1ProofWithPublicValues), LightClientError> {
+    let proving_error = |err: anyhow::Error| LightClientError::ProvingError {
+        program: program.to_string(),
+        source: err.into(),
+    };
+
+    let (public_values, report) = client
+        .execute(&pk.elf, stdin.clone())
+        .run()
+        .map_err(proving_error)?;
+    record_cycles(program, report.total_instruction_count());
+    let start_proving = Instant::now();
+    let proof = proof_type
+        .apply(client.prove(pk, stdin.clone()))
+        .run()
+        .map_err(proving_error)?;
+    record_proof(program, start_proving.elapsed());
+
+    Ok((report, public_values, proof))
+}
+
+/// Proves a program on a worker thread, giving up if the proof is not generated in time,
+/// e.g. to avoid hanging a CI job.
+///
+/// The SP1 prover can not be interrupted, so the worker thread keeps proving in the
+/// background after the timeout, until the proof completes or the process exits.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to prove with.
+/// * `pk` - The proving key of the program.
+/// * `stdin` - The inputs of the program.
+/// * `proof_type` - The kind of proof to generate.
+/// * `program` - The name of the program, used in the errors.
+/// * `timeout` - The maximum time to wait for the proof.
+///
+/// # Returns
+///
+/// A `Result` containing the proof, or `Err` if the proving failed or did not complete
+/// within `timeout`.
+pub fn prove_with_timeout(
+    client: Arc<ProverClient>,
+    pk: SP1ProvingKey,
+    stdin: SP1Stdin,
+    proof_type: ProofType,
+    program: &str,
+    timeout: Duration,
+) -> Result<SP1ProofWithPublicValues, LightClientError> {
+    wait_for_proof(
+        move || proof_type.apply(client.prove(&pk, stdin)).run(),
+        program,
+        timeout,
+    )
+}
+
+/// Runs `prove` on a worker thread, waiting at most `timeout` for the proof it returns.
+fn wait_for_proof<E>(
+    prove: impl FnOnce() -> Result<SP1ProofWithPublicValues, E> + Send + 'static,
+    program: &str,
+    timeout: Duration,
+) -> Result<SP1ProofWithPublicValues, LightClientError>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+{
+    let start_proving = Instant::now();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        // The receiver is gone if the proof came too late, which is fine.
+        let _ = sender.send(prove());
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(res) => {
+            let proof = res.map_err(|err| LightClientError::ProvingError {
+                program: program.to_string(),
+                source: err.into(),
+            })?;
+            record_proof(program, start_proving.elapsed());
+            Ok(proof)
+        }
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(LightClientError::Timeout {
+            program: program.to_string(),
+            timeout,
+        }),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(LightClientError::ProvingError {
+            program: program.to_string(),
+            source: "the proving thread panicked".into(),
+        }),
+    }
+}
+
+/// Verifies a proof, measuring the time spent doing so.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to verify with.
+/// * `proof` - The proof to verify.
+/// * `vk` - The verifying key of the program.
+/// * `program` - The name of the program, used in the errors.
+///
+/// # Returns
+///
+/// A `Result` containing the time spent verifying the proof, or `Err` if the proof could
+/// not be verified.
+pub fn verify_timed(
+    client: &ProverClient,
+    proof: &SP1ProofWithPublicValues,
+    vk: &SP1VerifyingKey,
+    program: &str,
+) -> Result<Duration, LightClientError> {
+    let start_verifying = Instant::now();
+    client
+        .verify(proof, vk)
+        .map_err(|err| LightClientError::VerificationError {
+            check: format!("{program} proof"),
+            source: err.into(),
+        })?;
+    let verifying_time = start_verifying.elapsed();
+    record_verification(program, verifying_time);
+
+    Ok(verifying_time)
+}
+
+/// Verifies a proof against a verifying key exported with `inclusion_vk_bytes` or
+/// `epoch_change_vk_bytes`, on the local CPU verifier. No program is set up, so this is
+/// cheap enough for a service that only verifies proofs.
+///
+/// # Arguments
+///
+/// * `proof` - The proof to verify.
+/// * `vk_bytes` - The serialized `SP1VerifyingKey` of the program.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the proof was verified, and `Err` if the bytes do not
+/// contain a serialized `SP1VerifyingKey` or if the proof could not be verified.
+pub fn verify_with_vk_bytes(
+    proof: &SP1ProofWithPublicValues,
+    vk_bytes: &[u8],
+) -> Result<(), LightClientError> {
+    let vk = load_vk(vk_bytes)?;
+
+    local_prover()
+        .verify(proof, &vk)
+        .map_err(|err| LightClientError::VerificationError {
+            check: "proof".to_string(),
+            source: err.into(),
+        })
+}
+
+/// Sets up the keys of a program, failing early if its ELF is empty. An ELF that was not
+/// built is embedded as an empty slice, which the SDK would only reject deep in the setup.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program with.
+/// * `program` - The directory of the program, e.g. `epoch-change`.
+/// * `elf` - The ELF of the program.
+///
+/// # Returns
+///
+/// A `Result` containing the proving and the verifying keys of the program, or
+/// `MissingElf` if the ELF is empty.
+pub fn setup_program(
+    client: &ProverClient,
+    program: &str,
+    elf: &[u8],
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), LightClientError> {
+    if elf.is_empty() {
+        return Err(LightClientError::MissingElf {
+            program: program.to_string(),
+        });
+    }
+
+    Ok(client.setup(elf))
+}
+
+/// Sets up the keys of a program and measures the time it took, through the key cache when
+/// one is given. With a cache, the first setup of an ELF is cold and the following ones
+/// only load the cached keys.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program with.
+/// * `elf` - The ELF of the program.
+/// * `key_cache` - The directory of the cached keys, if any.
+///
+/// # Returns
+///
+/// A `Result` containing the time spent setting up the keys, or `Err` if the cached keys
+/// could not be read or written.
+pub fn setup_timed(
+    client: &ProverClient,
+    elf: &[u8],
+    key_cache: Option<&Path>,
+) -> Result<Duration, LightClientError> {
+    let start_setup = Instant::now();
+    match key_cache {
+        Some(cache_dir) => {
+            load_or_setup_keys(client, elf, cache_dir)?;
+        }
+        None => {
+            client.setup(elf);
+        }
+    }
+
+    Ok(start_setup.elapsed())
+}
+
+/// Verifies many proofs of the same program, e.g. the inclusion proofs held by a relayer.
+///
+/// Verifying is CPU-bound, so the proofs are split across the available threads.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to verify with.
+/// * `vk` - The verifying key shared by all the proofs.
+/// * `proofs` - The proofs to verify.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if all the proofs were verified, and `Err` containing the
+/// lowest index of a proof that could not be verified otherwise.
+pub fn verify_many(
+    client: &ProverClient,
+    vk: &SP1VerifyingKey,
+    proofs: &[SP1ProofWithPublicValues],
+) -> Result<(), LightClientError> {
+    let nbr_threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = proofs.len().div_ceil(nbr_threads).max(1);
+
+    let failure = std::thread::scope(|scope| {
+        let handles: Vec<_> = proofs
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_idx, chunk)| {
+                scope.spawn(move || {
+                    chunk.iter().enumerate().find_map(|(idx, proof)| {
+                        client
+                            .verify(proof, vk)
+                            .err()
+                            .map(|err| (chunk_idx * chunk_size + idx, err))
+                    })
+                })
+            })
+            .collect();
+
+        // The chunks are in order, so the first failure found has the lowest index
+        handles.into_iter().find_map(|handle| {
+            handle
+                .join()
+                .expect("a thread panicked while verifying proofs")
+        })
+    });
+
+    match failure {
+        Some((index, err)) => Err(LightClientError::BatchVerificationError {
+            index,
+            source: err.into(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Returns the hash of the verifying key a proof was generated for, as returned by
+/// `bytes32`, when the proof carries it.
+///
+/// Plonk and Groth16 proofs expose it as their first public input. Core and compressed
+/// proofs do not, and are only bound to a program by verifying them against its key.
+///
+/// # Arguments
+///
+/// * `proof` - The proof to read the hash of the verifying key from.
+///
+/// # Returns
+///
+/// A `Result` containing the hash of the verifying key, or `None` for core and compressed
+/// proofs, or `Err` if the public input is not a 32 bytes number.
+pub fn proof_vkey_hash(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<Option<[u8; 32]>, LightClientError> {
+    let vkey_hash = match &proof.proof {
+        SP1Proof::Plonk(proof) => &proof.public_inputs[0],
+        SP1Proof::Groth16(proof) => &proof.public_inputs[0],
+        SP1Proof::Core(_) | SP1Proof::Compressed(_) => return Ok(None),
+    };
+
+    decimal_to_bytes32(vkey_hash)
+        .map(Some)
+        .ok_or_else(|| LightClientError::InvalidInput {
+            input: "vkey hash public input".to_string(),
+            reason: format!("'{vkey_hash}' is not a 32 bytes number"),
+        })
+}
+
+/// Converts a number written in decimal, as the BN254 public inputs are, to 32 big endian
+/// bytes.
+fn decimal_to_bytes32(decimal: &str) -> Option<[u8; 32]> {
+    if decimal.is_empty() {
+        return None;
+    }
+
+    let mut bytes = [0u8; 32];
+    for digit in decimal.chars() {
+        let mut carry = digit.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = (value & 0xff) as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+
+    Some(bytes)
+}
+
+/// Computes the digest of the public values of a proof, as the on-chain SP1 verifiers do
+/// when checking it, i.e. their SHA-256 hash with the top 3 bits masked to fit the BN254
+/// scalar field.
+///
+/// # Arguments
+///
+/// * `public_values` - The public values committed by the program.
+///
+/// # Returns
+///
+/// The digest of the public values.
+pub fn public_values_digest(public_values: &SP1PublicValues) -> [u8; 32] {
+    let mut digest: [u8; 32] = public_values
+        .hash()
+        .try_into()
+        .expect("SHA-256 hashes are 32 bytes long");
+    digest[0] &= 0b0001_1111;
+
+    digest
+}
+
+/// Returns the size of a proof along with its public values, as saved with bincode. The
+/// size varies widely with the proof type, from kilobytes to gigabytes for a core proof
+/// of many shards.
+///
+/// # Arguments
+///
+/// * `proof` - The proof to measure.
+///
+/// # Returns
+///
+/// The size of the serialized proof, in bytes.
+pub fn proof_size_bytes(proof: &SP1ProofWithPublicValues) -> usize {
+    bincode::serialized_size(proof).expect("a proof should always be serializable") as usize
+}
+
+fn local_prover() -> ProverClient {
+    ProverClient {
+        prover: Box::new(CpuProver::new()),
+    }
+}
+
+#[cfg(feature = "cuda")]
+fn gpu_prover() -> Result<ProverClient, LightClientError> {
+    Ok(ProverClient {
+        prover: Box::new(CudaProver::new()),
+    })
+}
+
+#[cfg(not(feature = "cuda"))]
+fn gpu_prover() -> Result<ProverClient, LightClientError> {
+    Err(LightClientError::FeatureDisabled {
+        capability: "GPU proving".to_string(),
+        feature: "cuda".to_string(),
+    })
+}
+
+fn check_available(
+    prover_type: ProverType,
+    ping_network: impl FnOnce() -> anyhow::Result<()>,
+) -> Result<(), LightClientError> {
+    if prover_type != ProverType::Network {
+        return Ok(());
+    }
+
+    ping_network().map_err(|err| LightClientError::ProverUnavailable {
+        prover: "network".to_string(),
+        source: err.into(),
+    })
+}
+
+fn ping_network() -> anyhow::Result<()> {
+    let private_key = std::env::var(SP1_PRIVATE_KEY_ENV)
+        .map_err(|_| anyhow!("{SP1_PRIVATE_KEY_ENV} must be set for remote proving"))?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(NetworkClient::new(&private_key).get_nonce())?;
+
+    Ok(())
+}
+
+fn check_local_environment(sp1_prover: Option<&str>) -> Result<(), LightClientError> {
+    match sp1_prover {
+        None => Ok(()),
+        Some(value) if value.eq_ignore_ascii_case("local") => Ok(()),
+        Some(value) => Err(LightClientError::IncompatibleEnvironment {
+            variable: SP1_PROVER_ENV.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sp1_sdk::Prover;
+
+    #[test]
+    fn test_check_local_environment() {
+        assert!(check_local_environment(None).is_ok());
+        assert!(check_local_environment(Some("local")).is_ok());
+        assert!(check_local_environment(Some("LOCAL")).is_ok());
+
+        for value in ["network", "mock", ""] {
+            assert!(matches!(
+                check_local_environment(Some(value)),
+                Err(LightClientError::IncompatibleEnvironment { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check_prover_available() {
+        check_prover_available(&ProverClient::mock()).unwrap();
+
+        // Only the network prover is pinged
+        let unreachable = || Err(anyhow!("connection refused"));
+        check_available(ProverType::Mock, unreachable).unwrap();
+        check_available(ProverType::Network, || Ok(())).unwrap();
+        match check_available(ProverType::Network, unreachable) {
+            Err(LightClientError::ProverUnavailable { prover, source }) => {
+                assert_eq!(prover, "network");
+                assert_eq!(source.to_string(), "connection refused");
+            }
+            other => panic!("expected an unavailable prover, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_proof_type() {
+        use crate::io::ProofEncoding;
+
+        assert_eq!("groth16".parse::<ProofType>().unwrap(), ProofType::Groth16);
+        assert_eq!(
+            "JSON".parse::<ProofEncoding>().unwrap(),
+            ProofEncoding::Json
+        );
+
+        match "stark".parse::<ProofType>() {
+            Err(LightClientError::InvalidInput { input, reason }) => {
+                assert_eq!(input, "proof type");
+                assert!(reason.ends_with("expected one of: core, compressed, plonk, groth16"));
+            }
+            other => panic!("expected an invalid input, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_rayon_num_threads() {
+        assert_eq!(
+            rayon_num_threads(NonZeroUsize::new(3)).as_deref(),
+            Some("3")
+        );
+        assert_eq!(rayon_num_threads(None), None);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    #[ignore = "proves with the CPU prover"]
+    fn test_verify_with_vk_bytes() {
+        use crate::epoch_change::epoch_change_vk_bytes;
+        use crate::fixture::generate_inclusion_stdin;
+        use crate::inclusion::{generate_keys, inclusion_vk_bytes};
+
+        let stdin = generate_inclusion_stdin();
+
+        // The keys are exported once, the verification does not set up any program
+        let client = local_prover();
+        let vk_bytes = inclusion_vk_bytes(&client).unwrap();
+        let (pk, _) = generate_keys(&client).unwrap();
+        let proof = client.prove(&pk, stdin).run().unwrap();
+
+        verify_with_vk_bytes(&proof, &vk_bytes).unwrap();
+        assert!(matches!(
+            verify_with_vk_bytes(&proof, &epoch_change_vk_bytes(&client).unwrap()),
+            Err(LightClientError::VerificationError { .. })
+        ));
+        assert!(matches!(
+            verify_with_vk_bytes(&proof, &vk_bytes[1..]),
+            Err(LightClientError::DeserializationError { .. })
+        ));
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    #[ignore = "proves with the CPU prover"]
+    fn test_compressed_proof_is_smaller_than_core() {
+        use crate::fixture::generate_inclusion_stdin;
+        use crate::inclusion::generate_keys;
+
+        let stdin = generate_inclusion_stdin();
+
+        // The mock prover does not produce proofs of realistic sizes
+        let client = local_prover();
+        let (pk, _) = generate_keys(&client).unwrap();
+        let core_proof = client.prove(&pk, stdin.clone()).run().unwrap();
+        let compressed_proof = client.prove(&pk, stdin).compressed().run().unwrap();
+
+        assert!(proof_size_bytes(&compressed_proof) < proof_size_bytes(&core_proof));
+    }
+
+    #[test]
+    fn test_setup_timed() {
+        use crate::inclusion::INCLUSION_ELF;
+
+        let client = ProverClient::mock();
+        assert!(setup_timed(&client, INCLUSION_ELF, None).unwrap() > Duration::ZERO);
+
+        let cache_dir = std::env::temp_dir().join("aptos-lc-test-setup-timed");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let cold = setup_timed(&client, INCLUSION_ELF, Some(&cache_dir)).unwrap();
+        let warm = setup_timed(&client, INCLUSION_ELF, Some(&cache_dir)).unwrap();
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+
+        assert!(cold > Duration::ZERO);
+        assert!(warm > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_setup_program_empty_elf() {
+        let client = ProverClient::mock();
+
+        assert!(matches!(
+            setup_program(&client, "inclusion", &[]),
+            Err(LightClientError::MissingElf { program }) if program == "inclusion"
+        ));
+    }
+
+    #[test]
+    fn test_local_prover_is_local() {
+        let client = local_prover();
+
+        assert_eq!(client.prover.id(), ProverType::Cpu);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_execute_and_prove() {
+        use crate::fixture::generate_inclusion_stdin;
+        use crate::inclusion::generate_keys;
+
+        let stdin = generate_inclusion_stdin();
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let (report, public_values, proof) =
+            execute_and_prove(&client, &pk, &stdin, ProofType::Plonk, "inclusion").unwrap();
+
+        assert!(report.total_instruction_count() > 0);
+        assert_eq!(public_values.as_slice(), proof.public_values.as_slice());
+        assert!(matches!(proof.proof, sp1_sdk::SP1Proof::Plonk(_)));
+        client.verify(&proof, &vk).unwrap();
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_prove_with_timeout() {
+        use crate::fixture::generate_inclusion_stdin;
+        use crate::inclusion::generate_keys;
+
+        // A proof blocked until the timeout is hit
+        let (release, blocked) = mpsc::channel::<()>();
+        assert!(matches!(
+            wait_for_proof(
+                move || {
+                    let _ = blocked.recv();
+                    Err("released")
+                },
+                "inclusion",
+                Duration::from_millis(10)
+            ),
+            Err(LightClientError::Timeout { .. })
+        ));
+        drop(release);
+
+        let client = Arc::new(ProverClient::mock());
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let stdin = generate_inclusion_stdin();
+        let proof = prove_with_timeout(
+            client.clone(),
+            pk,
+            stdin,
+            ProofType::Core,
+            "inclusion",
+            Duration::MAX,
+        )
+        .unwrap();
+        client.verify(&proof, &vk).unwrap();
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_verify_timed() {
+        use crate::fixture::generate_inclusion_stdin;
+        use crate::inclusion::generate_keys;
+
+        let stdin = generate_inclusion_stdin();
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client.prove(&pk, stdin).run().unwrap();
+
+        let verifying_time = verify_timed(&client, &proof, &vk, "inclusion").unwrap();
+        assert!(verifying_time > Duration::ZERO);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_verify_many() {
+        use crate::fixture::generate_inclusion_stdin;
+        use crate::inclusion::generate_keys;
+        use sp1_sdk::SP1PublicValues;
+
+        let stdin = generate_inclusion_stdin();
+
+        // The mock prover only binds the public values to Plonk proofs
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client.prove(&pk, stdin).plonk().run().unwrap();
+        let mut proofs = vec![proof; 5];
+
+        verify_many(&client, &vk, &proofs).unwrap();
+        verify_many(&client, &vk, &[]).unwrap();
+
+        proofs[3].public_values = SP1PublicValues::from(&[0; 4]);
+        match verify_many(&client, &vk, &proofs) {
+            Err(LightClientError::BatchVerificationError { index, .. }) => assert_eq!(index, 3),
+            other => panic!("expected a batch verification error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decimal_to_bytes32() {
+        let mut expected = [0; 32];
+        expected[30..].copy_from_slice(&0x1234u16.to_be_bytes());
+        assert_eq!(decimal_to_bytes32("4660"), Some(expected));
+        assert_eq!(decimal_to_bytes32("0"), Some([0; 32]));
+
+        // 2^256 does not fit in 32 bytes
+        let two_pow_256 =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        assert_eq!(decimal_to_bytes32(two_pow_256), None);
+        assert_eq!(decimal_to_bytes32(""), None);
+        assert_eq!(decimal_to_bytes32("12a"), None);
+    }
+
+    #[test]
+    fn test_public_values_digest() {
+        let committed = [7u8; 64];
+        let digest = public_values_digest(&SP1PublicValues::from(&committed));
+        assert_eq!(
+            digest,
+            public_values_digest(&SP1PublicValues::from(&committed))
+        );
+        assert_eq!(digest[0] & 0b1110_0000, 0);
+        assert_eq!(digest[1..], SP1PublicValues::from(&committed).hash()[1..]);
+
+        let mut flipped = committed;
+        flipped[42] ^= 1;
+        assert_ne!(
+            digest,
+            public_values_digest(&SP1PublicValues::from(&flipped))
+        );
+    }
+
+    #[cfg(not(feature = "cuda"))]
+    #[test]
+    fn test_gpu_prover_requires_cuda() {
+        assert!(matches!(
+            gpu_prover(),
+            Err(LightClientError::FeatureDisabled { .. })
+        ));
+    }
+
+    /// Starting the CUDA prover requires a GPU, so only check that the `cuda` path builds.
+    #[cfg(feature = "cuda")]
+    #[test]
+    fn test_gpu_prover_builds() {
+        let _: fn() -> Result<ProverClient, LightClientError> = gpu_prover;
+    }
+}
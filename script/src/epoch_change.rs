@@ -1,16 +1,850 @@
-use sp1_sdk::{ProverClient, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+use aptos_types::state_proof::StateProof;
+use aptos_types::trusted_state::TrustedState as AptosTrustedState;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{ProverClient, SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerifyingKey};
+use aptos_lc_core::types::output::EPOCH_CHANGE_OUTPUT_MAGIC;
+use aptos_lc_core::types::status::EpochChangeStatus;
+use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState, TrustedStateChange};
+use aptos_lc_core::types::validator::{ValidatorConsensusInfo, ValidatorVerifier};
+use aptos_lc_core::types::epoch_state::EpochState;
+use aptos_lc_core::types::AccountAddress;
+use crate::client::setup_program;
+use crate::error::LightClientError;
+use crate::io::vk_to_bytes;
+use crate::output::{serialize_hex, PublicValuesReader};
 
 pub const EPOCH_CHANGE_ELF: &[u8] = include_bytes!("../../programs/epoch-change/elf/riscv32im-succinct-zkvm-elf");
 
+/// Inputs of the epoch change program, serialized as it expects them.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct EpochChangeInputs {
+    /// The trusted state to ratchet, serialized with bcs.
+    trusted_state: Vec<u8>,
+    /// The epoch change proof from the trusted state to the next epoch, serialized with bcs.
+    epoch_change_proof: Vec<u8>,
+    /// Hash of the validator verifier the trusted state is expected to carry, pinning the
+    /// chain the proof follows. If absent, any trusted state is ratcheted.
+    #[serde(default)]
+    expected_start_validator_hash: Option<[u8; 32]>,
+}
+
+impl EpochChangeInputs {
+    pub const fn new(trusted_state: Vec<u8>, epoch_change_proof: Vec<u8>) -> Self {
+        Self {
+            trusted_state,
+            epoch_change_proof,
+            expected_start_validator_hash: None,
+        }
+    }
+
+    /// Pins the validator verifier the trusted state is expected to carry. The program
+    /// then commits `EpochChangeStatus::StartValidatorMismatch` rather than ratcheting a
+    /// trusted state of another chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_start_validator_hash` - The hash of the expected validator verifier.
+    ///
+    /// # Returns
+    ///
+    /// The `EpochChangeInputs` with the pinned validator verifier.
+    pub const fn with_expected_start_validator_hash(
+        mut self,
+        expected_start_validator_hash: [u8; 32],
+    ) -> Self {
+        self.expected_start_validator_hash = Some(expected_start_validator_hash);
+        self
+    }
+
+    /// Bundles the inputs of the epoch change program out of a trusted state and a
+    /// `StateProof` starting from its version, as returned by an Aptos node.
+    ///
+    /// # Arguments
+    ///
+    /// * `trusted_state` - The trusted state to ratchet.
+    /// * `state_proof` - The state proof carrying the epoch change.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `EpochChangeInputs`, or `Err` if the values could not be
+    /// serialized.
+    pub fn from_state_proof(
+        trusted_state: &AptosTrustedState,
+        state_proof: &StateProof,
+    ) -> Result<Self, LightClientError> {
+        let trusted_state =
+            bcs::to_bytes(trusted_state).map_err(|err| LightClientError::SerializationError {
+                structure: "TrustedState".to_string(),
+                source: err.into(),
+            })?;
+
+        Ok(Self::new(
+            trusted_state,
+            epoch_change_assets_from_state_proof(state_proof)?,
+        ))
+    }
+
+    /// Returns the `SP1Stdin` for the epoch change program.
+    pub fn stdin(&self) -> SP1Stdin {
+        generate_stdin_with_start_hash(
+            &self.trusted_state,
+            &self.epoch_change_proof,
+            self.expected_start_validator_hash,
+        )
+    }
+}
+
+/// Extracts the epoch change proof expected by the epoch change program from a `StateProof`.
+///
+/// # Arguments
+///
+/// * `state_proof` - The state proof carrying the epoch change.
+///
+/// # Returns
+///
+/// A `Result` containing the epoch change proof serialized with bcs.
+pub fn epoch_change_assets_from_state_proof(
+    state_proof: &StateProof,
+) -> Result<Vec<u8>, LightClientError> {
+    bcs::to_bytes(state_proof.epoch_changes()).map_err(|err| {
+        LightClientError::SerializationError {
+            structure: "EpochChangeProof".to_string(),
+            source: err.into(),
+        }
+    })
+}
+
+/// Values committed by the epoch change program.
+#[derive(Clone, Debug, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct EpochChangeOutput {
+    /// Hash of the validator verifier of the trusted state.
+    #[serde(serialize_with = "serialize_hex")]
+    prev_validator_verifier_hash: [u8; 32],
+    /// Hash of the validator verifier for the new epoch.
+    #[serde(serialize_with = "serialize_hex")]
+    validator_verifier_hash: [u8; 32],
+    /// Epoch of the ledger info ending the trusted epoch.
+    start_epoch: u64,
+    /// Epoch the trusted state was ratcheted to.
+    end_epoch: u64,
+}
+
+impl EpochChangeOutput {
+    /// Reads the `EpochChangeOutput` from the public values committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values of an execution or a proof of the program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `EpochChangeOutput`, or `Err` if the public values were
+    /// not committed by the epoch change program or if it could not ratchet the trusted state.
+    pub fn from_public_values(
+        public_values: &mut SP1PublicValues,
+    ) -> Result<Self, LightClientError> {
+        let mut reader = PublicValuesReader::new(public_values);
+        reader.read_magic(EPOCH_CHANGE_OUTPUT_MAGIC, "epoch change")?;
+
+        let kind = EpochChangeStatus::from_u8(reader.read_byte()?).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "EpochChangeStatus".to_string(),
+                source: err.into(),
+            }
+        })?;
+        if kind != EpochChangeStatus::Ratcheted {
+            return Err(LightClientError::EpochRatchetError { kind });
+        }
+
+        Ok(Self {
+            prev_validator_verifier_hash: reader.read_hash()?,
+            validator_verifier_hash: reader.read_hash()?,
+            start_epoch: reader.read_u64()?,
+            end_epoch: reader.read_u64()?,
+        })
+    }
+}
+
+/// Ratchets a trusted state out-of-circuit as the epoch change program does, so that the
+/// new trusted state can be persisted once its proof is verified.
+///
+/// # Arguments
+///
+/// * `trusted_state` - The trusted state to ratchet.
+/// * `epoch_change_proof` - The epoch change proof to the next epoch, serialized with bcs.
+///
+/// # Returns
+///
+/// A `Result` containing the new `TrustedState`, or `Err` if the program would not
+/// ratchet the trusted state with the proof.
+pub fn ratchet_trusted_state(
+    trusted_state: &TrustedState,
+    epoch_change_proof: &[u8],
+) -> Result<TrustedState, LightClientError> {
+    let epoch_change_proof = EpochChangeProof::from_bytes(epoch_change_proof).map_err(|err| {
+        LightClientError::DeserializationError {
+            structure: "EpochChangeProof".to_string(),
+            source: err.into(),
+        }
+    })?;
+    // The program has no epoch to ratchet from in a waypoint
+    if let TrustedState::EpochWaypoint(_) = trusted_state {
+        return Err(LightClientError::EpochRatchetError {
+            kind: EpochChangeStatus::WaypointTrustedState,
+        });
+    }
+
+    let new_state = match trusted_state.verify_and_ratchet_inner(&epoch_change_proof) {
+        Ok(TrustedStateChange::Epoch { new_state, .. }) => new_state,
+        Ok(TrustedStateChange::Version { .. } | TrustedStateChange::NoChange) => {
+            return Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::NoEpochChange,
+            })
+        }
+        Err(err) => {
+            return Err(LightClientError::EpochRatchetError {
+                kind: trusted_state.ratchet_failure_status(&epoch_change_proof, &err),
+            })
+        }
+    };
+    // Only a single epoch change can be proven at once
+    if new_state.epoch() != trusted_state.epoch().map(|epoch| epoch + 1) {
+        return Err(LightClientError::EpochRatchetError {
+            kind: EpochChangeStatus::EpochMismatch,
+        });
+    }
+
+    Ok(new_state)
+}
+
+/// Checks that a trusted state is inside an epoch, the only one the epoch change program
+/// can ratchet, before building the stdin of the program.
+///
+/// # Arguments
+///
+/// * `bytes` - The trusted state, serialized with bcs.
+///
+/// # Returns
+///
+/// A `Result` containing the `EpochState` of the trusted state, or `Err` if it could not
+/// be deserialized or is a waypoint.
+pub fn ensure_epoch_state(bytes: &[u8]) -> Result<EpochState, LightClientError> {
+    let trusted_state =
+        TrustedState::from_bytes(bytes).map_err(|err| LightClientError::DeserializationError {
+            structure: "TrustedState".to_string(),
+            source: err.into(),
+        })?;
+
+    match trusted_state {
+        TrustedState::EpochState { epoch_state, .. } => Ok(epoch_state),
+        TrustedState::EpochWaypoint(waypoint) => Err(LightClientError::NotEpochState {
+            version: waypoint.version(),
+        }),
+    }
+}
+
+/// Returns the validator verifier of the epoch a trusted state is ratcheted to by an epoch
+/// change proof, as the epoch change program does. Its hash is the one committed by the
+/// program, and it can be used to prove inclusions in the new epoch.
+///
+/// # Arguments
+///
+/// * `trusted_state` - The trusted state to ratchet, serialized with bcs.
+/// * `epoch_change_proof` - The epoch change proof to the next epoch, serialized with bcs.
+///
+/// # Returns
+///
+/// A `Result` containing the `ValidatorVerifier` of the next epoch, or `Err` if the
+/// program would not ratchet the trusted state with the proof.
+pub fn next_validator_verifier(
+    trusted_state: &[u8],
+    epoch_change_proof: &[u8],
+) -> Result<ValidatorVerifier, LightClientError> {
+    ensure_epoch_state(trusted_state)?;
+    let trusted_state = TrustedState::from_bytes(trusted_state).map_err(|err| {
+        LightClientError::DeserializationError {
+            structure: "TrustedState".to_string(),
+            source: err.into(),
+        }
+    })?;
 
+    match ratchet_trusted_state(&trusted_state, epoch_change_proof)? {
+        TrustedState::EpochState { epoch_state, .. } => Ok(epoch_state.verifier),
+        // A waypoint has no epoch, which `ratchet_trusted_state` rejects
+        TrustedState::EpochWaypoint(_) => unreachable!("ratcheted to an epoch waypoint"),
+    }
+}
+
+/// Change of a validator present in two validator verifiers.
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct ValidatorChange {
+    /// Address of the validator.
+    address: AccountAddress,
+    /// Voting power of the validator in the old validator verifier.
+    old_voting_power: u64,
+    /// Voting power of the validator in the new validator verifier.
+    new_voting_power: u64,
+    /// Whether the validator rotated its consensus key.
+    public_key_changed: bool,
+}
+
+/// Changes of the validator set between two validator verifiers, e.g. before and after an
+/// epoch change. The validators are listed in the order of the verifier they appear in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct ValidatorSetDiff {
+    /// Validators of the new validator verifier only.
+    added: Vec<ValidatorConsensusInfo>,
+    /// Validators of the old validator verifier only.
+    removed: Vec<ValidatorConsensusInfo>,
+    /// Validators of both validator verifiers, whose voting power or public key changed.
+    changed: Vec<ValidatorChange>,
+}
+
+impl ValidatorSetDiff {
+    /// Returns whether the two validator verifiers have the same validators, with the same
+    /// voting power and public key.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two validator verifiers, matching their validators by account address.
+///
+/// # Arguments
+///
+/// * `old` - The old validator verifier, serialized as `ValidatorVerifier::to_bytes` does.
+/// * `new` - The new validator verifier, serialized the same way.
+///
+/// # Returns
+///
+/// A `Result` containing the `ValidatorSetDiff` from `old` to `new`, or `Err` if either
+/// could not be deserialized.
+pub fn diff_validator_verifiers(
+    old: &[u8],
+    new: &[u8],
+) -> Result<ValidatorSetDiff, LightClientError> {
+    let deserialize = |bytes: &[u8]| {
+        ValidatorVerifier::from_bytes(bytes).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "ValidatorVerifier".to_string(),
+                source: err.into(),
+            }
+        })
+    };
+    let old = deserialize(old)?;
+    let new = deserialize(new)?;
+    let find = |verifier: &ValidatorVerifier, address: &AccountAddress| {
+        verifier
+            .validator_infos()
+            .iter()
+            .find(|info| info.address() == address)
+    };
+
+    let mut diff = ValidatorSetDiff::default();
+    for new_info in new.validator_infos() {
+        match find(&old, new_info.address()) {
+            None => diff.added.push(new_info.clone()),
+            Some(old_info) if old_info != new_info => diff.changed.push(ValidatorChange {
+                address: *new_info.address(),
+                old_voting_power: *old_info.voting_power(),
+                new_voting_power: *new_info.voting_power(),
+                public_key_changed: old_info.public_key() != new_info.public_key(),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .validator_infos()
+        .iter()
+        .filter(|old_info| find(&new, old_info.address()).is_none())
+        .cloned()
+        .collect();
+
+    Ok(diff)
+}
+
+/// Sets up the keys of the epoch change program.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program with.
+///
+/// # Returns
+///
+/// A `Result` containing the proving and the verifying keys, or `MissingElf` if the
+/// program was not built before the crate.
 #[inline]
-pub fn generate_keys(client: &ProverClient) -> (SP1ProvingKey, SP1VerifyingKey) {
-    client.setup(EPOCH_CHANGE_ELF)
+pub fn generate_keys(
+    client: &ProverClient,
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), LightClientError> {
+    let _span = tracing::info_span!("generate_keys", program = "epoch-change").entered();
+    setup_program(client, "epoch-change", EPOCH_CHANGE_ELF)
+}
+
+/// Returns the verifying key of the epoch change program, serialized so that proofs can be
+/// verified without a `ProverClient`. Use `io::load_vk` to read it back.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` used to set up the program.
+///
+/// # Returns
+///
+/// A `Result` containing the serialized `SP1VerifyingKey`.
+pub fn epoch_change_vk_bytes(client: &ProverClient) -> Result<Vec<u8>, LightClientError> {
+    vk_to_bytes(&generate_keys(client)?.1)
 }
 
 pub fn generate_stdin(current_trusted_state: &[u8], epoch_change_proof: &[u8]) -> SP1Stdin {
+    generate_stdin_with_start_hash(current_trusted_state, epoch_change_proof, None)
+}
+
+/// Returns the `SP1Stdin` for the epoch change program, optionally pinning the validator
+/// verifier of the trusted state.
+///
+/// # Arguments
+///
+/// * `current_trusted_state` - The trusted state to ratchet, serialized with bcs.
+/// * `epoch_change_proof` - The epoch change proof to the next epoch, serialized with bcs.
+/// * `expected_start_validator_hash` - The hash of the validator verifier the trusted state
+///   is expected to carry, if any.
+///
+/// # Returns
+///
+/// The `SP1Stdin` for the epoch change program.
+pub fn generate_stdin_with_start_hash(
+    current_trusted_state: &[u8],
+    epoch_change_proof: &[u8],
+    expected_start_validator_hash: Option<[u8; 32]>,
+) -> SP1Stdin {
+    let _span = tracing::info_span!("generate_stdin", program = "epoch-change").entered();
     let mut stdin = SP1Stdin::new();
     stdin.write_vec(current_trusted_state.to_vec());
     stdin.write_vec(epoch_change_proof.to_vec());
+    stdin.write(&expected_start_validator_hash);
     stdin
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use super::*;
+    use crate::fixture::AptosWrapperFixture;
+    use crate::io::{load_trusted_state, save_trusted_state};
+    use crate::types::EpochChangeData;
+    use aptos_lc_core::crypto::hash::CryptoHash;
+    use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+    use aptos_lc_core::types::validator::ValidatorVerifier;
+
+    #[test]
+    fn test_execute_epoch_change_commits_epochs() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+
+        let start_epoch = *fixture.aptos_wrapper().current_epoch();
+        let prev_validator_verifier_hash = fixture.validator_verifier().unwrap().hash();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+
+        let client = ProverClient::mock();
+        let stdin = generate_stdin(&trusted_state, &epoch_change_proof);
+        let (mut public_values, _) = client.execute(EPOCH_CHANGE_ELF, stdin).run().unwrap();
+
+        let output = EpochChangeOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(
+            output.prev_validator_verifier_hash(),
+            prev_validator_verifier_hash.as_ref()
+        );
+        assert_eq!(*output.start_epoch(), start_epoch);
+        assert_eq!(*output.end_epoch(), start_epoch + 1);
+    }
+
+    #[test]
+    fn test_epoch_change_inputs_from_state_proof() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let start_epoch = *fixture.aptos_wrapper().current_epoch();
+        let trusted_state = fixture.aptos_wrapper().trusted_state().clone();
+        let trusted_state_version = *fixture.aptos_wrapper().current_version();
+
+        fixture.generate_traffic().unwrap();
+        let state_proof = fixture.new_state_proof(trusted_state_version).unwrap();
+        let inputs = EpochChangeInputs::from_state_proof(&trusted_state, &state_proof).unwrap();
+
+        let decoded_inputs: EpochChangeInputs =
+            bcs::from_bytes(&bcs::to_bytes(&inputs).unwrap()).unwrap();
+        assert_eq!(decoded_inputs, inputs);
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let mut proof = client.prove(&pk, decoded_inputs.stdin()).run().unwrap();
+        client.verify(&proof, &vk).unwrap();
+
+        let output = EpochChangeOutput::from_public_values(&mut proof.public_values).unwrap();
+        assert_eq!(*output.end_epoch(), start_epoch + 1);
+    }
+
+    #[test]
+    fn test_sequential_ratchets_from_persisted_trusted_state() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let start_epoch = *fixture.aptos_wrapper().current_epoch();
+
+        let path = std::env::temp_dir().join("aptos-lc-test-sequential-ratchets.bin");
+        let trusted_state = TrustedState::from_bytes(&fixture.trusted_state().unwrap()).unwrap();
+        save_trusted_state(&trusted_state, &path).unwrap();
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        for nbr_ratchets in 1..=2 {
+            let trusted_state = load_trusted_state(&path).unwrap();
+            let EpochChangeData {
+                epoch_change_proof, ..
+            } = fixture.next_epoch_change_data().unwrap();
+
+            let stdin = generate_stdin(&trusted_state.to_bytes(), &epoch_change_proof);
+            let mut proof = client.prove(&pk, stdin).run().unwrap();
+            client.verify(&proof, &vk).unwrap();
+            let output = EpochChangeOutput::from_public_values(&mut proof.public_values).unwrap();
+            assert_eq!(*output.end_epoch(), start_epoch + nbr_ratchets);
+
+            let new_state = ratchet_trusted_state(&trusted_state, &epoch_change_proof).unwrap();
+            assert_eq!(new_state.epoch(), Some(*output.end_epoch()));
+            save_trusted_state(&new_state, &path).unwrap();
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_next_validator_verifier() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+
+        let mut public_values = execute_epoch_change(&trusted_state, &epoch_change_proof);
+        let output = EpochChangeOutput::from_public_values(&mut public_values).unwrap();
+
+        let validator_verifier =
+            next_validator_verifier(&trusted_state, &epoch_change_proof).unwrap();
+        assert_eq!(
+            validator_verifier.hash().as_ref(),
+            output.validator_verifier_hash()
+        );
+    }
+
+    #[test]
+    fn test_ensure_epoch_state_rejects_waypoint() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+
+        let epoch_state = ensure_epoch_state(&trusted_state).unwrap();
+        assert_eq!(
+            Some(*epoch_state.epoch()),
+            TrustedState::from_bytes(&trusted_state).unwrap().epoch()
+        );
+
+        let waypoint = TrustedState::from_bytes(&trusted_state).unwrap().waypoint();
+        let waypoint_state = TrustedState::EpochWaypoint(waypoint).to_bytes();
+        assert!(matches!(
+            ensure_epoch_state(&waypoint_state),
+            Err(LightClientError::NotEpochState { version }) if version == waypoint.version()
+        ));
+        assert!(matches!(
+            next_validator_verifier(&waypoint_state, &epoch_change_proof),
+            Err(LightClientError::NotEpochState { .. })
+        ));
+    }
+
+    #[test]
+    fn test_execute_epoch_change_waypoint_trusted_state() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+        let waypoint = TrustedState::EpochWaypoint(
+            TrustedState::from_bytes(&trusted_state).unwrap().waypoint(),
+        );
+
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client
+            .execute(
+                EPOCH_CHANGE_ELF,
+                generate_stdin(&waypoint.to_bytes(), &epoch_change_proof),
+            )
+            .run()
+            .unwrap();
+        assert!(matches!(
+            EpochChangeOutput::from_public_values(&mut public_values),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::WaypointTrustedState
+            })
+        ));
+
+        // The out-of-circuit ratchet agrees with the program
+        assert!(matches!(
+            ratchet_trusted_state(&waypoint, &epoch_change_proof),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::WaypointTrustedState
+            })
+        ));
+    }
+
+    #[test]
+    fn test_diff_validator_verifiers() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let old_validator_verifier = fixture.validator_verifier().unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+        let new_validator_verifier =
+            next_validator_verifier(&trusted_state, &epoch_change_proof).unwrap();
+
+        // In the next epoch, a validator leaves, another joins and a third one doubles its
+        // voting power
+        let mut validator_infos = new_validator_verifier.validator_infos().clone();
+        let left = validator_infos.pop().unwrap();
+        let joined = ValidatorConsensusInfo::new(
+            AccountAddress::new([0xab; 32]),
+            left.public_key().clone(),
+            *left.voting_power(),
+        );
+        validator_infos.push(joined.clone());
+        let changed = validator_infos[0].clone();
+        validator_infos[0] = ValidatorConsensusInfo::new(
+            *changed.address(),
+            changed.public_key().clone(),
+            changed.voting_power() * 2,
+        );
+        let new_validator_verifier = ValidatorVerifier::new(validator_infos);
+
+        let diff = diff_validator_verifiers(
+            &old_validator_verifier.to_bytes(),
+            &new_validator_verifier.to_bytes(),
+        )
+        .unwrap();
+        assert_eq!(diff.added(), &vec![joined]);
+        assert_eq!(diff.removed(), &vec![left]);
+        // Other validators may also have been rewarded at the epoch change
+        let change = diff
+            .changed()
+            .iter()
+            .find(|change| change.address() == changed.address())
+            .unwrap();
+        assert_eq!(
+            change,
+            &ValidatorChange {
+                address: *changed.address(),
+                old_voting_power: old_validator_verifier
+                    .get_voting_power(changed.address())
+                    .unwrap(),
+                new_voting_power: changed.voting_power() * 2,
+                public_key_changed: false,
+            }
+        );
+
+        let diff = diff_validator_verifiers(
+            &old_validator_verifier.to_bytes(),
+            &old_validator_verifier.to_bytes(),
+        )
+        .unwrap();
+        assert!(diff.is_empty());
+        assert!(matches!(
+            diff_validator_verifiers(&old_validator_verifier.to_bytes(), &[0xff]),
+            Err(LightClientError::DeserializationError { .. })
+        ));
+    }
+
+    fn execute_epoch_change(trusted_state: &[u8], epoch_change_proof: &[u8]) -> SP1PublicValues {
+        let client = ProverClient::mock();
+        let stdin = generate_stdin(trusted_state, epoch_change_proof);
+        let (public_values, _) = client.execute(EPOCH_CHANGE_ELF, stdin).run().unwrap();
+
+        public_values
+    }
+
+    #[test]
+    fn test_execute_epoch_change_pinned_start_validator() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+        let start_validator_hash = match TrustedState::from_bytes(&trusted_state).unwrap() {
+            TrustedState::EpochState { epoch_state, .. } => *epoch_state.verifier().hash().as_ref(),
+            TrustedState::EpochWaypoint(_) => panic!("expected epoch state for trusted state"),
+        };
+        let client = ProverClient::mock();
+        let execute = |expected_start_validator_hash| {
+            let inputs = EpochChangeInputs::new(trusted_state.clone(), epoch_change_proof.clone())
+                .with_expected_start_validator_hash(expected_start_validator_hash);
+            let (public_values, _) = client
+                .execute(EPOCH_CHANGE_ELF, inputs.stdin())
+                .run()
+                .unwrap();
+            public_values
+        };
+
+        let output = EpochChangeOutput::from_public_values(&mut execute(start_validator_hash));
+        assert_eq!(
+            output.unwrap().prev_validator_verifier_hash(),
+            &start_validator_hash
+        );
+
+        // The trusted state is not the one of the pinned chain
+        assert!(matches!(
+            EpochChangeOutput::from_public_values(&mut execute([0; 32])),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::StartValidatorMismatch
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_epoch_change_epoch_mismatch() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let trusted_state = fixture.trusted_state().unwrap();
+
+        // Chain the proofs of two consecutive epoch changes into a single one
+        let mut ledger_info_with_sigs = vec![];
+        for _ in 0..2 {
+            let EpochChangeData {
+                epoch_change_proof, ..
+            } = fixture.next_epoch_change_data().unwrap();
+            ledger_info_with_sigs.extend(
+                EpochChangeProof::from_bytes(&epoch_change_proof)
+                    .unwrap()
+                    .ledger_info_with_sigs,
+            );
+        }
+        let epoch_change_proof = EpochChangeProof {
+            ledger_info_with_sigs,
+            more: false,
+        };
+
+        let mut public_values =
+            execute_epoch_change(&trusted_state, &epoch_change_proof.to_bytes());
+
+        // The proof is valid, but leads two epochs after the trusted one
+        assert!(matches!(
+            EpochChangeOutput::from_public_values(&mut public_values),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::EpochMismatch
+            })
+        ));
+        assert!(matches!(
+            ratchet_trusted_state(
+                &TrustedState::from_bytes(&trusted_state).unwrap(),
+                &epoch_change_proof.to_bytes()
+            ),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::EpochMismatch
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_epoch_change_empty_proof() {
+        let fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let epoch_change_proof = EpochChangeProof {
+            ledger_info_with_sigs: vec![],
+            more: false,
+        };
+
+        let mut public_values = execute_epoch_change(
+            &fixture.trusted_state().unwrap(),
+            &epoch_change_proof.to_bytes(),
+        );
+
+        assert!(matches!(
+            EpochChangeOutput::from_public_values(&mut public_values),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::EmptyProof
+            })
+        ));
+    }
+
+    /// Returns a trusted state along with a valid proof to a newer version of its epoch.
+    fn version_only_data() -> (Vec<u8>, Vec<u8>) {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let trusted_state = fixture.trusted_state().unwrap();
+        fixture.generate_traffic().unwrap();
+
+        let latest_li =
+            LedgerInfoWithSignatures::from_bytes(&fixture.latest_li_bytes().unwrap()).unwrap();
+        let epoch_change_proof = EpochChangeProof {
+            ledger_info_with_sigs: vec![latest_li],
+            more: false,
+        };
+
+        (trusted_state, epoch_change_proof.to_bytes())
+    }
+
+    #[test]
+    fn test_execute_epoch_change_version_only_proof() {
+        let (trusted_state, epoch_change_proof) = version_only_data();
+
+        let mut public_values = execute_epoch_change(&trusted_state, &epoch_change_proof);
+
+        assert!(matches!(
+            EpochChangeOutput::from_public_values(&mut public_values),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::NoEpochChange
+            })
+        ));
+        assert!(matches!(
+            ratchet_trusted_state(
+                &TrustedState::from_bytes(&trusted_state).unwrap(),
+                &epoch_change_proof
+            ),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::NoEpochChange
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_epoch_change_invalid_signatures() {
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+
+        // Trust a validator set that did not sign the epoch change
+        let trusted_state = match TrustedState::from_bytes(&trusted_state).unwrap() {
+            TrustedState::EpochState {
+                waypoint,
+                epoch_state,
+            } => TrustedState::EpochState {
+                waypoint,
+                epoch_state: EpochState {
+                    epoch: epoch_state.epoch,
+                    verifier: ValidatorVerifier::new(
+                        epoch_state.verifier.validator_infos()[1..].to_vec(),
+                    ),
+                },
+            },
+            TrustedState::EpochWaypoint(_) => panic!("expected epoch state for trusted state"),
+        };
+
+        let mut public_values =
+            execute_epoch_change(&trusted_state.to_bytes(), &epoch_change_proof);
+
+        assert!(matches!(
+            EpochChangeOutput::from_public_values(&mut public_values),
+            Err(LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::InvalidSignatures
+            })
+        ));
+    }
+}
@@ -1,17 +1,139 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
 use thiserror::Error;
 use aptos_lc_core::crypto::hash::HashValue;
+use aptos_lc_core::types::status::{EpochChangeStatus, InclusionStatus, SignatureStatus};
 
+use crate::io::ProofEncoding;
+
+/// Error type for the light client programs and their host-side helpers.
 #[derive(Debug, Error)]
-pub(crate) enum LightClientError {
+pub enum LightClientError {
     #[error("[{program}] Failed to prove: {source}")]
     ProvingError {
         program: String,
         #[source]
         source: Box<dyn std::error::Error + Sync + Send>,
     },
+    #[error("Failed to deserialize {structure}: {source}")]
+    DeserializationError {
+        structure: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Failed to serialize {structure}: {source}")]
+    SerializationError {
+        structure: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("I/O error on {path}: {source}")]
+    IoError {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Out-of-circuit verification failed for {check}: {source}")]
+    VerificationError {
+        check: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Failed to verify the proof at index {index}: {source}")]
+    BatchVerificationError {
+        index: usize,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Inclusion program did not prove the account inclusion: {status:?}")]
+    InclusionFailure { status: InclusionStatus },
+    #[error("Epoch change program could not ratchet the trusted state: {kind:?}")]
+    EpochRatchetError { kind: EpochChangeStatus },
+    #[error("Signature program did not verify the ledger info: {status:?}")]
+    SignatureFailure { status: SignatureStatus },
+    #[error("Local proving is forced but {variable} is set to '{value}'")]
+    IncompatibleEnvironment { variable: String, value: String },
+    #[error("{capability} requires the '{feature}' feature to be enabled")]
+    FeatureDisabled { capability: String, feature: String },
+    #[error("Expected the output of the {program} program, found magic {actual:?}")]
+    WrongProgramOutput { program: String, actual: [u8; 4] },
+    #[error("Public values are truncated: expected {expected} more bytes")]
+    TruncatedOutput { expected: usize },
+    #[error("Unsupported version {version} of the inclusion program output")]
+    UnsupportedOutputVersion { version: u8 },
+    #[error("Verifying key hash mismatch: expected {expected}, got {actual}")]
+    VerifyingKeyMismatch { expected: String, actual: String },
+    #[error("The proof was generated for the vkey hash {actual}, expected {expected}")]
+    WrongProgram { expected: String, actual: String },
+    #[error("Invalid {input}: {reason}")]
+    InvalidInput { input: String, reason: String },
+    #[error("Mismatch of the committed {field}: expected {expected}, got {actual}")]
+    Mismatch {
+        field: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("The committed block {block} is not among the checkpoints")]
+    UnknownBlock { block: String },
+    #[error("Version {version} is not yet available on the node, retry once it is committed")]
+    VersionNotAvailable { version: u64 },
+    #[error("The inputs of the program are too large to be written to its stdin: {bytes} bytes")]
+    InputTooLarge { bytes: usize },
+    #[error("Expected a {expected:?} encoded proof in {path}, found {detected:?}")]
+    ProofEncodingMismatch {
+        path: String,
+        expected: ProofEncoding,
+        detected: ProofEncoding,
+    },
+    #[error(
+        "The inclusion proof is signed by the validator verifier {actual}, not the one {expected} of the epoch change"
+    )]
+    HashContinuityError { expected: String, actual: String },
+    #[error(
+        "The trusted state is a waypoint at version {version}, while the epoch change program ratchets an epoch state"
+    )]
+    NotEpochState { version: u64 },
+    #[error("The proof is anchored to a ledger info {age_usecs} microseconds old, past the staleness bound")]
+    StaleProof { age_usecs: u64 },
+    #[error(
+        "The quorum voting power {quorum_voting_power} of the validator verifier exceeds its total voting power {total_voting_power}"
+    )]
+    UnsatisfiableQuorum {
+        quorum_voting_power: u128,
+        total_voting_power: u128,
+    },
+    #[error("Unsupported {capability}: {note}")]
+    Unsupported { capability: String, note: String },
+    #[error("The guest program panicked: {message}")]
+    GuestPanic { message: String },
+    #[error("Proving of the {program} program was cancelled")]
+    Cancelled { program: String },
+    #[error("Proving of the {program} program did not complete within {timeout:?}")]
+    Timeout { program: String, timeout: Duration },
+    #[error(
+        "The ELF of the {program} program is empty, build the guest first with `cargo prove build` in `programs/{program}`"
+    )]
+    MissingElf { program: String },
+    #[error("Failed to generate fixture at {step}: {source}")]
+    FixtureError {
+        step: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Failed to query {endpoint}: {source}")]
+    RpcError {
+        endpoint: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("The {prover} prover is unavailable: {source}")]
+    ProverUnavailable {
+        prover: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
 }
 
 /// Error type for the client.
@@ -52,4 +174,191 @@ pub enum ClientError {
         #[source]
         source: Box<dyn std::error::Error + Sync + Send>,
     },
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as _;
+
+    fn boxed_source(message: &str) -> Box<dyn std::error::Error + Sync + Send> {
+        anyhow::anyhow!(message.to_string()).into()
+    }
+
+    #[test]
+    fn test_proving_error_source() {
+        let err = LightClientError::ProvingError {
+            program: "inclusion".to_string(),
+            source: boxed_source("out of memory"),
+        };
+
+        assert!(err.to_string().contains("inclusion"));
+        assert_eq!(err.source().unwrap().to_string(), "out of memory");
+
+        let err = anyhow::Error::from(err);
+        assert_eq!(err.chain().count(), 2);
+        assert_eq!(err.root_cause().to_string(), "out of memory");
+    }
+
+    #[test]
+    fn test_light_client_error_display() {
+        let errors = vec![
+            LightClientError::ProvingError {
+                program: "inclusion".to_string(),
+                source: boxed_source("proving"),
+            },
+            LightClientError::DeserializationError {
+                structure: "SP1Stdin".to_string(),
+                source: boxed_source("deserialization"),
+            },
+            LightClientError::SerializationError {
+                structure: "SP1Stdin".to_string(),
+                source: boxed_source("serialization"),
+            },
+            LightClientError::IoError {
+                path: "stdin.bin".to_string(),
+                source: std::io::Error::other("io"),
+            },
+            LightClientError::VerificationError {
+                check: "SparseMerkleProof".to_string(),
+                source: boxed_source("verification"),
+            },
+            LightClientError::BatchVerificationError {
+                index: 1,
+                source: boxed_source("batch verification"),
+            },
+            LightClientError::InclusionFailure {
+                status: InclusionStatus::NotACheckpoint,
+            },
+            LightClientError::EpochRatchetError {
+                kind: EpochChangeStatus::StaleProof,
+            },
+            LightClientError::SignatureFailure {
+                status: SignatureStatus::InvalidSignatures,
+            },
+            LightClientError::IncompatibleEnvironment {
+                variable: "SP1_PROVER".to_string(),
+                value: "network".to_string(),
+            },
+            LightClientError::FeatureDisabled {
+                capability: "GPU proving".to_string(),
+                feature: "cuda".to_string(),
+            },
+            LightClientError::WrongProgramOutput {
+                program: "inclusion".to_string(),
+                actual: *b"EPCH",
+            },
+            LightClientError::TruncatedOutput { expected: 32 },
+            LightClientError::UnsupportedOutputVersion { version: 2 },
+            LightClientError::VerifyingKeyMismatch {
+                expected: "0x00".to_string(),
+                actual: "0x01".to_string(),
+            },
+            LightClientError::WrongProgram {
+                expected: "0x00".to_string(),
+                actual: "0x01".to_string(),
+            },
+            LightClientError::InvalidInput {
+                input: "latest_li".to_string(),
+                reason: "must not be empty".to_string(),
+            },
+            LightClientError::Mismatch {
+                field: "state root".to_string(),
+                expected: "0x00".to_string(),
+                actual: "0x01".to_string(),
+            },
+            LightClientError::UnknownBlock {
+                block: "0x00".to_string(),
+            },
+            LightClientError::VersionNotAvailable { version: 1 },
+            LightClientError::InputTooLarge { bytes: 1 << 30 },
+            LightClientError::ProofEncodingMismatch {
+                path: "proof.json".to_string(),
+                expected: ProofEncoding::Json,
+                detected: ProofEncoding::Bincode,
+            },
+            LightClientError::HashContinuityError {
+                expected: "0x00".to_string(),
+                actual: "0x01".to_string(),
+            },
+            LightClientError::NotEpochState { version: 1 },
+            LightClientError::StaleProof { age_usecs: 1 },
+            LightClientError::UnsatisfiableQuorum {
+                quorum_voting_power: 1,
+                total_voting_power: 0,
+            },
+            LightClientError::Unsupported {
+                capability: "sample account".to_string(),
+                note: "not an account of the fixture".to_string(),
+            },
+            LightClientError::GuestPanic {
+                message: "execution failed with exit code 1".to_string(),
+            },
+            LightClientError::Cancelled {
+                program: "inclusion".to_string(),
+            },
+            LightClientError::Timeout {
+                program: "inclusion".to_string(),
+                timeout: Duration::from_secs(1),
+            },
+            LightClientError::MissingElf {
+                program: "inclusion".to_string(),
+            },
+            LightClientError::FixtureError {
+                step: "generate_traffic",
+                source: boxed_source("fixture"),
+            },
+            LightClientError::RpcError {
+                endpoint: "http://127.0.0.1:8080/v1".to_string(),
+                source: boxed_source("connection refused"),
+            },
+            LightClientError::ProverUnavailable {
+                prover: "network".to_string(),
+                source: boxed_source("unreachable"),
+            },
+        ];
+
+        for err in &errors {
+            // Matching without a wildcard fails to compile when a variant is not listed above
+            let wraps_error = match err {
+                LightClientError::ProvingError { .. }
+                | LightClientError::DeserializationError { .. }
+                | LightClientError::SerializationError { .. }
+                | LightClientError::IoError { .. }
+                | LightClientError::VerificationError { .. }
+                | LightClientError::BatchVerificationError { .. }
+                | LightClientError::FixtureError { .. }
+                | LightClientError::RpcError { .. }
+                | LightClientError::ProverUnavailable { .. } => true,
+                LightClientError::InclusionFailure { .. }
+                | LightClientError::EpochRatchetError { .. }
+                | LightClientError::SignatureFailure { .. }
+                | LightClientError::IncompatibleEnvironment { .. }
+                | LightClientError::FeatureDisabled { .. }
+                | LightClientError::WrongProgramOutput { .. }
+                | LightClientError::TruncatedOutput { .. }
+                | LightClientError::UnsupportedOutputVersion { .. }
+                | LightClientError::VerifyingKeyMismatch { .. }
+                | LightClientError::WrongProgram { .. }
+                | LightClientError::InvalidInput { .. }
+                | LightClientError::Mismatch { .. }
+                | LightClientError::UnknownBlock { .. }
+                | LightClientError::VersionNotAvailable { .. }
+                | LightClientError::InputTooLarge { .. }
+                | LightClientError::ProofEncodingMismatch { .. }
+                | LightClientError::HashContinuityError { .. }
+                | LightClientError::NotEpochState { .. }
+                | LightClientError::StaleProof { .. }
+                | LightClientError::UnsatisfiableQuorum { .. }
+                | LightClientError::Unsupported { .. }
+                | LightClientError::GuestPanic { .. }
+                | LightClientError::Cancelled { .. }
+                | LightClientError::Timeout { .. }
+                | LightClientError::MissingElf { .. } => false,
+            };
+
+            assert!(!err.to_string().is_empty());
+            assert_eq!(err.source().is_some(), wraps_error, "{err}");
+        }
+    }
+}
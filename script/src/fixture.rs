@@ -0,0 +1,696 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fixtures generating the assets for the light client programs out of a simulated
+//! Aptos chain. They are used by the benchmark binaries and the tests.
+
+use aptos_lc_core::aptos_test_utils::wrapper::{
+    AptosWrapper, ExecuteBlockArgs, SparseMerkleProofAssets as AptosSparseMerkleProofAssets,
+};
+use aptos_lc_core::types::leaf::split_module_state_value;
+use aptos_lc_core::types::trusted_state::TrustedState;
+use aptos_lc_core::types::validator::ValidatorVerifier;
+use aptos_sdk::move_types::identifier::Identifier;
+use aptos_sdk::move_types::language_storage::ModuleId;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::proof::TransactionInfoWithProof;
+use aptos_types::state_proof::StateProof;
+use aptos_types::state_store::state_key::StateKey;
+
+use crate::epoch_change::epoch_change_assets_from_state_proof;
+use crate::error::LightClientError;
+#[cfg(test)]
+use crate::inclusion::generate_stdin;
+#[cfg(any(test, feature = "test-fixtures"))]
+use crate::inclusion::ProvingAssets;
+use crate::inclusion::{
+    AccountStateProofAssets, SparseMerkleProofAssets, StateKeyPreimage, TransactionProofAssets,
+    ValidatorVerifierAssets,
+};
+use crate::types::{EpochChangeData, InclusionData};
+#[cfg(test)]
+use sp1_sdk::SP1Stdin;
+
+/// Number of accounts beyond which generating a fixture is impractical.
+pub const MAX_PRACTICAL_NBR_ACCOUNTS: usize = 1 << 15;
+
+/// Wrapper around an `AptosWrapper` extracting the assets expected by the light client programs.
+pub struct AptosWrapperFixture {
+    aptos_wrapper: AptosWrapper,
+    ledger_info_signers: Option<usize>,
+}
+
+impl AptosWrapperFixture {
+    /// Creates a new simulated chain with funded accounts.
+    ///
+    /// Every account is created and funded on the simulated chain, so the time and memory
+    /// needed grow with `nbr_accounts`. Beyond `MAX_PRACTICAL_NBR_ACCOUNTS` accounts,
+    /// the generation becomes impractical.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_accounts` - The number of accounts to create, i.e. the number of leaves in the state tree.
+    /// * `nbr_validators` - The number of validators of the chain.
+    /// * `signers_per_block` - The number of validators signing each block.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `AptosWrapperFixture`, or `Err` if there is no account to
+    /// create or if the chain could not be created.
+    pub fn new(
+        nbr_accounts: usize,
+        nbr_validators: usize,
+        signers_per_block: usize,
+    ) -> Result<Self, LightClientError> {
+        if nbr_accounts == 0 {
+            return Err(LightClientError::InvalidInput {
+                input: "number of accounts".to_string(),
+                reason: "at least one account is needed to prove an inclusion".to_string(),
+            });
+        }
+
+        let aptos_wrapper = AptosWrapper::new(nbr_accounts, nbr_validators, signers_per_block)
+            .map_err(|err| fixture_error("new", err))?;
+
+        Ok(Self {
+            aptos_wrapper,
+            ledger_info_signers: None,
+        })
+    }
+
+    /// Overrides the number of validators signing the ledger info given to the programs,
+    /// e.g. to go below the quorum of the validator verifier. The chain keeps its own
+    /// signers, as it can not commit a block below quorum, so only the latest ledger info
+    /// is signed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_signers` - The number of validators signing the ledger info.
+    ///
+    /// # Returns
+    ///
+    /// The `AptosWrapperFixture` with the overridden signers.
+    pub const fn with_ledger_info_signers(mut self, nbr_signers: usize) -> Self {
+        self.ledger_info_signers = Some(nbr_signers);
+        self
+    }
+
+    /// Returns the underlying `AptosWrapper`.
+    pub const fn aptos_wrapper(&self) -> &AptosWrapper {
+        &self.aptos_wrapper
+    }
+
+    /// Returns the index of an account of the chain, to prove its inclusion with
+    /// `inclusion_data`. The accounts are always derived from the same keys, so an account
+    /// has the same index in any chain with more accounts than its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the account.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the index of the account, or `Unsupported` if it is not an
+    /// account of the chain.
+    pub fn account_index(&self, address: AccountAddress) -> Result<usize, LightClientError> {
+        self.aptos_wrapper
+            .accounts()
+            .iter()
+            .position(|account| account.address() == address)
+            .ok_or_else(|| LightClientError::Unsupported {
+                capability: "sample account".to_string(),
+                note: format!(
+                    "{address} is not among the {} accounts of the simulated chain, which can \
+                     only prove the accounts it generated",
+                    self.aptos_wrapper.accounts().len()
+                ),
+            })
+    }
+
+    /// Commits a new block of transfers between the accounts of the chain.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the block was committed, and `Err` otherwise.
+    pub fn generate_traffic(&mut self) -> Result<(), LightClientError> {
+        self.aptos_wrapper
+            .generate_traffic()
+            .map_err(|err| fixture_error("generate_traffic", err))
+    }
+
+    /// Same as `generate_traffic`, but picks the transfers of the block from a seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed to pick the transfers from.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the block was committed, and `Err` otherwise.
+    pub fn generate_traffic_seeded(&mut self, seed: [u8; 32]) -> Result<(), LightClientError> {
+        self.aptos_wrapper
+            .generate_traffic_seeded(seed)
+            .map_err(|err| fixture_error("generate_traffic", err))
+    }
+
+    /// Returns the current trusted state of the chain, serialized with bcs.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the serialized `TrustedState`.
+    pub fn trusted_state(&self) -> Result<Vec<u8>, LightClientError> {
+        bcs::to_bytes(self.aptos_wrapper.trusted_state())
+            .map_err(|err| fixture_error("serialize TrustedState", err))
+    }
+
+    /// Returns the validator verifier of the current trusted state.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ValidatorVerifier`, or `Err` if the trusted
+    /// state is not an epoch state.
+    pub fn validator_verifier(&self) -> Result<ValidatorVerifier, LightClientError> {
+        match TrustedState::from_bytes(&self.trusted_state()?)
+            .map_err(|err| fixture_error("deserialize TrustedState", err))?
+        {
+            TrustedState::EpochState { epoch_state, .. } => Ok(epoch_state.verifier().clone()),
+            _ => Err(fixture_error(
+                "validator_verifier",
+                "expected epoch state for current trusted state",
+            )),
+        }
+    }
+
+    /// Returns the latest `LedgerInfoWithSignatures` of the chain, serialized with bcs. It is
+    /// signed by the overridden number of validators, if any.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the serialized `LedgerInfoWithSignatures`.
+    pub fn latest_li_bytes(&self) -> Result<Vec<u8>, LightClientError> {
+        match self.ledger_info_signers {
+            None => self
+                .aptos_wrapper
+                .get_latest_li_bytes()
+                .map_err(|err| fixture_error("get_latest_li_bytes", err)),
+            Some(nbr_signers) => {
+                let latest_li = self
+                    .aptos_wrapper
+                    .get_latest_li_signed_by(nbr_signers)
+                    .map_err(|err| fixture_error("get_latest_li_signed_by", err))?;
+                bcs::to_bytes(&latest_li)
+                    .map_err(|err| fixture_error("serialize LedgerInfoWithSignatures", err))
+            }
+        }
+    }
+
+    /// Commits a block starting a new epoch, and returns a `StateProof` from the given
+    /// version to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_version` - The version the state proof starts from.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `StateProof` carrying the epoch change.
+    pub fn new_state_proof(&mut self, from_version: u64) -> Result<StateProof, LightClientError> {
+        self.aptos_wrapper
+            .new_state_proof(from_version)
+            .map_err(|err| fixture_error("new_state_proof", err))
+    }
+
+    /// Returns the assets to prove the inclusion of an account in the state of the latest
+    /// transaction, which carries a state checkpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_idx` - The index of the account to prove.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `InclusionData` for the account, or `Err` if there is
+    /// no account at the given index.
+    pub fn inclusion_data(&self, account_idx: usize) -> Result<InclusionData, LightClientError> {
+        let proof_assets = self.latest_proof_account("inclusion_data", account_idx)?;
+
+        let sparse_merkle_proof = bcs::to_bytes(proof_assets.state_proof())
+            .map_err(|err| fixture_error("serialize SparseMerkleProof", err))?;
+        let leaf_hash = proof_assets
+            .state_value_hash()
+            .map_err(|err| fixture_error("state_value_hash", err))?;
+
+        self.inclusion_data_for_leaf(
+            &proof_assets,
+            SparseMerkleProofAssets::new(
+                sparse_merkle_proof,
+                *proof_assets.key().as_ref(),
+                *leaf_hash.as_ref(),
+            ),
+        )
+    }
+
+    /// Same as `inclusion_data`, but with the proofs bundled in a single `AccountStateProof`,
+    /// built from the proofs returned by the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_idx` - The index of the account to prove.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `AccountStateProofAssets` for the account along with the
+    /// `ValidatorVerifierAssets` of the chain, or `Err` if there is no account at the given
+    /// index.
+    pub fn account_state_proof_data(
+        &self,
+        account_idx: usize,
+    ) -> Result<(AccountStateProofAssets, ValidatorVerifierAssets), LightClientError> {
+        let proof_assets = self.latest_proof_account("account_state_proof_data", account_idx)?;
+
+        let transaction_info_with_proof = TransactionInfoWithProof::new(
+            proof_assets.transaction_proof().clone(),
+            proof_assets.transaction().clone(),
+        );
+        let mut account_state_proof = bcs::to_bytes(&transaction_info_with_proof)
+            .map_err(|err| fixture_error("serialize TransactionInfoWithProof", err))?;
+        account_state_proof.extend(
+            bcs::to_bytes(proof_assets.state_proof())
+                .map_err(|err| fixture_error("serialize SparseMerkleProof", err))?,
+        );
+        let leaf_hash = proof_assets
+            .state_value_hash()
+            .map_err(|err| fixture_error("state_value_hash", err))?;
+
+        Ok((
+            AccountStateProofAssets::new(
+                account_state_proof,
+                *proof_assets.key().as_ref(),
+                *leaf_hash.as_ref(),
+                *proof_assets.transaction_version(),
+                self.latest_li_bytes()?,
+            ),
+            ValidatorVerifierAssets::new(self.validator_verifier()?.to_bytes()),
+        ))
+    }
+
+    fn latest_proof_account(
+        &self,
+        step: &'static str,
+        account_idx: usize,
+    ) -> Result<AptosSparseMerkleProofAssets, LightClientError> {
+        let nbr_accounts = self.aptos_wrapper.accounts().len();
+        if account_idx >= nbr_accounts {
+            return Err(fixture_error(
+                step,
+                format!(
+                    "requested account at index {account_idx}, but only {nbr_accounts} leaves were generated"
+                ),
+            ));
+        }
+
+        self.aptos_wrapper
+            .get_latest_proof_account(account_idx)
+            .map_err(|err| fixture_error("get_latest_proof_account", err))
+    }
+
+    /// Returns the assets to prove the inclusion of a module published on the chain, such
+    /// as the ones of the framework at genesis, in the state of the latest transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address the module is published at.
+    /// * `module_name` - The name of the module.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `InclusionData` for the module, or `Err` if no such module
+    /// is published.
+    pub fn module_inclusion_data(
+        &self,
+        address: AccountAddress,
+        module_name: &str,
+    ) -> Result<InclusionData, LightClientError> {
+        let module_name =
+            Identifier::new(module_name).map_err(|err| fixture_error("module name", err))?;
+        let module_id = ModuleId::new(address, module_name.clone());
+        let proof_assets = self
+            .aptos_wrapper
+            .get_latest_proof(&StateKey::module(&address, &module_name))
+            .map_err(|err| fixture_error("get_latest_proof", err))?;
+
+        let sparse_merkle_proof = bcs::to_bytes(proof_assets.state_proof())
+            .map_err(|err| fixture_error("serialize SparseMerkleProof", err))?;
+        let state_value = proof_assets.state_value().as_ref().ok_or_else(|| {
+            fixture_error("module_inclusion_data", format!("no module {module_name}"))
+        })?;
+        // The module may be stored along with the metadata of its storage slot
+        let state_value =
+            bcs::to_bytes(state_value).map_err(|err| fixture_error("serialize StateValue", err))?;
+        let (module_bytecode, module_metadata) = split_module_state_value(&state_value)
+            .map_err(|err| fixture_error("split_module_state_value", err))?;
+
+        let mut sparse_merkle_proof_assets = SparseMerkleProofAssets::for_module(
+            sparse_merkle_proof,
+            *proof_assets.key().as_ref(),
+            module_bytecode.to_vec(),
+        )
+        .with_key_preimage(StateKeyPreimage::for_module(&module_id));
+        if let Some(module_metadata) = module_metadata {
+            sparse_merkle_proof_assets =
+                sparse_merkle_proof_assets.with_module_metadata(module_metadata.to_vec());
+        }
+
+        self.inclusion_data_for_leaf(&proof_assets, sparse_merkle_proof_assets)
+    }
+
+    fn inclusion_data_for_leaf(
+        &self,
+        proof_assets: &AptosSparseMerkleProofAssets,
+        sparse_merkle_proof_assets: SparseMerkleProofAssets,
+    ) -> Result<InclusionData, LightClientError> {
+        let transaction = bcs::to_bytes(proof_assets.transaction())
+            .map_err(|err| fixture_error("serialize TransactionInfo", err))?;
+        let transaction_proof = bcs::to_bytes(proof_assets.transaction_proof())
+            .map_err(|err| fixture_error("serialize AccumulatorProof", err))?;
+
+        Ok(InclusionData {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets: TransactionProofAssets::new(
+                transaction,
+                *proof_assets.transaction_version(),
+                transaction_proof,
+                self.latest_li_bytes()?,
+            ),
+            validator_verifier_assets: ValidatorVerifierAssets::new(
+                self.validator_verifier()?.to_bytes(),
+            ),
+        })
+    }
+
+    /// Generates some traffic and a new epoch on the chain, and returns the assets to prove
+    /// the change from the current trusted state to the new epoch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `EpochChangeData` for the new epoch.
+    pub fn epoch_change_data(&mut self) -> Result<EpochChangeData, LightClientError> {
+        Ok(self.epoch_change()?.0)
+    }
+
+    /// Same as `epoch_change_data`, but also ratchets the trusted state of the chain to the
+    /// new epoch, so that consecutive calls return consecutive epoch changes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `EpochChangeData` for the new epoch.
+    pub fn next_epoch_change_data(&mut self) -> Result<EpochChangeData, LightClientError> {
+        let (epoch_change_data, state_proof) = self.epoch_change()?;
+
+        self.aptos_wrapper
+            .execute_block(ExecuteBlockArgs::StateProof(Box::new(state_proof)))
+            .map_err(|err| fixture_error("execute_block", err))?;
+
+        Ok(epoch_change_data)
+    }
+
+    fn epoch_change(&mut self) -> Result<(EpochChangeData, StateProof), LightClientError> {
+        let trusted_state = self.trusted_state()?;
+        let trusted_state_version = *self.aptos_wrapper.current_version();
+
+        self.generate_traffic()?;
+
+        let state_proof = self.new_state_proof(trusted_state_version)?;
+        let epoch_change_proof = epoch_change_assets_from_state_proof(&state_proof)?;
+
+        Ok((
+            EpochChangeData {
+                trusted_state,
+                epoch_change_proof,
+            },
+            state_proof,
+        ))
+    }
+}
+
+/// Generates a simulated chain and returns the assets to prove the inclusion of its latest
+/// account, for crates testing their integration with the inclusion program.
+///
+/// The accounts and validators are always derived from the same keys, but the transfers
+/// of the block are picked at random, so that the state root and the signed ledger info
+/// generally differ between calls. Use `generate_inclusion_fixture_seeded` for assets that
+/// are the same on every call.
+///
+/// # Arguments
+///
+/// * `nbr_leaves` - The number of accounts of the chain, i.e. of leaves in the state tree.
+/// * `nbr_validators` - The number of validators of the chain.
+/// * `signers` - The number of validators signing each block.
+///
+/// # Returns
+///
+/// A `Result` containing the `ProvingAssets`, or `Err` if the chain could not be generated.
+#[cfg(feature = "test-fixtures")]
+pub fn generate_inclusion_fixture(
+    nbr_leaves: usize,
+    nbr_validators: usize,
+    signers: usize,
+) -> Result<ProvingAssets, LightClientError> {
+    let mut fixture = AptosWrapperFixture::new(nbr_leaves, nbr_validators, signers)?;
+    fixture.generate_traffic()?;
+
+    ProvingAssets::from_inclusion_data(fixture.inclusion_data(nbr_leaves - 1)?)
+}
+
+/// Same as `generate_inclusion_fixture`, but picks the transfers of the block from a seed,
+/// so that the same arguments always give the same assets.
+///
+/// # Arguments
+///
+/// * `nbr_leaves` - The number of accounts of the chain, i.e. of leaves in the state tree.
+/// * `nbr_validators` - The number of validators of the chain.
+/// * `signers` - The number of validators signing each block.
+/// * `seed` - The seed to pick the transfers from.
+///
+/// # Returns
+///
+/// A `Result` containing the `ProvingAssets`, or `Err` if the chain could not be generated.
+#[cfg(feature = "test-fixtures")]
+pub fn generate_inclusion_fixture_seeded(
+    nbr_leaves: usize,
+    nbr_validators: usize,
+    signers: usize,
+    seed: [u8; 32],
+) -> Result<ProvingAssets, LightClientError> {
+    let mut fixture = AptosWrapperFixture::new(nbr_leaves, nbr_validators, signers)?;
+    fixture.generate_traffic_seeded(seed)?;
+
+    ProvingAssets::from_inclusion_data(fixture.inclusion_data(nbr_leaves - 1)?)
+}
+
+/// Generates a simulated chain of 2 accounts and 4 validators, 3 of them signing each block,
+/// with a block of transfers on top of its genesis. This is the chain the tests of the crate
+/// run the programs on, unless they need another one.
+#[cfg(test)]
+pub(crate) fn generate_fixture() -> AptosWrapperFixture {
+    let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+    fixture.generate_traffic().unwrap();
+    fixture
+}
+
+/// Returns the `ProvingAssets` of the second account of the chain of `fixture`.
+#[cfg(test)]
+pub(crate) fn generate_proving_assets(fixture: &AptosWrapperFixture) -> ProvingAssets {
+    ProvingAssets::from_inclusion_data(fixture.inclusion_data(1).unwrap()).unwrap()
+}
+
+/// Returns the stdin of the inclusion program for the second account of a chain generated
+/// by `generate_fixture`.
+#[cfg(test)]
+pub(crate) fn generate_inclusion_stdin() -> SP1Stdin {
+    let inclusion_data = generate_fixture().inclusion_data(1).unwrap();
+
+    generate_stdin(
+        &inclusion_data.sparse_merkle_proof_assets,
+        &inclusion_data.transaction_proof_assets,
+        &inclusion_data.validator_verifier_assets,
+    )
+    .unwrap()
+}
+
+/// Returns the name of the check or of the structure the validation of some assets failed
+/// on, panicking if it did not fail with a validation error.
+#[cfg(test)]
+pub(crate) fn failing_check(res: Result<(), LightClientError>) -> String {
+    match res {
+        Err(LightClientError::VerificationError { check, .. }) => check,
+        Err(LightClientError::DeserializationError { structure, .. }) => structure,
+        other => panic!("expected a validation error, got {other:?}"),
+    }
+}
+
+fn fixture_error(
+    step: &'static str,
+    source: impl Into<Box<dyn std::error::Error + Sync + Send>>,
+) -> LightClientError {
+    LightClientError::FixtureError {
+        step,
+        source: source.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::inclusion::validate_inclusion_assets;
+
+    #[test]
+    fn test_fixture_trusted_state_is_consistent() {
+        let first = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let second = AptosWrapperFixture::new(2, 4, 3).unwrap();
+
+        assert_eq!(
+            first.trusted_state().unwrap(),
+            second.trusted_state().unwrap()
+        );
+        assert_eq!(
+            first.validator_verifier().unwrap(),
+            second.validator_verifier().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fixture_inclusion_data_out_of_bounds() {
+        let fixture = generate_fixture();
+
+        match fixture.inclusion_data(2) {
+            Err(LightClientError::FixtureError { step, source }) => {
+                assert_eq!(step, "inclusion_data");
+                assert!(source.to_string().contains("index 2"));
+                assert!(source.to_string().contains("only 2 leaves"));
+            }
+            Err(err) => panic!("expected a fixture error, got {err}"),
+            Ok(_) => panic!("expected a fixture error for an out of bounds index"),
+        }
+    }
+
+    #[cfg(feature = "test-fixtures")]
+    #[test]
+    fn test_generate_inclusion_fixture() {
+        use crate::inclusion::{assert_inclusion_output, InclusionOutput, INCLUSION_ELF};
+        use sp1_sdk::ProverClient;
+
+        let proving_assets = generate_inclusion_fixture(2, 4, 3).unwrap();
+
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client
+            .execute(INCLUSION_ELF, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+
+        // The same seed gives the same assets
+        let seed = [7; 32];
+        assert_eq!(
+            generate_inclusion_fixture_seeded(2, 4, 3, seed)
+                .unwrap()
+                .state_checkpoint_hash(),
+            generate_inclusion_fixture_seeded(2, 4, 3, seed)
+                .unwrap()
+                .state_checkpoint_hash()
+        );
+    }
+
+    #[test]
+    fn test_fixture_without_accounts() {
+        assert!(matches!(
+            AptosWrapperFixture::new(0, 4, 3),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fixture_more_signers_than_validators() {
+        match AptosWrapperFixture::new(2, 4, 5) {
+            Err(LightClientError::FixtureError { step, .. }) => assert_eq!(step, "new"),
+            Err(err) => panic!("expected a fixture error, got {err}"),
+            Ok(_) => panic!("expected a fixture error for more signers than validators"),
+        }
+    }
+
+    #[test]
+    fn test_fixture_single_account() {
+        let mut fixture = AptosWrapperFixture::new(1, 4, 3).unwrap();
+        fixture.generate_traffic().unwrap();
+        let inclusion_data = fixture.inclusion_data(0).unwrap();
+
+        validate_inclusion_assets(&ProvingAssets::from_inclusion_data(inclusion_data).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fixture_does_not_install_logger() {
+        use tracing::subscriber::NoSubscriber;
+
+        // The fixture leaves the logs to the dispatcher of the caller
+        tracing::subscriber::with_default(NoSubscriber::new(), || {
+            let mut fixture = generate_fixture();
+            fixture.inclusion_data(1).unwrap();
+            fixture.epoch_change_data().unwrap();
+
+            tracing::dispatcher::get_default(|dispatch| assert!(dispatch.is::<NoSubscriber>()));
+        });
+    }
+
+    #[test]
+    fn test_fixture_sample_account() {
+        use crate::inclusion::{
+            account_state_key, run_inclusion_guest, InclusionOutput, ProvingAssets,
+        };
+
+        let mut fixture = AptosWrapperFixture::new(4, 4, 3).unwrap();
+        fixture.generate_traffic().unwrap();
+
+        let address = fixture.aptos_wrapper().accounts()[2].address();
+        let account_idx = fixture.account_index(address).unwrap();
+        assert_eq!(account_idx, 2);
+        let proving_assets =
+            ProvingAssets::from_inclusion_data(fixture.inclusion_data(account_idx).unwrap())
+                .unwrap();
+        let mut public_values = run_inclusion_guest(proving_assets.stdin().unwrap()).unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(output.key(), &account_state_key(address));
+
+        assert!(matches!(
+            fixture.account_index(AccountAddress::ONE),
+            Err(LightClientError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fixture_ledger_info_signers_around_quorum() {
+        use crate::inclusion::{run_inclusion_guest, InclusionOutput, ProvingAssets};
+        use aptos_lc_core::types::status::InclusionStatus;
+
+        // The quorum of 130 validators of equal voting power is 2f + 1 = 87 of them
+        let run = |nbr_signers| {
+            let mut fixture = AptosWrapperFixture::new(2, 130, 95)
+                .unwrap()
+                .with_ledger_info_signers(nbr_signers);
+            fixture.generate_traffic().unwrap();
+            let proving_assets = generate_proving_assets(&fixture);
+            let mut public_values = run_inclusion_guest(proving_assets.stdin().unwrap()).unwrap();
+            InclusionOutput::from_public_values(&mut public_values)
+        };
+
+        assert!(matches!(
+            run(86),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::InsufficientVotingPower
+            })
+        ));
+        // At quorum, the inclusion is proven as usual
+        run(87).unwrap();
+    }
+}
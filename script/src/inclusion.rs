@@ -1,16 +1,136 @@
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use aptos_sdk::crypto::hash::CryptoHash as AptosCryptoHash;
+use aptos_sdk::move_types::language_storage::ModuleId;
+use aptos_sdk::move_types::move_resource::MoveStructType;
+use aptos_types::access_path::AccessPath;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::account_config::AccountResource;
+use aptos_types::state_store::state_key::StateKey;
 use getset::Getters;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+use sp1_sdk::{
+    ExecutionReport, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues,
+    SP1Stdin, SP1VerifyingKey,
+};
+use aptos_lc_core::crypto::hash::{CryptoHash, HashValue};
+use aptos_lc_core::merkle::account_state_proof::ProofLayout;
+use aptos_lc_core::merkle::event_proof::contract_event_hash;
+use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
+use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorProof;
+use aptos_lc_core::types::leaf::{
+    access_path_leaf_kind, access_path_state_key, module_value_hash,
+    module_value_hash_with_metadata, state_value_hash, LeafKind,
+};
+use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::output::{
+    inclusion_accumulator_root, BATCH_OUTPUT_MAGIC, EVENT_OUTPUT_MAGIC, INCLUSION_OUTPUT_MAGIC,
+    INCLUSION_OUTPUT_VERSION, TRANSACTIONS_OUTPUT_MAGIC,
+};
+use aptos_lc_core::types::status::InclusionStatus;
+use aptos_lc_core::types::transaction::TransactionInfo;
+use aptos_lc_core::types::validator::ValidatorVerifier;
+use crate::bundle::vk_hash;
+use crate::client::{proof_vkey_hash, setup_program, verify_timed};
 use crate::error::LightClientError;
+use crate::io::vk_to_bytes;
+use crate::output::{serialize_hex, serialize_hex_option, PublicValuesReader};
+use crate::telemetry::{record_cycles, record_proof};
+use crate::types::InclusionData;
 
+#[cfg(not(feature = "skip-signatures"))]
 pub const INCLUSION_ELF: &[u8] = include_bytes!("../../programs/inclusion/elf/riscv32im-succinct-zkvm-elf");
+/// INSECURE: the inclusion program built with its `skip-signatures` feature, which does not
+/// verify the signatures of the ledger info. Only meant to test the other checks locally,
+/// its outputs are rejected by `assert_signatures_verified`.
+#[cfg(feature = "skip-signatures")]
+pub const INCLUSION_ELF: &[u8] =
+    include_bytes!("../../programs/inclusion/elf/riscv32im-succinct-zkvm-elf-skip-signatures");
+
+/// Default maximum number of bytes of the proofs written to the stdin of the program.
+pub const DEFAULT_MAX_STDIN_BYTES: usize = 64 * 1024 * 1024;
+
+/// Environment variable overriding `DEFAULT_MAX_STDIN_BYTES`.
+pub const MAX_STDIN_BYTES_ENV: &str = "APTOS_LC_MAX_STDIN_BYTES";
+
+/// Address and path of the access path of a leaf, which hash to its key with
+/// `access_path_state_key`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize, JsonSchema)]
+#[getset(get = "pub")]
+pub struct StateKeyPreimage {
+    /// Address of the account holding the resource or the module.
+    address: [u8; 32],
+    /// Path of the resource or the module under the address, i.e. the bcs serialization
+    /// of its `Path`.
+    path: Vec<u8>,
+}
+
+impl StateKeyPreimage {
+    pub const fn new(address: [u8; 32], path: Vec<u8>) -> StateKeyPreimage {
+        StateKeyPreimage { address, path }
+    }
+
+    /// Returns the preimage of the key of the leaf holding the `AccountResource` of an
+    /// account, as computed by `account_state_key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the account.
+    ///
+    /// # Returns
+    ///
+    /// The `StateKeyPreimage` of the account resource.
+    pub fn for_account(address: AccountAddress) -> StateKeyPreimage {
+        let path = AccessPath::resource_path_vec(AccountResource::struct_tag())
+            .expect("the AccountResource path should be serializable");
+
+        StateKeyPreimage::new(address.into_bytes(), path)
+    }
+
+    /// Returns the preimage of the key of the leaf holding the bytecode of a published
+    /// module, which the program requires to prove it.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_id` - The id of the module, i.e. its address and its name.
+    ///
+    /// # Returns
+    ///
+    /// The `StateKeyPreimage` of the module.
+    pub fn for_module(module_id: &ModuleId) -> StateKeyPreimage {
+        let access_path = AccessPath::code_access_path(module_id.clone());
+
+        StateKeyPreimage::new(access_path.address.into_bytes(), access_path.path)
+    }
+
+    /// Returns the key the preimage hashes to.
+    pub fn state_key(&self) -> [u8; 32] {
+        *access_path_state_key(&self.address, &self.path).as_ref()
+    }
+}
 
-#[derive(Clone, Debug, Getters, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize, JsonSchema)]
 #[getset(get = "pub")]
 pub struct SparseMerkleProofAssets {
     sparse_merkle_proof: Vec<u8>,
     leaf_key: [u8; 32],
     leaf_hash: [u8; 32],
+    /// Bytecode of the module held by the leaf, if it is a module rather than a resource.
+    /// The program then hashes it itself rather than trusting `leaf_hash`.
+    #[serde(default)]
+    module_bytecode: Option<Vec<u8>>,
+    /// Metadata of the storage slot of the module, serialized with bcs, if it was stored
+    /// with some. The program hashes it along with `module_bytecode`.
+    #[serde(default)]
+    module_metadata: Option<Vec<u8>>,
+    /// Preimage of `leaf_key`, checked and committed by the program if given.
+    #[serde(default)]
+    key_preimage: Option<StateKeyPreimage>,
 }
 
 impl SparseMerkleProofAssets {
@@ -23,17 +143,106 @@ impl SparseMerkleProofAssets {
             sparse_merkle_proof,
             leaf_key,
             leaf_hash,
+            module_bytecode: None,
+            module_metadata: None,
+            key_preimage: None,
+        }
+    }
+
+    /// Creates the assets to prove the inclusion of a published module, whose leaf hash
+    /// is computed from its bytecode. The program only proves it along with the preimage
+    /// of its key, as returned by `StateKeyPreimage::for_module` and set with
+    /// `with_key_preimage`, which ties the leaf to a module.
+    ///
+    /// # Arguments
+    ///
+    /// * `sparse_merkle_proof` - The proof of the module leaf, serialized with bcs.
+    /// * `leaf_key` - The key of the module leaf.
+    /// * `module_bytecode` - The bytecode of the module.
+    ///
+    /// # Returns
+    ///
+    /// The `SparseMerkleProofAssets` for the module leaf.
+    pub fn for_module(
+        sparse_merkle_proof: Vec<u8>,
+        leaf_key: [u8; 32],
+        module_bytecode: Vec<u8>,
+    ) -> SparseMerkleProofAssets {
+        SparseMerkleProofAssets {
+            sparse_merkle_proof,
+            leaf_key,
+            leaf_hash: leaf_hash_for_module(&module_bytecode),
+            module_bytecode: Some(module_bytecode),
+            module_metadata: None,
+            key_preimage: None,
+        }
+    }
+
+    /// Sets the metadata of the storage slot of a module stored with some, as returned by
+    /// `split_module_state_value`. The leaf hash of the module is computed again along
+    /// with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_metadata` - The metadata of the state value, serialized with bcs.
+    ///
+    /// # Returns
+    ///
+    /// The `SparseMerkleProofAssets` with the metadata of the module set.
+    pub fn with_module_metadata(mut self, module_metadata: Vec<u8>) -> Self {
+        self.module_metadata = Some(module_metadata);
+        self.rehash_module();
+        self
+    }
+
+    /// Computes the leaf hash of a module again, as the program does.
+    fn rehash_module(&mut self) {
+        if let Some(module_bytecode) = &self.module_bytecode {
+            self.leaf_hash =
+                *module_value_hash_with_metadata(module_bytecode, self.module_metadata.as_deref())
+                    .as_ref();
+        }
+    }
+
+    /// Sets the address and the path the key of the leaf is the hash of. The program then
+    /// fails unless they hash to the key, and commits them along with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_preimage` - The preimage of the key.
+    ///
+    /// # Returns
+    ///
+    /// The `SparseMerkleProofAssets` with the preimage of the key set.
+    pub fn with_key_preimage(mut self, key_preimage: StateKeyPreimage) -> Self {
+        self.key_preimage = Some(key_preimage);
+        self
+    }
+
+    /// Returns the kind of leaf the assets prove the inclusion of.
+    pub const fn leaf_kind(&self) -> LeafKind {
+        match self.module_bytecode {
+            Some(_) => LeafKind::Module,
+            None => LeafKind::Resource,
         }
     }
 }
 
-#[derive(Clone, Debug, Getters, Serialize, Deserialize)]
+#[derive(Clone, Debug, Getters, Serialize, Deserialize, JsonSchema)]
 #[getset(get = "pub")]
 pub struct TransactionProofAssets {
     transaction: Vec<u8>,
     transaction_index: u64,
     transaction_proof: Vec<u8>,
     latest_li: Vec<u8>,
+    /// Ledger info carrying the signatures, if fetched separately from `latest_li`. It
+    /// must share the transaction accumulator root of `latest_li`.
+    #[serde(default)]
+    signed_ledger_info: Option<Vec<u8>>,
+    /// `latest_li` decoded on first access through `ledger_info`.
+    #[getset(skip)]
+    #[serde(skip)]
+    decoded_latest_li: OnceLock<LedgerInfoWithSignatures>,
 }
 
 impl TransactionProofAssets {
@@ -48,102 +257,4305 @@ impl TransactionProofAssets {
             transaction_index,
             transaction_proof,
             latest_li,
+            signed_ledger_info: None,
+            decoded_latest_li: OnceLock::new(),
+        }
+    }
+
+    /// Sets a ledger info to verify the signatures on, distinct from the one the
+    /// transaction proof is verified against.
+    ///
+    /// # Arguments
+    ///
+    /// * `signed_ledger_info` - The ledger info carrying the signatures, serialized with
+    ///   bcs. It must be at the same version and epoch as `latest_li`, and share its
+    ///   transaction accumulator root.
+    ///
+    /// # Returns
+    ///
+    /// The `TransactionProofAssets` with the signed ledger info set.
+    pub fn with_signed_ledger_info(mut self, signed_ledger_info: Vec<u8>) -> Self {
+        self.signed_ledger_info = Some(signed_ledger_info);
+        self
+    }
+
+    /// Returns the ledger info the transaction is verified against, deserialized from
+    /// `latest_li`. The decoded value is cached for subsequent calls.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `LedgerInfoWithSignatures`, or `Err` if `latest_li`
+    /// could not be deserialized.
+    pub fn ledger_info(&self) -> Result<LedgerInfoWithSignatures, LightClientError> {
+        if let Some(ledger_info) = self.decoded_latest_li.get() {
+            return Ok(ledger_info.clone());
+        }
+
+        let ledger_info = LedgerInfoWithSignatures::from_bytes(&self.latest_li).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "LedgerInfoWithSignatures".to_string(),
+                source: err.into(),
+            }
+        })?;
+
+        Ok(self.decoded_latest_li.get_or_init(|| ledger_info).clone())
+    }
+
+    /// Returns the root of the transaction accumulator of `latest_li`, which the program
+    /// verifies the transaction proof against.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the root hash, or `Err` if `latest_li` could not be
+    /// deserialized.
+    pub fn accumulator_root(&self) -> Result<[u8; 32], LightClientError> {
+        let ledger_info = self.ledger_info()?;
+        let root_hash = ledger_info.ledger_info().transaction_accumulator_hash();
+
+        Ok(*root_hash.as_ref())
+    }
+
+    /// The serialized fields, leaving out the cache of `latest_li`, so that assets compare
+    /// equal whether or not it was decoded.
+    #[allow(clippy::type_complexity)]
+    const fn serialized_fields(&self) -> (&Vec<u8>, &u64, &Vec<u8>, &Vec<u8>, &Option<Vec<u8>>) {
+        (
+            &self.transaction,
+            &self.transaction_index,
+            &self.transaction_proof,
+            &self.latest_li,
+            &self.signed_ledger_info,
+        )
+    }
+}
+
+impl PartialEq for TransactionProofAssets {
+    fn eq(&self, other: &Self) -> bool {
+        self.serialized_fields() == other.serialized_fields()
+    }
+}
+
+impl Eq for TransactionProofAssets {}
+
+impl Hash for TransactionProofAssets {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.serialized_fields().hash(state);
+    }
+}
+
+/// Assets to prove the inclusion of an account from a single `AccountStateProof`, bundling
+/// the proof of the transaction in the accumulator with the proof of the account in the
+/// state tree at that transaction.
+///
+/// The proof is expected in the encoding of `aptos-node-v1.14.0`, i.e. the bcs
+/// serialization of a `TransactionInfoWithProof` followed by the one of a
+/// `SparseMerkleProof`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct AccountStateProofAssets {
+    account_state_proof: Vec<u8>,
+    leaf_key: [u8; 32],
+    leaf_hash: [u8; 32],
+    transaction_index: u64,
+    latest_li: Vec<u8>,
+}
+
+impl AccountStateProofAssets {
+    pub const fn new(
+        account_state_proof: Vec<u8>,
+        leaf_key: [u8; 32],
+        leaf_hash: [u8; 32],
+        transaction_index: u64,
+        latest_li: Vec<u8>,
+    ) -> AccountStateProofAssets {
+        AccountStateProofAssets {
+            account_state_proof,
+            leaf_key,
+            leaf_hash,
+            transaction_index,
+            latest_li,
+        }
+    }
+
+    /// Bundles the proofs of split assets in a single `AccountStateProof`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sparse_merkle_proof_assets` - The assets for the account inclusion in the state tree.
+    /// * `transaction_proof_assets` - The assets for the transaction inclusion in the accumulator.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `AccountStateProofAssets`, or `Err` if the assets prove a
+    /// module, or carry a separately signed ledger info, which the bundled proof does not
+    /// cover.
+    pub fn from_split_assets(
+        sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+        transaction_proof_assets: &TransactionProofAssets,
+    ) -> Result<AccountStateProofAssets, LightClientError> {
+        if sparse_merkle_proof_assets.module_bytecode.is_some() {
+            return Err(LightClientError::InvalidInput {
+                input: "sparse_merkle_proof_assets".to_string(),
+                reason: "an account state proof only proves resources".to_string(),
+            });
+        }
+        if transaction_proof_assets.signed_ledger_info.is_some() {
+            return Err(LightClientError::InvalidInput {
+                input: "transaction_proof_assets".to_string(),
+                reason: "an account state proof is verified against latest_li".to_string(),
+            });
+        }
+
+        // The bundled proof is the concatenation of the bcs serialization of its parts
+        let account_state_proof = [
+            transaction_proof_assets.transaction_proof.as_slice(),
+            &transaction_proof_assets.transaction,
+            &sparse_merkle_proof_assets.sparse_merkle_proof,
+        ]
+        .concat();
+
+        Ok(AccountStateProofAssets::new(
+            account_state_proof,
+            sparse_merkle_proof_assets.leaf_key,
+            sparse_merkle_proof_assets.leaf_hash,
+            transaction_proof_assets.transaction_index,
+            transaction_proof_assets.latest_li.clone(),
+        ))
+    }
+}
+
+/// Assets to prove the inclusion of several transactions, at different indices, in the
+/// accumulator of a single ledger info. Its signatures are only verified once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct MultiTransactionProofAssets {
+    /// The transactions, each along with its index and its proof in the accumulator.
+    transactions: Vec<(Vec<u8>, u64, Vec<u8>)>,
+    latest_li: Vec<u8>,
+}
+
+impl MultiTransactionProofAssets {
+    /// Creates the `MultiTransactionProofAssets` of transactions proven under `latest_li`.
+    ///
+    /// # Arguments
+    ///
+    /// * `transactions` - The transactions, each along with its index and its proof in the
+    ///   accumulator.
+    /// * `latest_li` - The ledger info the transactions are proven against.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `MultiTransactionProofAssets`, or `Err` if there are no
+    /// transactions.
+    pub fn new(
+        transactions: Vec<(Vec<u8>, u64, Vec<u8>)>,
+        latest_li: Vec<u8>,
+    ) -> Result<MultiTransactionProofAssets, LightClientError> {
+        if transactions.is_empty() {
+            return Err(LightClientError::InvalidInput {
+                input: "transactions".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        Ok(MultiTransactionProofAssets {
+            transactions,
+            latest_li,
+        })
+    }
+
+    /// Gathers the transactions of several `TransactionProofAssets` sharing a ledger info.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction_proof_assets` - The assets for each transaction inclusion in the
+    ///   accumulator.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `MultiTransactionProofAssets`, or `Err` if there are no
+    /// assets, if they are not verified against the same `latest_li`, or if they carry a
+    /// separately signed ledger info.
+    pub fn from_transaction_proof_assets(
+        transaction_proof_assets: &[TransactionProofAssets],
+    ) -> Result<MultiTransactionProofAssets, LightClientError> {
+        let invalid_input = |reason: &str| LightClientError::InvalidInput {
+            input: "transaction_proof_assets".to_string(),
+            reason: reason.to_string(),
+        };
+
+        let first = transaction_proof_assets
+            .first()
+            .ok_or_else(|| invalid_input("must not be empty"))?;
+        if transaction_proof_assets
+            .iter()
+            .any(|assets| assets.latest_li != first.latest_li)
+        {
+            return Err(invalid_input("the transactions must share latest_li"));
+        }
+        if transaction_proof_assets
+            .iter()
+            .any(|assets| assets.signed_ledger_info.is_some())
+        {
+            return Err(invalid_input(
+                "the transactions are verified against latest_li",
+            ));
+        }
+
+        MultiTransactionProofAssets::new(
+            transaction_proof_assets
+                .iter()
+                .map(|assets| {
+                    (
+                        assets.transaction.clone(),
+                        assets.transaction_index,
+                        assets.transaction_proof.clone(),
+                    )
+                })
+                .collect(),
+            first.latest_li.clone(),
+        )
+    }
+}
+
+/// Assets to prove the inclusion of several resources in the state checkpoint of a single
+/// transaction. The program commits the number of leaves and the root folding them, rather
+/// than each of them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct BatchInclusionProofAssets {
+    /// The leaves, each as its key, the hash of its value and its sparse Merkle proof.
+    leaves: Vec<([u8; 32], [u8; 32], Vec<u8>)>,
+    transaction_proof_assets: TransactionProofAssets,
+}
+
+impl BatchInclusionProofAssets {
+    /// Gathers the leaves of several `SparseMerkleProofAssets`, proven against the state
+    /// checkpoint of the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `sparse_merkle_proof_assets` - The assets for each leaf inclusion in the state.
+    /// * `transaction_proof_assets` - The assets for the transaction inclusion in the
+    ///   accumulator.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `BatchInclusionProofAssets`, or `Err` if there are no
+    /// leaves, if two leaves share a key, if a leaf is not a resource, or if the transaction
+    /// carries a separately signed ledger info.
+    pub fn new(
+        sparse_merkle_proof_assets: &[SparseMerkleProofAssets],
+        transaction_proof_assets: TransactionProofAssets,
+    ) -> Result<BatchInclusionProofAssets, LightClientError> {
+        let invalid_input = |input: &str, reason: &str| LightClientError::InvalidInput {
+            input: input.to_string(),
+            reason: reason.to_string(),
+        };
+
+        if sparse_merkle_proof_assets.is_empty() {
+            return Err(invalid_input(
+                "sparse_merkle_proof_assets",
+                "must not be empty",
+            ));
+        }
+        let mut keys = HashSet::new();
+        if !sparse_merkle_proof_assets
+            .iter()
+            .all(|assets| keys.insert(assets.leaf_key))
+        {
+            return Err(invalid_input(
+                "sparse_merkle_proof_assets",
+                "the leaves must have distinct keys",
+            ));
+        }
+        if sparse_merkle_proof_assets
+            .iter()
+            .any(|assets| assets.module_bytecode.is_some())
+        {
+            return Err(invalid_input(
+                "sparse_merkle_proof_assets",
+                "only resources are proven in a batch",
+            ));
+        }
+        if transaction_proof_assets.signed_ledger_info.is_some() {
+            return Err(invalid_input(
+                "transaction_proof_assets",
+                "the leaves are verified against latest_li",
+            ));
+        }
+
+        Ok(BatchInclusionProofAssets {
+            leaves: sparse_merkle_proof_assets
+                .iter()
+                .map(|assets| {
+                    (
+                        assets.leaf_key,
+                        assets.leaf_hash,
+                        assets.sparse_merkle_proof.clone(),
+                    )
+                })
+                .collect(),
+            transaction_proof_assets,
+        })
+    }
+
+    /// Recomputes the root the program commits for the leaves, to check a
+    /// `BatchInclusionOutput` against. As in the program, the leaves are folded in the
+    /// ascending order of their keys, whatever the order they were given in.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `inclusion_accumulator_root` of the keys and value hashes
+    /// of the leaves, or `Err` if two leaves share a key, for which the program commits
+    /// `InclusionStatus::DuplicateKey`.
+    pub fn accumulator_root(&self) -> Result<[u8; 32], LightClientError> {
+        let leaves = self
+            .leaves
+            .iter()
+            .map(|(key, value_hash, _)| (*key, *value_hash))
+            .collect::<Vec<_>>();
+
+        inclusion_accumulator_root(&leaves)
+            .map(|root| *root.as_ref())
+            .ok_or_else(|| LightClientError::InvalidInput {
+                input: "leaves".to_string(),
+                reason: "the leaves must have distinct keys".to_string(),
+            })
+    }
+}
+
+/// Assets to prove that an event was emitted by a transaction, given along with them as
+/// `TransactionProofAssets`.
+///
+/// An event is not part of the state: rather than a leaf proven against the state
+/// checkpoint of the transaction, as `SparseMerkleProofAssets` are, it is proven against
+/// the event root hash of the transaction, the root of the accumulator of the events it
+/// emitted. It thus attests that the event happened, not that any value is current.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct EventProofAssets {
+    /// The `ContractEvent`, serialized with bcs.
+    event: Vec<u8>,
+    /// The index of the event among the events emitted by the transaction.
+    event_index: u64,
+    /// The proof of the event in the event accumulator of the transaction, serialized with
+    /// bcs.
+    event_proof: Vec<u8>,
+}
+
+impl EventProofAssets {
+    pub const fn new(event: Vec<u8>, event_index: u64, event_proof: Vec<u8>) -> Self {
+        Self {
+            event,
+            event_index,
+            event_proof,
         }
     }
+
+    /// Returns the hash of the event, as committed by the program in an `EventOutput`.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the event.
+    pub fn event_hash(&self) -> [u8; 32] {
+        *contract_event_hash(&self.event).as_ref()
+    }
 }
 
-#[derive(Clone, Debug, Getters, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, Serialize, Deserialize, JsonSchema)]
 #[getset(get = "pub")]
 pub struct ValidatorVerifierAssets {
     validator_verifier: Vec<u8>,
+    /// Second candidate validator verifier, for a ledger info around an epoch boundary that
+    /// may be signed by either the previous or the next validator set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alternative_validator_verifier: Option<Vec<u8>>,
 }
 
 impl ValidatorVerifierAssets {
     pub const fn new(validator_verifier: Vec<u8>) -> ValidatorVerifierAssets {
-        ValidatorVerifierAssets { validator_verifier }
+        ValidatorVerifierAssets {
+            validator_verifier,
+            alternative_validator_verifier: None,
+        }
+    }
+
+    /// Creates the assets for a validator verifier, after checking that it deserializes and
+    /// that its quorum can be reached, as the program requires.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator_verifier` - The serialized validator verifier.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ValidatorVerifierAssets`, or `Err` if the validator
+    /// verifier could not be deserialized or if its quorum voting power exceeds its total
+    /// voting power.
+    pub fn try_new(
+        validator_verifier: Vec<u8>,
+    ) -> Result<ValidatorVerifierAssets, LightClientError> {
+        check_quorum_reachable(&ValidatorVerifier::from_bytes(&validator_verifier).map_err(
+            |err| LightClientError::DeserializationError {
+                structure: "ValidatorVerifier".to_string(),
+                source: err.into(),
+            },
+        )?)?;
+
+        Ok(ValidatorVerifierAssets::new(validator_verifier))
     }
+
+    /// Creates the assets for one or two candidate validator verifiers. The program proves
+    /// the inclusion if the signatures verify under either of them, and commits the index
+    /// of the one they verified under.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator_verifiers` - The serialized candidate validator verifiers, in the order
+    ///   they are tried.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ValidatorVerifierAssets`, or `Err` if there are not one
+    /// or two candidates.
+    pub fn new_multi(
+        validator_verifiers: Vec<Vec<u8>>,
+    ) -> Result<ValidatorVerifierAssets, LightClientError> {
+        let nbr_candidates = validator_verifiers.len();
+        let mut validator_verifiers = validator_verifiers.into_iter();
+        match (
+            validator_verifiers.next(),
+            validator_verifiers.next(),
+            validator_verifiers.next(),
+        ) {
+            (Some(validator_verifier), alternative_validator_verifier, None) => {
+                Ok(ValidatorVerifierAssets {
+                    validator_verifier,
+                    alternative_validator_verifier,
+                })
+            }
+            _ => Err(LightClientError::InvalidInput {
+                input: "validator_verifiers".to_string(),
+                reason: format!("expected one or two candidates, got {nbr_candidates}"),
+            }),
+        }
+    }
+
+    /// Returns the candidate validator verifier the signatures of a ledger info verify
+    /// under, trying them in order as the inclusion program does.
+    ///
+    /// # Arguments
+    ///
+    /// * `signed_li` - The ledger info carrying the signatures.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the index of the matching candidate along with its
+    /// `ValidatorVerifier`, or `Err` if a candidate could not be deserialized, if the quorum
+    /// of every candidate is unreachable or if the signatures verify under none of them.
+    pub fn matching_validator_verifier(
+        &self,
+        signed_li: &LedgerInfoWithSignatures,
+    ) -> Result<(u8, ValidatorVerifier), LightClientError> {
+        // The program deserializes every candidate before verifying any signature
+        let validator_verifiers = std::iter::once(&self.validator_verifier)
+            .chain(&self.alternative_validator_verifier)
+            .map(|bytes| {
+                ValidatorVerifier::from_bytes(bytes).map_err(|err| {
+                    LightClientError::DeserializationError {
+                        structure: "ValidatorVerifier".to_string(),
+                        source: err.into(),
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // A candidate whose quorum is unreachable is skipped, and only reported when no
+        // other candidate could have verified the signatures
+        let mut quorum_error = None;
+        let mut signature_error = None;
+        for (index, validator_verifier) in (0u8..).zip(validator_verifiers) {
+            if let Err(err) = check_quorum_reachable(&validator_verifier) {
+                quorum_error = Some(err);
+                continue;
+            }
+            match signed_li.verify_signatures(&validator_verifier) {
+                Ok(()) => return Ok((index, validator_verifier)),
+                Err(err) => signature_error = Some(err),
+            }
+        }
+
+        Err(match signature_error {
+            Some(err) => LightClientError::VerificationError {
+                check: "LedgerInfoWithSignatures".to_string(),
+                source: err.into(),
+            },
+            None => quorum_error.expect("there is at least one candidate validator verifier"),
+        })
+    }
+}
+
+/// Checks that the quorum of a validator verifier can be reached, lest the program could
+/// never verify a ledger info signed by it.
+fn check_quorum_reachable(validator_verifier: &ValidatorVerifier) -> Result<(), LightClientError> {
+    if !validator_verifier.is_quorum_reachable() {
+        return Err(LightClientError::UnsatisfiableQuorum {
+            quorum_voting_power: validator_verifier.quorum_voting_power(),
+            total_voting_power: validator_verifier.total_voting_power(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Assets of the inclusion program for one account, along with the state root it is
+/// expected to commit.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct ProvingAssets {
+    sparse_merkle_proof_assets: SparseMerkleProofAssets,
+    transaction_proof_assets: TransactionProofAssets,
+    validator_verifier_assets: ValidatorVerifierAssets,
+    /// State root expected to be committed by the program, computed out-of-circuit.
+    state_checkpoint_hash: [u8; 32],
+}
+
+impl ProvingAssets {
+    /// Bundles the assets of the inclusion program, computing the state root they are
+    /// expected to prove the inclusion in.
+    ///
+    /// # Arguments
+    ///
+    /// * `inclusion_data` - The assets of the inclusion program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ProvingAssets`, or `Err` if the expected state root could
+    /// not be computed, as described for `expected_state_root`.
+    pub fn from_inclusion_data(inclusion_data: InclusionData) -> Result<Self, LightClientError> {
+        let InclusionData {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+        } = inclusion_data;
+
+        let state_checkpoint_hash =
+            expected_state_root(&sparse_merkle_proof_assets, &transaction_proof_assets)?;
+
+        Ok(Self {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+            state_checkpoint_hash,
+        })
+    }
+
+    /// Returns the `SP1Stdin` for the inclusion program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SP1Stdin`, or `Err` if an asset is empty.
+    pub fn stdin(&self) -> Result<SP1Stdin, LightClientError> {
+        generate_stdin(
+            &self.sparse_merkle_proof_assets,
+            &self.transaction_proof_assets,
+            &self.validator_verifier_assets,
+        )
+    }
+
+    /// Returns the values the inclusion program is expected to commit for these assets.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExpectedInclusion`, or `Err` if the validator verifier
+    /// or a ledger info could not be deserialized. With two candidate validator verifiers,
+    /// it is also `Err` if the signatures verify under neither of them.
+    pub fn expected_inclusion(&self) -> Result<ExpectedInclusion, LightClientError> {
+        // The program commits the block of the ledger info carrying the signatures.
+        let signed_li = match &self.transaction_proof_assets.signed_ledger_info {
+            Some(bytes) => LedgerInfoWithSignatures::from_bytes(bytes).map_err(|err| {
+                LightClientError::DeserializationError {
+                    structure: "LedgerInfoWithSignatures".to_string(),
+                    source: err.into(),
+                }
+            })?,
+            None => self.transaction_proof_assets.ledger_info()?,
+        };
+
+        // A single candidate is the one committed, whether the signatures verify or not
+        let assets = &self.validator_verifier_assets;
+        let validator_verifier = match assets.alternative_validator_verifier {
+            None => ValidatorVerifier::from_bytes(&assets.validator_verifier).map_err(|err| {
+                LightClientError::DeserializationError {
+                    structure: "ValidatorVerifier".to_string(),
+                    source: err.into(),
+                }
+            })?,
+            Some(_) => assets.matching_validator_verifier(&signed_li)?.1,
+        };
+
+        Ok(ExpectedInclusion::new(
+            *validator_verifier.hash().as_ref(),
+            self.state_checkpoint_hash,
+            *signed_li.ledger_info().block_id().as_ref(),
+            self.sparse_merkle_proof_assets.leaf_key,
+            self.sparse_merkle_proof_assets.leaf_hash,
+        ))
+    }
+
+    /// Describes the checks the inclusion program performs on these assets, along with the
+    /// hashes each of them involves, computed out-of-circuit. Nothing is proven, so this
+    /// shows what a proof of the assets would attest to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the description, one line per check, or `Err` if an asset
+    /// could not be deserialized, as described for `expected_inclusion`.
+    pub fn explain(&self) -> Result<String, LightClientError> {
+        let expected = self.expected_inclusion()?;
+        let transaction_hash =
+            TransactionInfo::from_bytes(&self.transaction_proof_assets.transaction)
+                .map_err(|err| LightClientError::DeserializationError {
+                    structure: "TransactionInfo".to_string(),
+                    source: err.into(),
+                })?
+                .hash();
+        let latest_li = self.transaction_proof_assets.ledger_info()?;
+        let accumulator_root = self.transaction_proof_assets.accumulator_root()?;
+        let hex = |hash: &[u8]| format!("0x{}", hex::encode(hash));
+
+        Ok(format!(
+            "The inclusion program checks that:\n\
+             1. Transaction accumulator: the transaction {} at index {} is included in the \
+             transaction accumulator of root {}, at version {}.\n\
+             2. Signatures: the ledger info of block {} is signed by a quorum of the validator \
+             set of hash {}.\n\
+             3. Sparse Merkle: the leaf of key {} and value hash {} is included in the state \
+             tree of root {}, the state checkpoint of the transaction.",
+            hex(transaction_hash.as_ref()),
+            self.transaction_proof_assets.transaction_index,
+            hex(&accumulator_root),
+            latest_li.ledger_info().version(),
+            hex(expected.block_id()),
+            hex(expected.validator_verifier_hash()),
+            hex(expected.key()),
+            hex(expected.value()),
+            hex(expected.state_root()),
+        ))
+    }
+}
+
+/// Computes the hash of a state value as Aptos does for the leaves of its state tree.
+///
+/// # Arguments
+///
+/// * `value` - The bcs serialization of the Aptos `StateValue`, i.e. the raw resource
+///   bytes along with their metadata, if any.
+///
+/// # Returns
+///
+/// The hash to use as `leaf_hash` in `SparseMerkleProofAssets`.
+pub fn state_value_leaf_hash(value: &[u8]) -> [u8; 32] {
+    *state_value_hash(value).as_ref()
 }
 
 
+/// Computes the hash of a published module as Aptos does for the leaves of its state
+/// tree, i.e. the hash of the state value holding its bytecode.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytecode of the module.
+///
+/// # Returns
+///
+/// The hash to use as `leaf_hash` in `SparseMerkleProofAssets`.
+pub fn leaf_hash_for_module(bytes: &[u8]) -> [u8; 32] {
+    *module_value_hash(bytes).as_ref()
+}
+
+/// Computes the key of the leaf holding the `AccountResource` of an account in the state
+/// tree, i.e. the hash of its access path, as Aptos does.
+///
+/// # Arguments
+///
+/// * `address` - The address of the account.
+///
+/// # Returns
+///
+/// The key to use as `leaf_key` in `SparseMerkleProofAssets`.
+pub fn account_state_key(address: AccountAddress) -> [u8; 32] {
+    // Serializing the access path can only fail for malformed struct tags, which the
+    // `AccountResource` one is not.
+    let state_key = StateKey::resource(&address, &AccountResource::struct_tag())
+        .expect("the AccountResource state key should be serializable");
+
+    *AptosCryptoHash::hash(&state_key).as_ref()
+}
+
+/// Sets up the keys of the inclusion program.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program with.
+///
+/// # Returns
+///
+/// A `Result` containing the proving and the verifying keys, or `MissingElf` if the
+/// program was not built before the crate.
 #[inline]
-pub fn generate_keys(client: &ProverClient) -> (SP1ProvingKey, SP1VerifyingKey) {
-    client.setup(INCLUSION_ELF)
+pub fn generate_keys(
+    client: &ProverClient,
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), LightClientError> {
+    let _span = tracing::info_span!("generate_keys", program = "inclusion").entered();
+    setup_program(client, "inclusion", INCLUSION_ELF)
+}
+
+/// Returns the verifying key of the inclusion program, serialized so that proofs can be
+/// verified without a `ProverClient`. Use `io::load_vk` to read it back.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` used to set up the program.
+///
+/// # Returns
+///
+/// A `Result` containing the serialized `SP1VerifyingKey`.
+pub fn inclusion_vk_bytes(client: &ProverClient) -> Result<Vec<u8>, LightClientError> {
+    vk_to_bytes(&generate_keys(client)?.1)
+}
+
+/// Verifies a proof received from a third party, making sure that it was generated for
+/// the inclusion program before reading its outputs.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program and verify with.
+/// * `proof` - The proof to verify.
+/// * `expected_vkey_hash` - The hash of the verifying key of the inclusion program, as
+///   returned by `bytes32`.
+///
+/// # Returns
+///
+/// A `Result` containing the `InclusionOutput` of the proof, or `Err` if the embedded
+/// program does not have the expected verifying key, if the proof was generated for
+/// another program, if it could not be verified, if it did not prove an inclusion, or if
+/// the program skipped the signatures.
+pub fn verify_inclusion_trusted(
+    client: &ProverClient,
+    proof: &SP1ProofWithPublicValues,
+    expected_vkey_hash: [u8; 32],
+) -> Result<InclusionOutput, LightClientError> {
+    let (_, vk) = generate_keys(client)?;
+    let actual = vk_hash(&vk);
+    if actual != expected_vkey_hash {
+        return Err(LightClientError::VerifyingKeyMismatch {
+            expected: format!("0x{}", hex::encode(expected_vkey_hash)),
+            actual: format!("0x{}", hex::encode(actual)),
+        });
+    }
+
+    // Checked before verifying, to tell a proof of another program from an invalid one
+    if let Some(proof_vkey_hash) = proof_vkey_hash(proof)? {
+        if proof_vkey_hash != expected_vkey_hash {
+            return Err(LightClientError::WrongProgram {
+                expected: format!("0x{}", hex::encode(expected_vkey_hash)),
+                actual: format!("0x{}", hex::encode(proof_vkey_hash)),
+            });
+        }
+    }
+
+    verify_timed(client, proof, &vk, "inclusion")?;
+    let output = InclusionOutput::from_public_values(&mut proof.public_values.clone())?;
+    assert_signatures_verified(&output)?;
+
+    Ok(output)
 }
 
-#[allow(dead_code)]
-struct InclusionOutput {
+/// Values committed by the inclusion program.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct InclusionOutput {
+    /// Hash of the validator verifier that signed the ledger info.
+    #[serde(serialize_with = "serialize_hex")]
     validator_verifier_hash: [u8; 32],
+    /// Root hash of the state tree including the account.
+    #[serde(serialize_with = "serialize_hex")]
     state_hash: [u8; 32],
+    /// Identifier of the block of the ledger info.
+    #[serde(serialize_with = "serialize_hex")]
     block_hash: [u8; 32],
+    /// Key of the account leaf.
+    #[serde(serialize_with = "serialize_hex")]
     key: [u8; 32],
+    /// Hash of the account state value.
+    #[serde(serialize_with = "serialize_hex")]
     value: [u8; 32],
+    /// Kind of the proven leaf. Outputs of version `0` always prove resources.
+    leaf_kind: LeafKind,
+    /// Number of leaves of the transaction accumulator of the ledger info, i.e. its version
+    /// plus one. Outputs of version `0` do not carry it.
+    accumulator_leaves: Option<u64>,
+    /// Epoch of the signed ledger info, i.e. of the validator verifier. Outputs of version
+    /// `0` do not carry it.
+    epoch: Option<u64>,
+    /// Index of the candidate validator verifier the signatures verified under, `0` when
+    /// a single one was given. Outputs of version `0` do not carry it.
+    validator_verifier_index: Option<u8>,
+    /// Hash of the transaction whose state checkpoint includes the leaf. Outputs of version
+    /// `0` do not carry it.
+    #[serde(serialize_with = "serialize_hex_option")]
+    transaction_hash: Option<[u8; 32]>,
+    /// Preimage of the key, if one was given to the program. Outputs of version `0` do not
+    /// carry it.
+    key_preimage: Option<StateKeyPreimage>,
+    /// Whether the program skipped the verification of the signatures, as only a build with
+    /// its insecure `skip-signatures` feature does. Outputs of version `0` do not carry it.
+    signatures_skipped: Option<bool>,
+    /// Timestamp of the signed ledger info, in microseconds since the Unix epoch. Outputs of
+    /// version `0` do not carry it.
+    timestamp_usecs: Option<u64>,
 }
 
-pub fn generate_stdin(
-    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
-    transaction_proof_assets: &TransactionProofAssets,
-    validator_verifier_assets: &ValidatorVerifierAssets,
-) -> SP1Stdin {
-    let mut stdin = SP1Stdin::new();
+impl InclusionOutput {
+    /// Reads the `InclusionOutput` from the public values committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values of an execution or a proof of the program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `InclusionOutput`, or `Err` if the public values were
+    /// not committed by the inclusion program or if it did not prove the inclusion.
+    pub fn from_public_values(
+        public_values: &mut SP1PublicValues,
+    ) -> Result<Self, LightClientError> {
+        read_inclusion_output_versioned(public_values, INCLUSION_OUTPUT_VERSION)
+    }
+}
 
-    // Validator verifier: Writes validator verifier data for proof validation.
-    stdin.write_vec(sparse_merkle_proof_assets.sparse_merkle_proof.clone());
-    stdin.write(&sparse_merkle_proof_assets.leaf_key);
-    stdin.write(&sparse_merkle_proof_assets.leaf_hash);
+/// Reads an `InclusionOutput` from public values committed by a given version of the
+/// inclusion program, allowing to verify proofs generated before a layout change.
+///
+/// # Arguments
+///
+/// * `public_values` - The public values of an execution or a proof of the program.
+/// * `version` - The version of the layout of the public values, as described for
+///   `INCLUSION_OUTPUT_VERSION`.
+///
+/// # Returns
+///
+/// A `Result` containing the `InclusionOutput`, or `Err` if the version is unknown or
+/// if the public values do not match its layout.
+pub fn read_inclusion_output_versioned(
+    public_values: &mut SP1PublicValues,
+    version: u8,
+) -> Result<InclusionOutput, LightClientError> {
+    read_inclusion_output(&mut PublicValuesReader::new(public_values), version)
+}
 
-    // Tx inclusion input
-    stdin.write_vec(transaction_proof_assets.transaction.clone());
-    stdin.write(&transaction_proof_assets.transaction_index);
-    stdin.write_vec(transaction_proof_assets.transaction_proof.clone());
-    stdin.write_vec(transaction_proof_assets.latest_li.clone());
+/// Reads an `InclusionOutput` committed by a given version of the inclusion program, leaving
+/// the reader after its last value.
+fn read_inclusion_output(
+    reader: &mut PublicValuesReader,
+    version: u8,
+) -> Result<InclusionOutput, LightClientError> {
+    match version {
+        0 => (),
+        1 => {
+            reader.read_magic(INCLUSION_OUTPUT_MAGIC, "inclusion")?;
 
-    // Validator verifier
-    stdin.write_vec(validator_verifier_assets.validator_verifier.clone());
+            let status = InclusionStatus::from_u8(reader.read_byte()?).map_err(|err| {
+                LightClientError::DeserializationError {
+                    structure: "InclusionStatus".to_string(),
+                    source: err.into(),
+                }
+            })?;
+            if status != InclusionStatus::Included {
+                return Err(LightClientError::InclusionFailure { status });
+            }
+        }
+        _ => return Err(LightClientError::UnsupportedOutputVersion { version }),
+    }
 
-    stdin
+    let mut output = InclusionOutput {
+        validator_verifier_hash: reader.read_hash()?,
+        state_hash: reader.read_hash()?,
+        block_hash: reader.read_hash()?,
+        key: reader.read_hash()?,
+        value: reader.read_hash()?,
+        leaf_kind: LeafKind::Resource,
+        accumulator_leaves: None,
+        epoch: None,
+        validator_verifier_index: None,
+        transaction_hash: None,
+        key_preimage: None,
+        signatures_skipped: None,
+        timestamp_usecs: None,
+    };
+    if version == 0 {
+        return Ok(output);
+    }
+
+    output.leaf_kind = LeafKind::from_u8(reader.read_byte()?).map_err(|err| {
+        LightClientError::DeserializationError {
+            structure: "LeafKind".to_string(),
+            source: err.into(),
+        }
+    })?;
+    output.accumulator_leaves = Some(reader.read_u64()?);
+    output.epoch = Some(reader.read_u64()?);
+    output.validator_verifier_index = Some(reader.read_byte()?);
+    output.transaction_hash = Some(reader.read_hash()?);
+    // Without a preimage, a zero address and an empty path are committed
+    let has_key_preimage = reader.read_byte()? == 1;
+    let key_preimage = StateKeyPreimage::new(reader.read_hash()?, reader.read_bytes()?);
+    output.key_preimage = has_key_preimage.then_some(key_preimage);
+    output.signatures_skipped = Some(reader.read_byte()? == 1);
+    output.timestamp_usecs = Some(reader.read_u64()?);
+
+    Ok(output)
 }
 
-#[allow(dead_code)]
-fn prove_inclusion(
-    client: &ProverClient,
-    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
-    transaction_proof_assets: &TransactionProofAssets,
-    validator_verifier_assets: &ValidatorVerifierAssets,
-) -> Result<(SP1ProofWithPublicValues, InclusionOutput), LightClientError> {
-    sp1_sdk::utils::setup_logger();
+/// Reads the values committed by a proof of the inclusion program, e.g. of a proof loaded
+/// with `load_proof`, without the assets it was generated from.
+///
+/// The committed values are the authoritative record of what a proof attests to: once it
+/// verifies, they are what the program checked, whatever assets later claim to match it.
+///
+/// # Arguments
+///
+/// * `proof` - The proof of the inclusion program.
+///
+/// # Returns
+///
+/// A `Result` containing the `InclusionOutput`, or `Err` if the public values were not
+/// committed by the current inclusion program or if it did not prove the inclusion.
+pub fn expected_from_proof(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<InclusionOutput, LightClientError> {
+    InclusionOutput::from_public_values(&mut proof.public_values.clone())
+}
 
-    let stdin = generate_stdin(
-        sparse_merkle_proof_assets,
-        transaction_proof_assets,
-        validator_verifier_assets,
-    );
-    let (pk, _) = generate_keys(client);
+/// Checks the invariants of the values committed by a proof of the inclusion program,
+/// without the assets it was generated from. The public values must follow the layout of
+/// `INCLUSION_OUTPUT_VERSION` without trailing bytes, the accumulator must hold at least a
+/// transaction, at most two candidate validator verifiers are accepted by the program, and
+/// a committed key preimage must hash to the committed key. The epoch and the validator
+/// verifier can only be checked against a trusted state, e.g. the one ratcheted by the epoch
+/// change program.
+///
+/// # Arguments
+///
+/// * `proof` - The proof of the inclusion program.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the committed values are consistent, and `Err` if they can
+/// not be read, as described for `expected_from_proof`, or a `Mismatch` error naming the
+/// first broken invariant otherwise.
+pub fn assert_self_consistent(proof: &SP1ProofWithPublicValues) -> Result<(), LightClientError> {
+    let mut reader = PublicValuesReader::new(&proof.public_values);
+    let output = read_inclusion_output(&mut reader, INCLUSION_OUTPUT_VERSION)?;
+    if reader.remaining() != 0 {
+        return Err(LightClientError::Mismatch {
+            field: "public values length".to_string(),
+            expected: reader.position().to_string(),
+            actual: (reader.position() + reader.remaining()).to_string(),
+        });
+    }
 
-    let mut proof =
-        client
-            .prove(&pk, stdin)
-            .run()
-            .map_err(|err| LightClientError::ProvingError {
-                program: "prove-merkle-inclusion".to_string(),
+    if output.accumulator_leaves == Some(0) {
+        return Err(LightClientError::Mismatch {
+            field: "accumulator leaves".to_string(),
+            expected: "at least 1".to_string(),
+            actual: "0".to_string(),
+        });
+    }
+    if let Some(index) = output.validator_verifier_index.filter(|index| *index > 1) {
+        return Err(LightClientError::Mismatch {
+            field: "validator verifier index".to_string(),
+            expected: "0 or 1".to_string(),
+            actual: index.to_string(),
+        });
+    }
+    if let Some(key_preimage) = &output.key_preimage {
+        let key = key_preimage.state_key();
+        if key != output.key {
+            return Err(LightClientError::Mismatch {
+                field: "key".to_string(),
+                expected: format!("0x{}", hex::encode(key)),
+                actual: format!("0x{}", hex::encode(output.key)),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Values committed by the inclusion program when proving several transactions under a
+/// single ledger info.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct MultiTransactionOutput {
+    /// Hash of the validator verifier that signed the ledger info.
+    validator_verifier_hash: [u8; 32],
+    /// Identifier of the block of the ledger info.
+    block_hash: [u8; 32],
+    /// Hashes of the proven transactions, in the order they were given.
+    transaction_hashes: Vec<[u8; 32]>,
+}
+
+impl MultiTransactionOutput {
+    /// Reads the `MultiTransactionOutput` from the public values committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values of an execution or a proof of the program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `MultiTransactionOutput`, or `Err` if the public values
+    /// were not committed by the inclusion program proving transactions, or if it did not
+    /// prove their inclusion.
+    pub fn from_public_values(
+        public_values: &mut SP1PublicValues,
+    ) -> Result<Self, LightClientError> {
+        let mut reader = PublicValuesReader::new(public_values);
+        reader.read_magic(TRANSACTIONS_OUTPUT_MAGIC, "inclusion")?;
+
+        let status = InclusionStatus::from_u8(reader.read_byte()?).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "InclusionStatus".to_string(),
                 source: err.into(),
-            })?;
+            }
+        })?;
+        if status != InclusionStatus::Included {
+            return Err(LightClientError::InclusionFailure { status });
+        }
 
-    // Read output.
-    let validator_verifier_hash: [u8; 32] = proof.public_values.read();
-    let state_hash: [u8; 32]  = proof.public_values.read();
-    let block_hash: [u8; 32]  = proof.public_values.read();
-    let key: [u8; 32]  = proof.public_values.read();
-    let value: [u8; 32]  = proof.public_values.read();
-
-    Ok((
-        proof,
-        InclusionOutput {
-            validator_verifier_hash,
-            state_hash,
-            block_hash,
-            key,
-            value,
-        },
-    ))
+        Ok(MultiTransactionOutput {
+            validator_verifier_hash: reader.read_hash()?,
+            block_hash: reader.read_hash()?,
+            transaction_hashes: reader.read_hashes()?,
+        })
+    }
+}
+
+/// Values committed by the inclusion program when proving several leaves against the state
+/// checkpoint of a single transaction.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct BatchInclusionOutput {
+    /// Hash of the validator verifier that signed the ledger info.
+    validator_verifier_hash: [u8; 32],
+    /// Identifier of the block of the ledger info.
+    block_hash: [u8; 32],
+    /// State root hash the leaves were proven against.
+    state_root: [u8; 32],
+    /// Number of proven leaves.
+    leaf_count: u64,
+    /// Root folding the keys and value hashes of the proven leaves, as computed by
+    /// `BatchInclusionProofAssets::accumulator_root`.
+    accumulator_root: [u8; 32],
+}
+
+impl BatchInclusionOutput {
+    /// Reads the `BatchInclusionOutput` from the public values committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values of an execution or a proof of the program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `BatchInclusionOutput`, or `Err` if the public values were
+    /// not committed by the inclusion program proving a batch, or if it did not prove the
+    /// inclusion of the leaves.
+    pub fn from_public_values(
+        public_values: &mut SP1PublicValues,
+    ) -> Result<Self, LightClientError> {
+        let mut reader = PublicValuesReader::new(public_values);
+        reader.read_magic(BATCH_OUTPUT_MAGIC, "inclusion")?;
+
+        let status = InclusionStatus::from_u8(reader.read_byte()?).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "InclusionStatus".to_string(),
+                source: err.into(),
+            }
+        })?;
+        if status != InclusionStatus::Included {
+            return Err(LightClientError::InclusionFailure { status });
+        }
+
+        Ok(BatchInclusionOutput {
+            validator_verifier_hash: reader.read_hash()?,
+            block_hash: reader.read_hash()?,
+            state_root: reader.read_hash()?,
+            leaf_count: reader.read_u64()?,
+            accumulator_root: reader.read_hash()?,
+        })
+    }
+}
+
+/// Values committed by the inclusion program when proving an event emitted by a
+/// transaction.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct EventOutput {
+    /// Hash of the validator verifier that signed the ledger info.
+    validator_verifier_hash: [u8; 32],
+    /// Identifier of the block of the ledger info.
+    block_hash: [u8; 32],
+    /// Hash of the transaction that emitted the event.
+    transaction_hash: [u8; 32],
+    /// Hash of the proven event.
+    event_hash: [u8; 32],
 }
 
+impl EventOutput {
+    /// Reads the `EventOutput` from the public values committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values of an execution or a proof of the program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `EventOutput`, or `Err` if the public values were not
+    /// committed by the inclusion program proving an event, or if it did not prove its
+    /// inclusion.
+    pub fn from_public_values(
+        public_values: &mut SP1PublicValues,
+    ) -> Result<Self, LightClientError> {
+        let mut reader = PublicValuesReader::new(public_values);
+        reader.read_magic(EVENT_OUTPUT_MAGIC, "inclusion")?;
+
+        let status = InclusionStatus::from_u8(reader.read_byte()?).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "InclusionStatus".to_string(),
+                source: err.into(),
+            }
+        })?;
+        if status != InclusionStatus::Included {
+            return Err(LightClientError::InclusionFailure { status });
+        }
+
+        Ok(EventOutput {
+            validator_verifier_hash: reader.read_hash()?,
+            block_hash: reader.read_hash()?,
+            transaction_hash: reader.read_hash()?,
+            event_hash: reader.read_hash()?,
+        })
+    }
+}
+
+/// Values the inclusion program is expected to commit, to be checked against an
+/// `InclusionOutput` with `assert_inclusion_output`.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct ExpectedInclusion {
+    /// Hash of the validator verifier expected to have signed the ledger info.
+    validator_verifier_hash: [u8; 32],
+    /// Root hash of the state tree expected to include the account.
+    state_root: [u8; 32],
+    /// Identifier of the block of the signed ledger info.
+    block_id: [u8; 32],
+    /// Key of the account leaf.
+    key: [u8; 32],
+    /// Hash of the account state value.
+    value: [u8; 32],
+}
+
+impl ExpectedInclusion {
+    pub const fn new(
+        validator_verifier_hash: [u8; 32],
+        state_root: [u8; 32],
+        block_id: [u8; 32],
+        key: [u8; 32],
+        value: [u8; 32],
+    ) -> Self {
+        Self {
+            validator_verifier_hash,
+            state_root,
+            block_id,
+            key,
+            value,
+        }
+    }
+}
+
+/// Checks the values committed by the inclusion program against the expected ones.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` read from the public values of the program.
+/// * `expected` - The values the program is expected to have committed.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if all values match, or a `Mismatch` error naming the first
+/// field that differs. It is also `Err` if the program skipped the signatures, as described
+/// for `assert_signatures_verified`.
+pub fn assert_inclusion_output(
+    output: &InclusionOutput,
+    expected: &ExpectedInclusion,
+) -> Result<(), LightClientError> {
+    assert_signatures_verified(output)?;
+
+    let fields = [
+        (
+            "validator verifier hash",
+            &output.validator_verifier_hash,
+            &expected.validator_verifier_hash,
+        ),
+        ("state root", &output.state_hash, &expected.state_root),
+        ("block id", &output.block_hash, &expected.block_id),
+        ("key", &output.key, &expected.key),
+        ("value", &output.value, &expected.value),
+    ];
+
+    for (field, actual, expected) in fields {
+        if actual != expected {
+            return Err(LightClientError::Mismatch {
+                field: field.to_string(),
+                expected: format!("0x{}", hex::encode(expected)),
+                actual: format!("0x{}", hex::encode(actual)),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the inclusion program verified the signatures of the ledger info. A program
+/// built with its `skip-signatures` feature trusts any ledger info, so its proofs attest to
+/// nothing about the chain and must be rejected.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` read from the public values of the program.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the signatures were verified, or if the output was committed
+/// before the flag was, and a `Mismatch` error otherwise.
+pub fn assert_signatures_verified(output: &InclusionOutput) -> Result<(), LightClientError> {
+    if output.signatures_skipped == Some(true) {
+        return Err(LightClientError::Mismatch {
+            field: "signatures".to_string(),
+            expected: "verified".to_string(),
+            actual: "skipped by a skip-signatures build".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that a proof of the inclusion program is anchored to a recent enough ledger info,
+/// from the timestamp it committed. A ledger info ahead of `now_usecs`, e.g. because of a
+/// clock skew, is fresh.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` read from the public values of the program.
+/// * `max_age` - The oldest accepted age of the ledger info.
+/// * `now_usecs` - The current time, in microseconds since the Unix epoch.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the ledger info is at most `max_age` old, a `StaleProof`
+/// error if it is older, and an `Unsupported` error if the output was committed before the
+/// timestamp was.
+pub fn assert_fresh(
+    output: &InclusionOutput,
+    max_age: Duration,
+    now_usecs: u64,
+) -> Result<(), LightClientError> {
+    let timestamp_usecs = output
+        .timestamp_usecs
+        .ok_or_else(|| LightClientError::Unsupported {
+            capability: "freshness check".to_string(),
+            note: "the output was committed before the timestamp of the ledger info".to_string(),
+        })?;
+
+    let age_usecs = now_usecs.saturating_sub(timestamp_usecs);
+    if u128::from(age_usecs) > max_age.as_micros() {
+        return Err(LightClientError::StaleProof { age_usecs });
+    }
+
+    Ok(())
+}
+
+/// Checks that the transaction accumulator committed by the inclusion program is large
+/// enough to hold the proven transaction, as a sanity check of its size.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` read from the public values of the program.
+/// * `transaction_index` - The index of the proven transaction in the accumulator.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the accumulator has more than `transaction_index` leaves,
+/// or if the output was committed before the leaves were, and a `Mismatch` error
+/// otherwise.
+pub fn assert_accumulator_leaves(
+    output: &InclusionOutput,
+    transaction_index: u64,
+) -> Result<(), LightClientError> {
+    match output.accumulator_leaves {
+        Some(accumulator_leaves) if accumulator_leaves <= transaction_index => {
+            Err(LightClientError::Mismatch {
+                field: "accumulator leaves".to_string(),
+                expected: format!("more than {transaction_index}"),
+                actual: accumulator_leaves.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the transaction committed by the inclusion program is the one of the
+/// assets, so that the proof is tied to that transaction.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` read from the public values of the program.
+/// * `transaction` - The serialized `TransactionInfo` given to the program.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the committed hash is the one of `transaction`, or if the
+/// output was committed before the hash was, and `Err` if the transaction can not be
+/// deserialized or if the hashes differ.
+pub fn assert_transaction_hash(
+    output: &InclusionOutput,
+    transaction: &[u8],
+) -> Result<(), LightClientError> {
+    let Some(transaction_hash) = output.transaction_hash else {
+        return Ok(());
+    };
+    let expected = TransactionInfo::from_bytes(transaction)
+        .map_err(|err| LightClientError::DeserializationError {
+            structure: "TransactionInfo".to_string(),
+            source: err.into(),
+        })?
+        .hash();
+    if transaction_hash != *expected.as_ref() {
+        return Err(LightClientError::Mismatch {
+            field: "transaction hash".to_string(),
+            expected: format!("0x{}", hex::encode(expected.as_ref())),
+            actual: format!("0x{}", hex::encode(transaction_hash)),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the block committed by the inclusion program is one of the approved
+/// checkpoints, so that the proof anchors to a known block.
+///
+/// # Arguments
+///
+/// * `output` - The `InclusionOutput` read from the public values of the program.
+/// * `checkpoints` - The identifiers of the approved blocks.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the committed block is a checkpoint, or an `UnknownBlock`
+/// error otherwise.
+pub fn assert_block_in_checkpoints(
+    output: &InclusionOutput,
+    checkpoints: &HashSet<[u8; 32]>,
+) -> Result<(), LightClientError> {
+    if !checkpoints.contains(&output.block_hash) {
+        return Err(LightClientError::UnknownBlock {
+            block: format!("0x{}", hex::encode(output.block_hash)),
+        });
+    }
+
+    Ok(())
+}
+
+/// Writes the assets of the inclusion program to an `SP1Stdin`, in the order the program
+/// reads them.
+///
+/// Empty assets can not be deserialized by the program, so they are rejected here rather
+/// than deep in its execution. So are assets whose total size exceeds the limit set by
+/// `MAX_STDIN_BYTES_ENV`, `DEFAULT_MAX_STDIN_BYTES` by default, before reaching the prover.
+///
+/// # Arguments
+///
+/// * `sparse_merkle_proof_assets` - The assets for the account inclusion in the state tree.
+/// * `transaction_proof_assets` - The assets for the transaction inclusion in the accumulator.
+/// * `validator_verifier_assets` - The assets for the validator verifier signing the ledger info.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1Stdin`, or `Err` naming the first empty asset, or if the
+/// assets are too large.
+pub fn generate_stdin(
+    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<SP1Stdin, LightClientError> {
+    generate_stdin_with_limit(
+        sparse_merkle_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+        max_stdin_bytes()?,
+    )
+}
+
+fn generate_stdin_with_limit(
+    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+    max_bytes: usize,
+) -> Result<SP1Stdin, LightClientError> {
+    let _span =
+        tracing::info_span!("generate_stdin", program = "inclusion", nbr_leaves = 1).entered();
+
+    check_not_empty(
+        "sparse_merkle_proof",
+        &sparse_merkle_proof_assets.sparse_merkle_proof,
+    )?;
+    check_not_empty("transaction", &transaction_proof_assets.transaction)?;
+    check_not_empty(
+        "transaction_proof",
+        &transaction_proof_assets.transaction_proof,
+    )?;
+    check_not_empty("latest_li", &transaction_proof_assets.latest_li)?;
+    if let Some(signed_ledger_info) = &transaction_proof_assets.signed_ledger_info {
+        check_not_empty("signed_ledger_info", signed_ledger_info)?;
+    }
+    check_not_empty(
+        "validator_verifier",
+        &validator_verifier_assets.validator_verifier,
+    )?;
+    if let Some(alternative_validator_verifier) =
+        &validator_verifier_assets.alternative_validator_verifier
+    {
+        check_not_empty(
+            "alternative_validator_verifier",
+            alternative_validator_verifier,
+        )?;
+    }
+    check_stdin_size(
+        &[
+            (
+                "sparse_merkle_proof",
+                sparse_merkle_proof_assets.sparse_merkle_proof.len(),
+            ),
+            (
+                "module_bytecode",
+                sparse_merkle_proof_assets
+                    .module_bytecode
+                    .as_ref()
+                    .map_or(0, Vec::len),
+            ),
+            ("transaction", transaction_proof_assets.transaction.len()),
+            (
+                "transaction_proof",
+                transaction_proof_assets.transaction_proof.len(),
+            ),
+            ("latest_li", transaction_proof_assets.latest_li.len()),
+            (
+                "signed_ledger_info",
+                transaction_proof_assets
+                    .signed_ledger_info
+                    .as_ref()
+                    .map_or(0, Vec::len),
+            ),
+            (
+                "validator_verifier",
+                validator_verifier_assets.validator_verifier.len(),
+            ),
+            (
+                "alternative_validator_verifier",
+                validator_verifier_assets
+                    .alternative_validator_verifier
+                    .as_ref()
+                    .map_or(0, Vec::len),
+            ),
+        ],
+        max_bytes,
+    )?;
+
+    let mut stdin = SP1Stdin::new();
+
+    // Proofs, given separately
+    stdin.write(&ProofLayout::Split.to_u8());
+    stdin.write_vec(sparse_merkle_proof_assets.sparse_merkle_proof.clone());
+    stdin.write_vec(transaction_proof_assets.transaction.clone());
+    stdin.write_vec(transaction_proof_assets.transaction_proof.clone());
+
+    // Validator verifier: Writes validator verifier data for proof validation.
+    stdin.write(&sparse_merkle_proof_assets.leaf_key);
+    stdin.write(&sparse_merkle_proof_assets.leaf_hash);
+    stdin.write(&sparse_merkle_proof_assets.leaf_kind().to_u8());
+    if let Some(module_bytecode) = &sparse_merkle_proof_assets.module_bytecode {
+        stdin.write_vec(module_bytecode.clone());
+        stdin.write(&sparse_merkle_proof_assets.module_metadata);
+    }
+    // Read by the program as an optional tuple of the address and the path
+    stdin.write(&sparse_merkle_proof_assets.key_preimage);
+
+    // Tx inclusion input
+    stdin.write(&transaction_proof_assets.transaction_index);
+    stdin.write_vec(transaction_proof_assets.latest_li.clone());
+    stdin.write(&transaction_proof_assets.signed_ledger_info);
+
+    // Validator verifier
+    stdin.write_vec(validator_verifier_assets.validator_verifier.clone());
+    stdin.write(&validator_verifier_assets.alternative_validator_verifier);
+
+    Ok(stdin)
+}
+
+/// Writes the assets of the inclusion program to an `SP1Stdin`, with the proofs bundled in
+/// a single `AccountStateProof`. The program then commits the same values as for
+/// `generate_stdin`.
+///
+/// # Arguments
+///
+/// * `account_state_proof_assets` - The assets for the account inclusion in the state tree
+///   and of its transaction in the accumulator.
+/// * `validator_verifier_assets` - The assets for the validator verifier signing the ledger info.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1Stdin`, or `Err` naming the first empty asset.
+pub fn generate_account_state_proof_stdin(
+    account_state_proof_assets: &AccountStateProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<SP1Stdin, LightClientError> {
+    let _span =
+        tracing::info_span!("generate_stdin", program = "inclusion", nbr_leaves = 1).entered();
+
+    check_not_empty(
+        "account_state_proof",
+        &account_state_proof_assets.account_state_proof,
+    )?;
+    check_not_empty("latest_li", &account_state_proof_assets.latest_li)?;
+    check_not_empty(
+        "validator_verifier",
+        &validator_verifier_assets.validator_verifier,
+    )?;
+
+    let mut stdin = SP1Stdin::new();
+
+    stdin.write(&ProofLayout::Combined.to_u8());
+    stdin.write_vec(account_state_proof_assets.account_state_proof.clone());
+
+    stdin.write(&account_state_proof_assets.leaf_key);
+    stdin.write(&account_state_proof_assets.leaf_hash);
+    stdin.write(&LeafKind::Resource.to_u8());
+    stdin.write(&None::<StateKeyPreimage>);
+
+    stdin.write(&account_state_proof_assets.transaction_index);
+    stdin.write_vec(account_state_proof_assets.latest_li.clone());
+    // The signatures are carried by the ledger info the transaction is proven against
+    stdin.write(&None::<Vec<u8>>);
+
+    stdin.write_vec(validator_verifier_assets.validator_verifier.clone());
+    stdin.write(&validator_verifier_assets.alternative_validator_verifier);
+
+    Ok(stdin)
+}
+
+/// Writes the assets of the inclusion program to an `SP1Stdin`, to prove several
+/// transactions under a single ledger info. The program then commits a
+/// `MultiTransactionOutput`.
+///
+/// # Arguments
+///
+/// * `multi_transaction_proof_assets` - The assets for the transactions inclusion in the
+///   accumulator.
+/// * `validator_verifier_assets` - The assets for the validator verifier signing the ledger info.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1Stdin`, or `Err` naming the first empty asset, or if two
+/// candidate validator verifiers are given.
+pub fn generate_multi_transaction_stdin(
+    multi_transaction_proof_assets: &MultiTransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<SP1Stdin, LightClientError> {
+    let _span = tracing::info_span!(
+        "generate_stdin",
+        program = "inclusion",
+        nbr_leaves = multi_transaction_proof_assets.transactions.len()
+    )
+    .entered();
+
+    if multi_transaction_proof_assets.transactions.is_empty() {
+        return Err(LightClientError::InvalidInput {
+            input: "transactions".to_string(),
+            reason: "must not be empty".to_string(),
+        });
+    }
+    for (transaction, _, transaction_proof) in &multi_transaction_proof_assets.transactions {
+        check_not_empty("transaction", transaction)?;
+        check_not_empty("transaction_proof", transaction_proof)?;
+    }
+    check_not_empty("latest_li", &multi_transaction_proof_assets.latest_li)?;
+    check_not_empty(
+        "validator_verifier",
+        &validator_verifier_assets.validator_verifier,
+    )?;
+    // Only the signatures of an account proof can be verified under either of two sets
+    if validator_verifier_assets
+        .alternative_validator_verifier
+        .is_some()
+    {
+        return Err(LightClientError::InvalidInput {
+            input: "validator_verifier_assets".to_string(),
+            reason: "transactions are only proven under a single validator verifier".to_string(),
+        });
+    }
+
+    let mut stdin = SP1Stdin::new();
+
+    stdin.write(&ProofLayout::Transactions.to_u8());
+    stdin.write(&multi_transaction_proof_assets.transactions);
+    stdin.write_vec(multi_transaction_proof_assets.latest_li.clone());
+
+    stdin.write_vec(validator_verifier_assets.validator_verifier.clone());
+
+    Ok(stdin)
+}
+
+/// Writes the assets of the inclusion program to an `SP1Stdin`, to prove several leaves
+/// against the state checkpoint of a single transaction. The program then commits a
+/// `BatchInclusionOutput`.
+///
+/// # Arguments
+///
+/// * `batch_inclusion_proof_assets` - The assets for the leaves inclusion in the state, and
+///   for the transaction inclusion in the accumulator.
+/// * `validator_verifier_assets` - The assets for the validator verifier signing the ledger info.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1Stdin`, or `Err` naming the first empty asset, or if two
+/// candidate validator verifiers are given.
+pub fn generate_batch_stdin(
+    batch_inclusion_proof_assets: &BatchInclusionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<SP1Stdin, LightClientError> {
+    let _span = tracing::info_span!(
+        "generate_stdin",
+        program = "inclusion",
+        nbr_leaves = batch_inclusion_proof_assets.leaves.len()
+    )
+    .entered();
+
+    let transaction_proof_assets = &batch_inclusion_proof_assets.transaction_proof_assets;
+    for (_, _, sparse_merkle_proof) in &batch_inclusion_proof_assets.leaves {
+        check_not_empty("sparse_merkle_proof", sparse_merkle_proof)?;
+    }
+    check_not_empty("transaction", &transaction_proof_assets.transaction)?;
+    check_not_empty(
+        "transaction_proof",
+        &transaction_proof_assets.transaction_proof,
+    )?;
+    check_not_empty("latest_li", &transaction_proof_assets.latest_li)?;
+    check_not_empty(
+        "validator_verifier",
+        &validator_verifier_assets.validator_verifier,
+    )?;
+    // Only the signatures of an account proof can be verified under either of two sets
+    if validator_verifier_assets
+        .alternative_validator_verifier
+        .is_some()
+    {
+        return Err(LightClientError::InvalidInput {
+            input: "validator_verifier_assets".to_string(),
+            reason: "a batch is only proven under a single validator verifier".to_string(),
+        });
+    }
+
+    let mut stdin = SP1Stdin::new();
+
+    stdin.write(&ProofLayout::Batch.to_u8());
+    stdin.write(&batch_inclusion_proof_assets.leaves);
+    stdin.write_vec(transaction_proof_assets.transaction.clone());
+    stdin.write(&transaction_proof_assets.transaction_index);
+    stdin.write_vec(transaction_proof_assets.transaction_proof.clone());
+    stdin.write_vec(transaction_proof_assets.latest_li.clone());
+    stdin.write_vec(validator_verifier_assets.validator_verifier.clone());
+
+    Ok(stdin)
+}
+
+/// Writes the assets of the inclusion program to an `SP1Stdin`, to prove an event emitted
+/// by a transaction. The program then commits an `EventOutput`.
+///
+/// # Arguments
+///
+/// * `event_proof_assets` - The assets for the event inclusion in the event accumulator of
+///   the transaction.
+/// * `transaction_proof_assets` - The assets for the transaction inclusion in the
+///   accumulator.
+/// * `validator_verifier_assets` - The assets for the validator verifier signing the ledger info.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1Stdin`, or `Err` naming the first empty asset, if the
+/// signatures are carried by a separate ledger info, or if two candidate validator
+/// verifiers are given.
+pub fn generate_event_stdin(
+    event_proof_assets: &EventProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<SP1Stdin, LightClientError> {
+    let _span =
+        tracing::info_span!("generate_stdin", program = "inclusion", nbr_leaves = 1).entered();
+
+    check_not_empty("event", &event_proof_assets.event)?;
+    check_not_empty("event_proof", &event_proof_assets.event_proof)?;
+    check_not_empty("transaction", &transaction_proof_assets.transaction)?;
+    check_not_empty(
+        "transaction_proof",
+        &transaction_proof_assets.transaction_proof,
+    )?;
+    check_not_empty("latest_li", &transaction_proof_assets.latest_li)?;
+    check_not_empty(
+        "validator_verifier",
+        &validator_verifier_assets.validator_verifier,
+    )?;
+    if transaction_proof_assets.signed_ledger_info.is_some() {
+        return Err(LightClientError::InvalidInput {
+            input: "transaction_proof_assets".to_string(),
+            reason: "an event is verified against latest_li".to_string(),
+        });
+    }
+    if validator_verifier_assets
+        .alternative_validator_verifier
+        .is_some()
+    {
+        return Err(LightClientError::InvalidInput {
+            input: "validator_verifier_assets".to_string(),
+            reason: "events are only proven under a single validator verifier".to_string(),
+        });
+    }
+
+    let mut stdin = SP1Stdin::new();
+
+    stdin.write(&ProofLayout::Event.to_u8());
+    stdin.write_vec(event_proof_assets.event.clone());
+    stdin.write(&event_proof_assets.event_index);
+    stdin.write_vec(event_proof_assets.event_proof.clone());
+    stdin.write_vec(transaction_proof_assets.transaction.clone());
+    stdin.write(&transaction_proof_assets.transaction_index);
+    stdin.write_vec(transaction_proof_assets.transaction_proof.clone());
+    stdin.write_vec(transaction_proof_assets.latest_li.clone());
+
+    stdin.write_vec(validator_verifier_assets.validator_verifier.clone());
+
+    Ok(stdin)
+}
+
+/// Returns the maximum number of bytes of the assets written to the stdin of the program.
+fn max_stdin_bytes() -> Result<usize, LightClientError> {
+    match std::env::var(MAX_STDIN_BYTES_ENV) {
+        Ok(value) => value.parse().map_err(|_| LightClientError::InvalidInput {
+            input: MAX_STDIN_BYTES_ENV.to_string(),
+            reason: format!("'{value}' is not a number of bytes"),
+        }),
+        Err(_) => Ok(DEFAULT_MAX_STDIN_BYTES),
+    }
+}
+
+fn check_stdin_size(fields: &[(&str, usize)], max_bytes: usize) -> Result<(), LightClientError> {
+    for (field, len) in fields {
+        tracing::debug!(field, bytes = len, "stdin field size");
+    }
+
+    let bytes = fields.iter().map(|(_, len)| len).sum();
+    if bytes > max_bytes {
+        return Err(LightClientError::InputTooLarge { bytes });
+    }
+
+    Ok(())
+}
+
+fn check_not_empty(field: &str, bytes: &[u8]) -> Result<(), LightClientError> {
+    if bytes.is_empty() {
+        return Err(LightClientError::InvalidInput {
+            input: field.to_string(),
+            reason: "must not be empty".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Performs, on the host, the same deserialization and verification steps as the inclusion
+/// program. This allows to catch inconsistent assets before spending time on proving.
+///
+/// # Arguments
+///
+/// * `proving_assets` - The assets of the inclusion program.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the assets would be accepted by the program, and `Err`
+/// describing the first failing step otherwise.
+pub fn validate_inclusion_assets(proving_assets: &ProvingAssets) -> Result<(), LightClientError> {
+    let ProvingAssets {
+        sparse_merkle_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+        ..
+    } = proving_assets;
+
+    let latest_li = transaction_proof_assets.ledger_info()?;
+    let signed_li = match &transaction_proof_assets.signed_ledger_info {
+        Some(bytes) => {
+            let signed_li = LedgerInfoWithSignatures::from_bytes(bytes).map_err(|err| {
+                LightClientError::DeserializationError {
+                    structure: "LedgerInfoWithSignatures".to_string(),
+                    source: err.into(),
+                }
+            })?;
+            if signed_li.ledger_info().transaction_accumulator_hash()
+                != latest_li.ledger_info().transaction_accumulator_hash()
+            {
+                return Err(LightClientError::VerificationError {
+                    check: "SignedLedgerInfo".to_string(),
+                    source: "signed ledger info does not share the transaction accumulator root"
+                        .into(),
+                });
+            }
+            if signed_li.ledger_info().version() != latest_li.ledger_info().version()
+                || signed_li.ledger_info().epoch() != latest_li.ledger_info().epoch()
+            {
+                return Err(LightClientError::VerificationError {
+                    check: "SignedLedgerInfo".to_string(),
+                    source: "signed ledger info is not at the version and epoch of the ledger info"
+                        .into(),
+                });
+            }
+            signed_li
+        }
+        None => latest_li.clone(),
+    };
+
+    // Verify the signatures first, as the program does
+    validator_verifier_assets.matching_validator_verifier(&signed_li)?;
+    let signed_li_version = signed_li.ledger_info().version();
+    if transaction_proof_assets.transaction_index > signed_li_version {
+        return Err(LightClientError::VerificationError {
+            check: "TransactionVersion".to_string(),
+            source: format!(
+                "transaction at version {} is newer than the ledger info at version {signed_li_version}",
+                transaction_proof_assets.transaction_index
+            )
+            .into(),
+        });
+    }
+
+    // Verify transaction inclusion in the LedgerInfoWithSignatures
+    let transaction = TransactionInfo::from_bytes(&transaction_proof_assets.transaction)
+        .map_err(|err| LightClientError::DeserializationError {
+            structure: "TransactionInfo".to_string(),
+            source: err.into(),
+        })?;
+    let transaction_proof =
+        TransactionAccumulatorProof::from_bytes(&transaction_proof_assets.transaction_proof)
+            .map_err(|err| LightClientError::DeserializationError {
+                structure: "TransactionAccumulatorProof".to_string(),
+                source: err.into(),
+            })?;
+
+    transaction_proof
+        .verify(
+            latest_li.ledger_info().transaction_accumulator_hash(),
+            transaction.hash(),
+            transaction_proof_assets.transaction_index,
+        )
+        .map_err(|err| LightClientError::VerificationError {
+            check: "TransactionAccumulatorProof".to_string(),
+            source: err.into(),
+        })?;
+
+    if let Some(key_preimage) = &sparse_merkle_proof_assets.key_preimage {
+        if key_preimage.state_key() != sparse_merkle_proof_assets.leaf_key {
+            return Err(LightClientError::VerificationError {
+                check: "StateKeyPreimage".to_string(),
+                source: "the address and the path do not hash to the leaf key".into(),
+            });
+        }
+    }
+    // The program ties the kind of the leaf to the path of the preimage of its key
+    let leaf_kind = sparse_merkle_proof_assets.leaf_kind();
+    match &sparse_merkle_proof_assets.key_preimage {
+        None if leaf_kind == LeafKind::Module => {
+            return Err(LightClientError::VerificationError {
+                check: "LeafKind".to_string(),
+                source: "a module leaf requires the preimage of its key".into(),
+            });
+        }
+        Some(key_preimage) if access_path_leaf_kind(&key_preimage.path) != Some(leaf_kind) => {
+            return Err(LightClientError::VerificationError {
+                check: "LeafKind".to_string(),
+                source: format!("the path of the key does not hold a {leaf_kind:?} leaf").into(),
+            });
+        }
+        _ => (),
+    }
+
+    // Verify account inclusion in the state checkpoint
+    let sparse_merkle_proof =
+        SparseMerkleProof::from_bytes(&sparse_merkle_proof_assets.sparse_merkle_proof).map_err(
+            |err| LightClientError::DeserializationError {
+                structure: "SparseMerkleProof".to_string(),
+                source: err.into(),
+            },
+        )?;
+    let state_checkpoint =
+        transaction
+            .state_checkpoint()
+            .ok_or_else(|| LightClientError::VerificationError {
+                check: "TransactionInfo".to_string(),
+                source: "transaction does not carry a state checkpoint".into(),
+            })?;
+    verify_leaf_inclusion(
+        &sparse_merkle_proof,
+        state_checkpoint,
+        sparse_merkle_proof_assets,
+    )?;
+
+    Ok(())
+}
+
+/// Verifies the leaf of the assets against the state checkpoint it is proven in.
+///
+/// # Arguments
+///
+/// * `sparse_merkle_proof` - The deserialized proof of the assets.
+/// * `state_checkpoint` - The state checkpoint of the transaction.
+/// * `sparse_merkle_proof_assets` - The assets for the account inclusion in the state tree.
+///
+/// # Returns
+///
+/// A `Result` containing the reconstructed root, or `Err` if the proof does not carry a leaf,
+/// as it then proves the key is absent, or if it does not verify.
+fn verify_leaf_inclusion(
+    sparse_merkle_proof: &SparseMerkleProof,
+    state_checkpoint: HashValue,
+    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+) -> Result<HashValue, LightClientError> {
+    if sparse_merkle_proof.leaf().is_none() {
+        return Err(LightClientError::VerificationError {
+            check: "SparseMerkleProof".to_string(),
+            source: "the proof does not carry a leaf, so it does not prove an inclusion".into(),
+        });
+    }
+
+    sparse_merkle_proof
+        .verify_by_hash(
+            state_checkpoint,
+            HashValue::new(sparse_merkle_proof_assets.leaf_key),
+            HashValue::new(sparse_merkle_proof_assets.leaf_hash),
+        )
+        .map_err(|err| LightClientError::VerificationError {
+            check: "SparseMerkleProof".to_string(),
+            source: err.into(),
+        })
+}
+
+/// Reconstructs the hashes of the path of a leaf up to the root of the state tree. The
+/// program only checks the root, so this shows at which level a failing proof diverges.
+///
+/// # Arguments
+///
+/// * `proof_bytes` - The proof of the leaf, serialized with bcs.
+/// * `key` - The key of the leaf.
+/// * `leaf_hash` - The hash of the value of the leaf.
+///
+/// # Returns
+///
+/// A `Result` containing the hash of the leaf node followed by the hash of each level,
+/// the last one being the reconstructed root, or `Err` if the proof could not be
+/// deserialized or has more siblings than the bits of a key.
+pub fn trace_sparse_merkle(
+    proof_bytes: &[u8],
+    key: [u8; 32],
+    leaf_hash: [u8; 32],
+) -> Result<Vec<[u8; 32]>, LightClientError> {
+    let sparse_merkle_proof = SparseMerkleProof::from_bytes(proof_bytes).map_err(|err| {
+        LightClientError::DeserializationError {
+            structure: "SparseMerkleProof".to_string(),
+            source: err.into(),
+        }
+    })?;
+    let trace = sparse_merkle_proof
+        .trace_by_hash(HashValue::new(key), HashValue::new(leaf_hash))
+        .map_err(|err| LightClientError::VerificationError {
+            check: "SparseMerkleProof".to_string(),
+            source: err.into(),
+        })?;
+
+    Ok(trace.iter().map(|hash| *hash.as_ref()).collect())
+}
+
+/// Computes out-of-circuit the state root expected to be committed by the inclusion program,
+/// i.e. the state checkpoint of the transaction that the account inclusion is verified against.
+///
+/// # Arguments
+///
+/// * `sparse_merkle_proof_assets` - The assets for the account inclusion in the state tree.
+/// * `transaction_proof_assets` - The assets for the transaction carrying the state checkpoint.
+///
+/// # Returns
+///
+/// A `Result` containing the expected state root, or `Err` if the transaction does not
+/// carry a state checkpoint or if the account is not included in it.
+pub fn expected_state_root(
+    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+) -> Result<[u8; 32], LightClientError> {
+    let transaction = TransactionInfo::from_bytes(&transaction_proof_assets.transaction)
+        .map_err(|err| LightClientError::DeserializationError {
+            structure: "TransactionInfo".to_string(),
+            source: err.into(),
+        })?;
+    let state_checkpoint =
+        transaction
+            .state_checkpoint()
+            .ok_or_else(|| LightClientError::VerificationError {
+                check: "TransactionInfo".to_string(),
+                source: "transaction does not carry a state checkpoint".into(),
+            })?;
+
+    let sparse_merkle_proof =
+        SparseMerkleProof::from_bytes(&sparse_merkle_proof_assets.sparse_merkle_proof).map_err(
+            |err| LightClientError::DeserializationError {
+                structure: "SparseMerkleProof".to_string(),
+                source: err.into(),
+            },
+        )?;
+    let reconstructed_root_hash = verify_leaf_inclusion(
+        &sparse_merkle_proof,
+        state_checkpoint,
+        sparse_merkle_proof_assets,
+    )?;
+
+    Ok(*reconstructed_root_hash.as_ref())
+}
+
+/// `ExecutionReport` of an execution of the inclusion program, along with the number of
+/// bytes of public values it committed. A program stopping on a failure status commits
+/// fewer bytes than one proving the inclusion, and an outdated program fewer than the
+/// current layout.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct InclusionExecutionReport {
+    /// The report of the SP1 executor.
+    report: ExecutionReport,
+    /// Number of bytes of public values committed by the program.
+    public_values_len: usize,
+}
+
+impl InclusionExecutionReport {
+    pub fn new(report: ExecutionReport, public_values: &SP1PublicValues) -> Self {
+        Self {
+            report,
+            public_values_len: public_values.as_slice().len(),
+        }
+    }
+}
+
+/// Executes the inclusion program without proving, and reads the values it committed. This
+/// is much cheaper than proving, to check that the assets are accepted by the program.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to execute with.
+/// * `proving_assets` - The assets of the inclusion program.
+///
+/// # Returns
+///
+/// A `Result` containing the `InclusionExecutionReport` and the `InclusionOutput`, or
+/// `Err` if the execution failed or if the program did not prove the inclusion.
+pub fn execute_and_read_inclusion(
+    client: &ProverClient,
+    proving_assets: &ProvingAssets,
+) -> Result<(InclusionExecutionReport, InclusionOutput), LightClientError> {
+    let stdin = proving_assets.stdin()?;
+    let (mut public_values, report) = tracing::info_span!("execute", program = "inclusion")
+        .in_scope(|| client.execute(INCLUSION_ELF, stdin).run())
+        .map_err(|err| LightClientError::ProvingError {
+            program: "inclusion".to_string(),
+            source: err.into(),
+        })?;
+    record_cycles("inclusion", report.total_instruction_count());
+    let report = InclusionExecutionReport::new(report, &public_values);
+
+    Ok((report, InclusionOutput::from_public_values(&mut public_values)?))
+}
+
+/// Runs the inclusion program on a raw stdin in the SP1 executor, without proving. This
+/// takes milliseconds, so unit tests of the program can check what it commits, or that it
+/// rejects an input, without paying for a proof.
+///
+/// # Arguments
+///
+/// * `stdin` - The stdin of the inclusion program, e.g. as written by `ProvingAssets::stdin`.
+///
+/// # Returns
+///
+/// A `Result` containing the public values committed by the program, or `GuestPanic` if
+/// the program did not run to completion.
+pub fn run_inclusion_guest(stdin: SP1Stdin) -> Result<SP1PublicValues, LightClientError> {
+    // Executing does not involve any of the proving backends, so the mock one is enough
+    let client = ProverClient::mock();
+    let (public_values, _) = tracing::info_span!("execute", program = "inclusion")
+        .in_scope(|| client.execute(INCLUSION_ELF, stdin).run())
+        // The executor only reports the exit code of a panicking program, its message is
+        // printed on the standard output
+        .map_err(|err| LightClientError::GuestPanic {
+            message: err.to_string(),
+        })?;
+
+    Ok(public_values)
+}
+
+/// Outcome of an execution of the inclusion program with `try_execute_inclusion`.
+#[derive(Debug)]
+pub enum ExecutionOutcome {
+    /// The program proved the inclusion.
+    Ok(InclusionExecutionReport),
+    /// The program ran to completion, but committed a status other than `Included`.
+    GuestFailure(InclusionStatus),
+    /// The assets could not be written to the stdin of the program, the program panicked,
+    /// or its public values could not be read. A panic of the executor is reported as a
+    /// `GuestPanic` too.
+    HostError(LightClientError),
+}
+
+/// Executes the inclusion program without proving, like `execute_and_read_inclusion`, but
+/// never fails nor panics: whatever happens is reported by the returned outcome. This suits
+/// callers such as services, which should not be brought down by a single bad request.
+///
+/// # Arguments
+///
+/// * `proving_assets` - The assets of the inclusion program.
+///
+/// # Returns
+///
+/// The `ExecutionOutcome` of the execution.
+pub fn try_execute_inclusion(proving_assets: &ProvingAssets) -> ExecutionOutcome {
+    let execute = || -> Result<InclusionExecutionReport, LightClientError> {
+        // Executing does not involve any of the proving backends, so the mock one is enough
+        let client = ProverClient::mock();
+        let (mut public_values, report) = tracing::info_span!("execute", program = "inclusion")
+            .in_scope(|| client.execute(INCLUSION_ELF, proving_assets.stdin()?).run())
+            .map_err(|err| LightClientError::GuestPanic {
+                message: err.to_string(),
+            })?;
+        let report = InclusionExecutionReport::new(report, &public_values);
+        InclusionOutput::from_public_values(&mut public_values)?;
+
+        Ok(report)
+    };
+
+    // Nothing is shared with the caller but the assets, which are only read
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(execute)) {
+        Ok(Ok(report)) => ExecutionOutcome::Ok(report),
+        Ok(Err(LightClientError::InclusionFailure { status })) => {
+            ExecutionOutcome::GuestFailure(status)
+        }
+        Ok(Err(err)) => ExecutionOutcome::HostError(err),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            ExecutionOutcome::HostError(LightClientError::GuestPanic { message })
+        }
+    }
+}
+
+/// Proves the inclusion of an account, giving up as soon as `cancel` is set, e.g. when the
+/// request that triggered the proof was cancelled.
+///
+/// The flag is checked before the setup of the program, and between the setup and the
+/// proving. The SP1 prover can not be interrupted, so a proof that is already in progress
+/// when the flag is set still has to drain before `Cancelled` is returned.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to prove with.
+/// * `sparse_merkle_proof_assets` - The assets for the account inclusion in the state tree.
+/// * `transaction_proof_assets` - The assets for the transaction inclusion in the accumulator.
+/// * `validator_verifier_assets` - The assets for the validator verifier signing the ledger info.
+/// * `cancel` - The flag to set to cancel the proving.
+///
+/// # Returns
+///
+/// A `Result` containing the proof and the `InclusionOutput`, or `Err` if the proving
+/// was cancelled or failed.
+pub fn prove_inclusion_with_cancel(
+    client: &ProverClient,
+    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+    cancel: &AtomicBool,
+) -> Result<(SP1ProofWithPublicValues, InclusionOutput), LightClientError> {
+    // A single leaf is proven per account
+    let _span = tracing::info_span!(
+        "prove_inclusion",
+        program = "inclusion",
+        nbr_leaves = 1,
+        transaction_index = transaction_proof_assets.transaction_index
+    )
+    .entered();
+    let check_cancel = || {
+        if cancel.load(Ordering::Relaxed) {
+            Err(LightClientError::Cancelled {
+                program: "prove-merkle-inclusion".to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    check_cancel()?;
+    let stdin = generate_stdin(
+        sparse_merkle_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+    )?;
+    let (pk, _) = generate_keys(client)?;
+
+    check_cancel()?;
+    let start_proving = Instant::now();
+    let mut proof = tracing::info_span!("prove", program = "inclusion", nbr_leaves = 1)
+        .in_scope(|| client.prove(&pk, stdin).run())
+        .map_err(|err| LightClientError::ProvingError {
+            program: "prove-merkle-inclusion".to_string(),
+            source: err.into(),
+        })?;
+    record_proof("inclusion", start_proving.elapsed());
+    check_cancel()?;
+
+    // Read output.
+    let output = InclusionOutput::from_public_values(&mut proof.public_values)?;
+
+    Ok((proof, output))
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use super::*;
+    use crate::fixture::{
+        failing_check, generate_fixture, generate_proving_assets, AptosWrapperFixture,
+    };
+    use crate::io::{load_proof, save_proof, ProofEncoding};
+    use crate::output::diff_public_values;
+    use crate::types::EpochChangeData;
+    use aptos_lc_core::crypto::hash::HASH_LENGTH;
+    use aptos_lc_core::merkle::sparse_proof::SPARSE_MERKLE_PLACEHOLDER_HASH;
+    use aptos_lc_core::types::output::EPOCH_CHANGE_OUTPUT_MAGIC;
+    use aptos_lc_core::types::validator::ValidatorConsensusInfo;
+    use aptos_sdk::move_types::identifier::Identifier;
+    use std::sync::{Arc, Mutex};
+    use tracing::{span, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    fn generate_assets(
+        fixture: &AptosWrapperFixture,
+    ) -> (
+        SparseMerkleProofAssets,
+        TransactionProofAssets,
+        ValidatorVerifierAssets,
+    ) {
+        let InclusionData {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+        } = fixture.inclusion_data(1).unwrap();
+
+        (
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+        )
+    }
+
+    #[test]
+    fn test_dedupe_assets() {
+        let fixture = generate_fixture();
+        let assets = generate_assets(&fixture);
+        let duplicate = assets.clone();
+        // Decoding the ledger info does not change the identity of the assets
+        duplicate.1.ledger_info().unwrap();
+        let InclusionData {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+        } = fixture.inclusion_data(0).unwrap();
+
+        let asset_sets = std::collections::HashSet::from([
+            assets,
+            duplicate,
+            (
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+            ),
+        ]);
+        assert_eq!(asset_sets.len(), 2);
+    }
+
+    /// Bundles the assets as `ProvingAssets` without computing the state root they are
+    /// expected to prove, so that invalid assets reach `validate_inclusion_assets`.
+    fn to_proving_assets(
+        sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+        transaction_proof_assets: &TransactionProofAssets,
+        validator_verifier_assets: &ValidatorVerifierAssets,
+    ) -> ProvingAssets {
+        ProvingAssets {
+            sparse_merkle_proof_assets: sparse_merkle_proof_assets.clone(),
+            transaction_proof_assets: transaction_proof_assets.clone(),
+            validator_verifier_assets: validator_verifier_assets.clone(),
+            state_checkpoint_hash: [0; 32],
+        }
+    }
+
+    fn empty_stdin_field(
+        sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+        transaction_proof_assets: &TransactionProofAssets,
+        validator_verifier_assets: &ValidatorVerifierAssets,
+    ) -> String {
+        match generate_stdin(
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+        ) {
+            Err(LightClientError::InvalidInput { input, .. }) => input,
+            other => panic!("expected an invalid input error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_inclusion_assets() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+
+        validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn test_trace_sparse_merkle() {
+        let proving_assets =
+            ProvingAssets::from_inclusion_data(generate_fixture().inclusion_data(1).unwrap())
+                .unwrap();
+        let assets = proving_assets.sparse_merkle_proof_assets();
+
+        let trace = trace_sparse_merkle(
+            &assets.sparse_merkle_proof,
+            assets.leaf_key,
+            assets.leaf_hash,
+        )
+        .unwrap();
+        assert!(trace.len() > 1);
+        assert_eq!(trace.last(), Some(proving_assets.state_checkpoint_hash()));
+
+        // Another value diverges from the leaf up to the root
+        let trace_other =
+            trace_sparse_merkle(&assets.sparse_merkle_proof, assets.leaf_key, [0; 32]).unwrap();
+        assert_eq!(trace_other.len(), trace.len());
+        assert!(trace_other
+            .iter()
+            .zip(&trace)
+            .all(|(other, hash)| other != hash));
+
+        let truncated = &assets.sparse_merkle_proof[..assets.sparse_merkle_proof.len() - 1];
+        assert!(matches!(
+            trace_sparse_merkle(truncated, assets.leaf_key, assets.leaf_hash),
+            Err(LightClientError::DeserializationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_malformed_transaction() {
+        let (sparse_merkle_proof_assets, mut transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        transaction_proof_assets.transaction.pop();
+
+        let res = validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ));
+
+        assert!(matches!(
+            res,
+            Err(LightClientError::DeserializationError { .. })
+        ));
+        assert_eq!(failing_check(res), "TransactionInfo");
+    }
+
+    #[test]
+    fn test_validate_wrong_transaction_index() {
+        let (sparse_merkle_proof_assets, mut transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        // The fixture proves the latest transaction, so a lower index stays below the
+        // ledger info version and is only caught by the accumulator proof.
+        transaction_proof_assets.transaction_index -= 1;
+
+        let res = validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ));
+
+        assert_eq!(failing_check(res), "TransactionAccumulatorProof");
+    }
+
+    #[test]
+    fn test_validate_transaction_newer_than_ledger_info() {
+        let (sparse_merkle_proof_assets, mut transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        transaction_proof_assets.transaction_index += 1;
+
+        let res = validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ));
+
+        assert_eq!(failing_check(res), "TransactionVersion");
+    }
+
+    #[test]
+    fn test_validate_wrong_validator_verifier() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let validator_verifier =
+            ValidatorVerifier::from_bytes(validator_verifier_assets.validator_verifier()).unwrap();
+        let validator_verifier_assets = ValidatorVerifierAssets::new(
+            ValidatorVerifier::new(validator_verifier.validator_infos()[1..].to_vec()).to_bytes(),
+        );
+
+        let res = validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ));
+
+        assert_eq!(failing_check(res), "LedgerInfoWithSignatures");
+    }
+
+    #[test]
+    fn test_validate_wrong_leaf_hash() {
+        let (mut sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        sparse_merkle_proof_assets.leaf_hash = [0; 32];
+
+        let res = validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ));
+
+        assert_eq!(failing_check(res), "SparseMerkleProof");
+    }
+
+    #[test]
+    fn test_validate_proof_without_leaf() {
+        let (mut sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        // Without a leaf, the proof shows the key is absent rather than included
+        let sparse_merkle_proof =
+            SparseMerkleProof::from_bytes(&sparse_merkle_proof_assets.sparse_merkle_proof).unwrap();
+        sparse_merkle_proof_assets.sparse_merkle_proof =
+            SparseMerkleProof::new(None, sparse_merkle_proof.siblings().clone()).to_bytes();
+
+        let res = validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        ));
+
+        assert!(matches!(
+            res,
+            Err(LightClientError::VerificationError { .. })
+        ));
+        assert_eq!(failing_check(res), "SparseMerkleProof");
+        assert_eq!(
+            failing_check(
+                expected_state_root(&sparse_merkle_proof_assets, &transaction_proof_assets)
+                    .map(|_| ())
+            ),
+            "SparseMerkleProof"
+        );
+    }
+
+    /// Returns the validators of the fixture, stripped of their voting power so that they
+    /// can never reach a quorum.
+    fn unreachable_validator_verifier(fixture: &AptosWrapperFixture) -> ValidatorVerifier {
+        ValidatorVerifier::new(
+            fixture
+                .validator_verifier()
+                .unwrap()
+                .validator_infos()
+                .iter()
+                .map(|info| {
+                    ValidatorConsensusInfo::new(*info.address(), info.public_key().clone(), 0)
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_unsatisfiable_quorum() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+
+        let degenerate = unreachable_validator_verifier(&fixture);
+        assert!(matches!(
+            ValidatorVerifierAssets::try_new(degenerate.to_bytes()),
+            Err(LightClientError::UnsatisfiableQuorum {
+                total_voting_power: 0,
+                ..
+            })
+        ));
+        assert_eq!(
+            ValidatorVerifierAssets::try_new(validator_verifier_assets.validator_verifier.clone())
+                .unwrap(),
+            validator_verifier_assets
+        );
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &ValidatorVerifierAssets::new(degenerate.to_bytes()),
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::UnsatisfiableQuorum
+            })
+        ));
+    }
+
+    #[test]
+    fn test_unsatisfiable_alternative_quorum() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let validator_verifier = validator_verifier_assets.validator_verifier().clone();
+        let degenerate = unreachable_validator_verifier(&fixture).to_bytes();
+        let client = ProverClient::mock();
+
+        // An unreachable candidate is skipped, on whichever side the valid one is
+        for (validator_verifiers, expected_index) in [
+            (vec![validator_verifier.clone(), degenerate.clone()], 0),
+            (vec![degenerate.clone(), validator_verifier], 1),
+        ] {
+            let validator_verifier_assets =
+                ValidatorVerifierAssets::new_multi(validator_verifiers).unwrap();
+            validate_inclusion_assets(&to_proving_assets(
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &validator_verifier_assets,
+            ))
+            .unwrap();
+            let stdin = generate_stdin(
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &validator_verifier_assets,
+            )
+            .unwrap();
+            let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+            let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+            assert_eq!(output.validator_verifier_index(), &Some(expected_index));
+        }
+
+        // But rejected when no candidate is left
+        let validator_verifier_assets =
+            ValidatorVerifierAssets::new_multi(vec![degenerate.clone(), degenerate]).unwrap();
+        assert!(matches!(
+            validate_inclusion_assets(&to_proving_assets(
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &validator_verifier_assets
+            )),
+            Err(LightClientError::UnsatisfiableQuorum { .. })
+        ));
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::UnsatisfiableQuorum
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_non_checkpoint_transaction() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let aptos_wrapper = fixture.aptos_wrapper();
+
+        // The transaction preceding the one closing the block is a transfer,
+        // which does not carry a state checkpoint.
+        let version = *aptos_wrapper.current_version() - 1;
+        let (transaction, transaction_proof) =
+            aptos_wrapper.get_transaction_with_proof(version).unwrap();
+        assert!(transaction.state_checkpoint_hash().is_none());
+        let transaction_proof_assets = TransactionProofAssets::new(
+            bcs::to_bytes(&transaction).unwrap(),
+            version,
+            bcs::to_bytes(&transaction_proof).unwrap(),
+            transaction_proof_assets.latest_li().clone(),
+        );
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::NotACheckpoint
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_multi_transaction_inclusion() {
+        let fixture = generate_fixture();
+        let (_, transaction_proof_assets, validator_verifier_assets) = generate_assets(&fixture);
+        let aptos_wrapper = fixture.aptos_wrapper();
+
+        // The transactions closing the block and the two preceding it, proven against the
+        // same ledger info
+        let current_version = *aptos_wrapper.current_version();
+        let transactions = (current_version - 2..=current_version)
+            .map(|version| {
+                let (transaction, transaction_proof) =
+                    aptos_wrapper.get_transaction_with_proof(version).unwrap();
+                TransactionProofAssets::new(
+                    bcs::to_bytes(&transaction).unwrap(),
+                    version,
+                    bcs::to_bytes(&transaction_proof).unwrap(),
+                    transaction_proof_assets.latest_li().clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let multi_transaction_proof_assets =
+            MultiTransactionProofAssets::from_transaction_proof_assets(&transactions).unwrap();
+
+        let stdin = generate_multi_transaction_stdin(
+            &multi_transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = MultiTransactionOutput::from_public_values(&mut public_values).unwrap();
+
+        let transaction_hashes = transactions
+            .iter()
+            .map(|assets| {
+                *TransactionInfo::from_bytes(assets.transaction())
+                    .unwrap()
+                    .hash()
+                    .as_ref()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(output.transaction_hashes(), &transaction_hashes);
+        let ledger_info = transaction_proof_assets.ledger_info().unwrap();
+        assert_eq!(
+            output.block_hash(),
+            ledger_info.ledger_info().block_id().as_ref()
+        );
+
+        // An output proving an account is not mistaken for one proving transactions
+        let stdin = generate_stdin(
+            &generate_assets(&fixture).0,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        assert!(matches!(
+            MultiTransactionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::WrongProgramOutput { .. })
+        ));
+
+        // An empty list of transactions is rejected on the host, and in the program
+        assert!(matches!(
+            MultiTransactionProofAssets::new(vec![], transaction_proof_assets.latest_li().clone()),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            MultiTransactionProofAssets::from_transaction_proof_assets(&[]),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&ProofLayout::Transactions.to_u8());
+        stdin.write(&Vec::<(Vec<u8>, u64, Vec<u8>)>::new());
+        stdin.write_vec(transaction_proof_assets.latest_li().clone());
+        stdin.write_vec(validator_verifier_assets.validator_verifier().clone());
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        assert!(matches!(
+            MultiTransactionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::EmptyBatch
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_batch_inclusion() {
+        let mut fixture = AptosWrapperFixture::new(3, 4, 3).unwrap();
+        fixture.generate_traffic().unwrap();
+
+        // The accounts are all proven against the state checkpoint of the latest transaction
+        let inclusion_data = (0..3)
+            .map(|account_idx| fixture.inclusion_data(account_idx).unwrap())
+            .collect::<Vec<_>>();
+        let transaction_proof_assets = inclusion_data[0].transaction_proof_assets.clone();
+        let validator_verifier_assets = inclusion_data[0].validator_verifier_assets.clone();
+        assert!(inclusion_data
+            .iter()
+            .all(|data| data.transaction_proof_assets == transaction_proof_assets));
+        let sparse_merkle_proof_assets = inclusion_data
+            .into_iter()
+            .map(|data| data.sparse_merkle_proof_assets)
+            .collect::<Vec<_>>();
+        let batch_inclusion_proof_assets = BatchInclusionProofAssets::new(
+            &sparse_merkle_proof_assets,
+            transaction_proof_assets.clone(),
+        )
+        .unwrap();
+
+        let stdin = generate_batch_stdin(&batch_inclusion_proof_assets, &validator_verifier_assets)
+            .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = BatchInclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        // The root committed by the program is the one recomputed on the host
+        assert_eq!(*output.leaf_count(), 3);
+        assert_eq!(
+            output.accumulator_root(),
+            &batch_inclusion_proof_assets.accumulator_root().unwrap()
+        );
+        let ledger_info = transaction_proof_assets.ledger_info().unwrap();
+        assert_eq!(
+            output.block_hash(),
+            ledger_info.ledger_info().block_id().as_ref()
+        );
+        let transaction =
+            TransactionInfo::from_bytes(transaction_proof_assets.transaction()).unwrap();
+        assert_eq!(
+            output.state_root(),
+            transaction.state_checkpoint().unwrap().as_ref()
+        );
+
+        // The program commits the same root for the leaves given in any order
+        for permutation in [[2, 0, 1], [1, 2, 0], [2, 1, 0]] {
+            let permuted_assets = BatchInclusionProofAssets::new(
+                &permutation.map(|idx| sparse_merkle_proof_assets[idx].clone()),
+                transaction_proof_assets.clone(),
+            )
+            .unwrap();
+            let stdin = generate_batch_stdin(&permuted_assets, &validator_verifier_assets).unwrap();
+            let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+            let permuted_output =
+                BatchInclusionOutput::from_public_values(&mut public_values).unwrap();
+            assert_eq!(
+                permuted_output.accumulator_root(),
+                output.accumulator_root()
+            );
+            assert_eq!(
+                permuted_assets.accumulator_root().unwrap(),
+                batch_inclusion_proof_assets.accumulator_root().unwrap()
+            );
+        }
+
+        // A batch proves at least one leaf, each under a distinct key, which the program
+        // enforces as well with a status
+        assert!(matches!(
+            BatchInclusionProofAssets::new(&[], transaction_proof_assets.clone()),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+        let duplicated_assets = [
+            sparse_merkle_proof_assets[0].clone(),
+            sparse_merkle_proof_assets[0].clone(),
+        ];
+        assert!(matches!(
+            BatchInclusionProofAssets::new(&duplicated_assets, transaction_proof_assets.clone()),
+            Err(LightClientError::InvalidInput { .. })
+        ));
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&ProofLayout::Batch.to_u8());
+        stdin.write(&vec![batch_inclusion_proof_assets.leaves()[0].clone(); 2]);
+        stdin.write_vec(transaction_proof_assets.transaction().clone());
+        stdin.write(transaction_proof_assets.transaction_index());
+        stdin.write_vec(transaction_proof_assets.transaction_proof().clone());
+        stdin.write_vec(transaction_proof_assets.latest_li().clone());
+        stdin.write_vec(validator_verifier_assets.validator_verifier().clone());
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        assert!(matches!(
+            BatchInclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::DuplicateKey
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_event_inclusion() {
+        let fixture = generate_fixture();
+        let (_, transaction_proof_assets, validator_verifier_assets) = generate_assets(&fixture);
+        let aptos_wrapper = fixture.aptos_wrapper();
+
+        // The last event of the latest transaction emitting any, e.g. a transfer
+        let version = (0..=*aptos_wrapper.current_version())
+            .rev()
+            .find(|version| !aptos_wrapper.get_events(*version).unwrap().is_empty())
+            .unwrap();
+        let event_index = aptos_wrapper.get_events(version).unwrap().len() as u64 - 1;
+        let (event, event_proof) = aptos_wrapper
+            .get_event_with_proof(version, event_index)
+            .unwrap();
+        let event_proof_assets = EventProofAssets::new(
+            bcs::to_bytes(&event).unwrap(),
+            event_index,
+            bcs::to_bytes(&event_proof).unwrap(),
+        );
+        let (transaction, transaction_proof) =
+            aptos_wrapper.get_transaction_with_proof(version).unwrap();
+        let transaction_proof_assets = TransactionProofAssets::new(
+            bcs::to_bytes(&transaction).unwrap(),
+            version,
+            bcs::to_bytes(&transaction_proof).unwrap(),
+            transaction_proof_assets.latest_li().clone(),
+        );
+
+        let stdin = generate_event_stdin(
+            &event_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = EventOutput::from_public_values(&mut public_values).unwrap();
+
+        assert_eq!(output.event_hash(), AptosCryptoHash::hash(&event).as_ref());
+        assert_eq!(output.event_hash(), &event_proof_assets.event_hash());
+        assert_eq!(
+            output.transaction_hash(),
+            TransactionInfo::from_bytes(transaction_proof_assets.transaction())
+                .unwrap()
+                .hash()
+                .as_ref()
+        );
+        let ledger_info = transaction_proof_assets.ledger_info().unwrap();
+        assert_eq!(
+            output.block_hash(),
+            ledger_info.ledger_info().block_id().as_ref()
+        );
+
+        // The event is not at another index of the accumulator of the transaction
+        let other_index = EventProofAssets::new(
+            event_proof_assets.event().clone(),
+            event_index + 1,
+            event_proof_assets.event_proof().clone(),
+        );
+        let stdin = generate_event_stdin(
+            &other_index,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        assert!(client.execute(INCLUSION_ELF, stdin).run().is_err());
+    }
+
+    #[test]
+    fn test_execute_proof_deeper_than_key() {
+        let (mut sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let sparse_merkle_proof =
+            SparseMerkleProof::from_bytes(sparse_merkle_proof_assets.sparse_merkle_proof())
+                .unwrap();
+        let mut siblings = sparse_merkle_proof.siblings().clone();
+        siblings.resize(HASH_LENGTH * 8 + 1, SPARSE_MERKLE_PLACEHOLDER_HASH);
+        sparse_merkle_proof_assets.sparse_merkle_proof =
+            SparseMerkleProof::new(*sparse_merkle_proof.leaf(), siblings).to_bytes();
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::InvalidProofDepth
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_transaction_newer_than_ledger_info() {
+        let (sparse_merkle_proof_assets, mut transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let latest_li = transaction_proof_assets.ledger_info().unwrap();
+        transaction_proof_assets.transaction_index = latest_li.ledger_info().version() + 1;
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::NewerThanLedgerInfo
+            })
+        ));
+    }
+
+    #[test]
+    fn test_execute_invalid_signatures_skips_proofs() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let client = ProverClient::mock();
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (_, report) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let valid_cycles = report.total_instruction_count();
+
+        // The quorum of 4 validators of equal voting power is 3 of them
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3)
+            .unwrap()
+            .with_ledger_info_signers(2);
+        fixture.generate_traffic().unwrap();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (mut public_values, report) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+
+        // The program stops on the signatures, before spending any cycle on the proofs
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::InsufficientVotingPower
+            })
+        ));
+        assert!(!report.cycle_tracker.contains_key("verify_proofs"));
+        assert!(report.total_instruction_count() < valid_cycles);
+    }
+
+    #[test]
+    fn test_leaf_hash_for_module() {
+        let fixture = generate_fixture();
+        let proof_assets = fixture
+            .aptos_wrapper()
+            .get_latest_proof(&StateKey::module(
+                &AccountAddress::ONE,
+                &Identifier::new("coin").unwrap(),
+            ))
+            .unwrap();
+
+        let bytecode = proof_assets.state_value().as_ref().unwrap().bytes();
+
+        assert_eq!(
+            &leaf_hash_for_module(bytecode),
+            proof_assets.state_value_hash().unwrap().as_ref()
+        );
+
+        // A module stored with metadata is hashed along with it
+        let sparse_merkle_proof_assets =
+            SparseMerkleProofAssets::for_module(vec![], [0; 32], bytecode.to_vec());
+        let metadata = [&[0][..], &50u64.to_le_bytes(), &0u64.to_le_bytes()].concat();
+        let with_metadata = sparse_merkle_proof_assets
+            .clone()
+            .with_module_metadata(metadata.clone());
+        assert_eq!(
+            with_metadata.leaf_hash(),
+            module_value_hash_with_metadata(bytecode, Some(&metadata)).as_ref()
+        );
+        assert_ne!(
+            with_metadata.leaf_hash(),
+            sparse_merkle_proof_assets.leaf_hash()
+        );
+    }
+
+    #[test]
+    fn test_account_state_proof_matches_split_proofs() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let (account_state_proof_assets, combined_validator_verifier_assets) =
+            fixture.account_state_proof_data(1).unwrap();
+
+        // The bundled proof is encoded as the split proofs put together
+        assert_eq!(
+            AccountStateProofAssets::from_split_assets(
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets
+            )
+            .unwrap(),
+            account_state_proof_assets
+        );
+
+        let client = ProverClient::mock();
+        let split_stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (split_public_values, _) = client.execute(INCLUSION_ELF, split_stdin).run().unwrap();
+        let combined_stdin = generate_account_state_proof_stdin(
+            &account_state_proof_assets,
+            &combined_validator_verifier_assets,
+        )
+        .unwrap();
+        let (mut combined_public_values, _) =
+            client.execute(INCLUSION_ELF, combined_stdin).run().unwrap();
+
+        assert!(diff_public_values(&split_public_values, &combined_public_values).is_empty());
+        InclusionOutput::from_public_values(&mut combined_public_values).unwrap();
+    }
+
+    #[test]
+    fn test_prove_module_inclusion() {
+        let fixture = generate_fixture();
+        let proving_assets = ProvingAssets::from_inclusion_data(
+            fixture
+                .module_inclusion_data(AccountAddress::ONE, "coin")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            proving_assets.sparse_merkle_proof_assets().leaf_kind(),
+            LeafKind::Module
+        );
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let mut proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        client.verify(&proof, &vk).unwrap();
+
+        let output = InclusionOutput::from_public_values(&mut proof.public_values).unwrap();
+        assert_eq!(output.leaf_kind(), &LeafKind::Module);
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+
+        // Without the preimage of its key, a leaf can not be told to hold a module
+        let mut sparse_merkle_proof_assets = proving_assets.sparse_merkle_proof_assets().clone();
+        sparse_merkle_proof_assets.key_preimage = None;
+        assert_eq!(
+            failing_check(validate_inclusion_assets(&to_proving_assets(
+                &sparse_merkle_proof_assets,
+                proving_assets.transaction_proof_assets(),
+                proving_assets.validator_verifier_assets()
+            ))),
+            "LeafKind"
+        );
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            proving_assets.transaction_proof_assets(),
+            proving_assets.validator_verifier_assets(),
+        )
+        .unwrap();
+        assert!(client.execute(INCLUSION_ELF, stdin).run().is_err());
+
+        // The program hashes the bytecode itself, so a tampered module is not included
+        let mut sparse_merkle_proof_assets = proving_assets.sparse_merkle_proof_assets().clone();
+        sparse_merkle_proof_assets
+            .module_bytecode
+            .as_mut()
+            .unwrap()
+            .push(0);
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            proving_assets.transaction_proof_assets(),
+            proving_assets.validator_verifier_assets(),
+        )
+        .unwrap();
+        assert!(client.execute(INCLUSION_ELF, stdin).run().is_err());
+    }
+
+    #[test]
+    fn test_state_value_leaf_hash() {
+        let fixture = generate_fixture();
+        let proof_assets = fixture
+            .aptos_wrapper()
+            .get_latest_proof_account(1)
+            .unwrap();
+
+        let state_value = bcs::to_bytes(proof_assets.state_value().as_ref().unwrap()).unwrap();
+
+        assert_eq!(
+            &state_value_leaf_hash(&state_value),
+            proof_assets.state_value_hash().unwrap().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_account_state_key() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, _, _) = generate_assets(&fixture);
+
+        let address = fixture.aptos_wrapper().accounts()[1].address();
+        assert_eq!(
+            &account_state_key(address),
+            sparse_merkle_proof_assets.leaf_key()
+        );
+        assert_ne!(
+            &account_state_key(fixture.aptos_wrapper().accounts()[0].address()),
+            sparse_merkle_proof_assets.leaf_key()
+        );
+    }
+
+    #[test]
+    fn test_generate_stdin_rejects_empty_assets() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+
+        let mut empty_sparse_merkle_proof = sparse_merkle_proof_assets.clone();
+        empty_sparse_merkle_proof.sparse_merkle_proof = vec![];
+        assert_eq!(
+            empty_stdin_field(
+                &empty_sparse_merkle_proof,
+                &transaction_proof_assets,
+                &validator_verifier_assets
+            ),
+            "sparse_merkle_proof"
+        );
+
+        let mut empty_transaction = transaction_proof_assets.clone();
+        empty_transaction.transaction = vec![];
+        assert_eq!(
+            empty_stdin_field(
+                &sparse_merkle_proof_assets,
+                &empty_transaction,
+                &validator_verifier_assets
+            ),
+            "transaction"
+        );
+
+        let mut empty_transaction_proof = transaction_proof_assets.clone();
+        empty_transaction_proof.transaction_proof = vec![];
+        assert_eq!(
+            empty_stdin_field(
+                &sparse_merkle_proof_assets,
+                &empty_transaction_proof,
+                &validator_verifier_assets
+            ),
+            "transaction_proof"
+        );
+
+        let mut empty_latest_li = transaction_proof_assets.clone();
+        empty_latest_li.latest_li = vec![];
+        assert_eq!(
+            empty_stdin_field(
+                &sparse_merkle_proof_assets,
+                &empty_latest_li,
+                &validator_verifier_assets
+            ),
+            "latest_li"
+        );
+
+        let empty_signed_ledger_info = transaction_proof_assets
+            .clone()
+            .with_signed_ledger_info(vec![]);
+        assert_eq!(
+            empty_stdin_field(
+                &sparse_merkle_proof_assets,
+                &empty_signed_ledger_info,
+                &validator_verifier_assets
+            ),
+            "signed_ledger_info"
+        );
+
+        assert_eq!(
+            empty_stdin_field(
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &ValidatorVerifierAssets::new(vec![])
+            ),
+            "validator_verifier"
+        );
+
+        generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_generate_stdin_rejects_oversized_assets() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let max_bytes = 1024 * 1024;
+
+        generate_stdin_with_limit(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+            max_bytes,
+        )
+        .unwrap();
+
+        let mut oversized_sparse_merkle_proof = sparse_merkle_proof_assets.clone();
+        oversized_sparse_merkle_proof.sparse_merkle_proof = vec![0; max_bytes];
+        match generate_stdin_with_limit(
+            &oversized_sparse_merkle_proof,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+            max_bytes,
+        ) {
+            Err(LightClientError::InputTooLarge { bytes }) => assert!(bytes > max_bytes),
+            other => panic!("expected an input too large error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_inclusion_output_rejects_epoch_change_output() {
+        let mut public_values = SP1PublicValues::new();
+        public_values.write(&EPOCH_CHANGE_OUTPUT_MAGIC);
+        public_values.write(&[0u8; 32]);
+        public_values.write(&[1u8; 32]);
+        public_values.write(&1u64);
+        public_values.write(&2u64);
+
+        match InclusionOutput::from_public_values(&mut public_values) {
+            Err(LightClientError::WrongProgramOutput { actual, .. }) => {
+                assert_eq!(actual, EPOCH_CHANGE_OUTPUT_MAGIC)
+            }
+            other => panic!("expected a wrong program output error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_expected_state_root() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+
+        let expected_state_root =
+            expected_state_root(&sparse_merkle_proof_assets, &transaction_proof_assets).unwrap();
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        assert_eq!(output.state_hash(), &expected_state_root);
+    }
+
+    #[test]
+    fn test_committed_key_is_proved_leaf_key() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let proved_leaf =
+            SparseMerkleProof::from_bytes(sparse_merkle_proof_assets.sparse_merkle_proof())
+                .unwrap()
+                .leaf()
+                .unwrap();
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        assert_eq!(output.key(), proved_leaf.key().as_ref());
+        assert_eq!(output.value(), proved_leaf.value_hash().as_ref());
+    }
+
+    #[test]
+    fn test_transaction_proof_assets_ledger_info() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+
+        let ledger_info = transaction_proof_assets.ledger_info().unwrap();
+        assert_eq!(ledger_info.to_bytes(), *transaction_proof_assets.latest_li());
+        // The cached value is returned on subsequent calls
+        assert_eq!(transaction_proof_assets.ledger_info().unwrap(), ledger_info);
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        assert_eq!(
+            output.block_hash(),
+            ledger_info.ledger_info().block_id().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_proving_assets_getters() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+
+        let proving_assets = generate_proving_assets(&fixture);
+
+        assert_eq!(
+            proving_assets.sparse_merkle_proof_assets().leaf_key(),
+            sparse_merkle_proof_assets.leaf_key()
+        );
+        assert_eq!(
+            proving_assets.sparse_merkle_proof_assets().leaf_hash(),
+            sparse_merkle_proof_assets.leaf_hash()
+        );
+        assert_eq!(
+            proving_assets.transaction_proof_assets().transaction(),
+            transaction_proof_assets.transaction()
+        );
+        assert_eq!(
+            proving_assets.transaction_proof_assets().transaction_index(),
+            transaction_proof_assets.transaction_index()
+        );
+        assert_eq!(
+            proving_assets.validator_verifier_assets().validator_verifier(),
+            validator_verifier_assets.validator_verifier()
+        );
+        assert_eq!(
+            *proving_assets.state_checkpoint_hash(),
+            expected_state_root(&sparse_merkle_proof_assets, &transaction_proof_assets).unwrap()
+        );
+
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client
+            .execute(INCLUSION_ELF, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(output.state_hash(), proving_assets.state_checkpoint_hash());
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_assert_self_consistent_saved_proof() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+        let client = ProverClient::mock();
+        let (pk, _) = generate_keys(&client).unwrap();
+        let proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let path = std::env::temp_dir().join("aptos-lc-test-self-consistent.proof");
+        save_proof(&proof, &path, ProofEncoding::Bincode).unwrap();
+        let saved_proof = load_proof(&path, ProofEncoding::Bincode);
+        std::fs::remove_file(&path).unwrap();
+        let mut saved_proof = saved_proof.unwrap();
+
+        assert_self_consistent(&saved_proof).unwrap();
+        let output = expected_from_proof(&saved_proof).unwrap();
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+
+        // Trailing bytes do not follow the layout of the program
+        let mut bytes = saved_proof.public_values.to_vec();
+        bytes.push(0);
+        saved_proof.public_values = SP1PublicValues::from(&bytes);
+        assert!(expected_from_proof(&saved_proof).is_ok());
+        assert!(matches!(
+            assert_self_consistent(&saved_proof),
+            Err(LightClientError::Mismatch { field, .. }) if field == "public values length"
+        ));
+    }
+
+    #[test]
+    fn test_explain_proving_assets() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let explanation = proving_assets.explain().unwrap();
+        assert_eq!(explanation.lines().count(), 4);
+        assert!(explanation.contains("Transaction accumulator"));
+        assert!(explanation.contains("Signatures"));
+        assert!(explanation.contains("Sparse Merkle"));
+        let expected = proving_assets.expected_inclusion().unwrap();
+        for hash in [expected.validator_verifier_hash(), expected.state_root()] {
+            assert!(explanation.contains(&hex::encode(hash)));
+        }
+    }
+
+    #[test]
+    fn test_committed_accumulator_leaves() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+        let transaction_proof_assets = proving_assets.transaction_proof_assets();
+
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client
+            .execute(INCLUSION_ELF, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        let ledger_info = transaction_proof_assets.ledger_info().unwrap();
+        assert_eq!(
+            output.accumulator_leaves(),
+            &Some(ledger_info.ledger_info().version() + 1)
+        );
+        assert_accumulator_leaves(&output, *transaction_proof_assets.transaction_index()).unwrap();
+
+        // The leaves are counted up to the ledger info carrying the signatures
+        let signed_assets = transaction_proof_assets
+            .clone()
+            .with_signed_ledger_info(transaction_proof_assets.latest_li().clone());
+        let stdin = generate_stdin(
+            proving_assets.sparse_merkle_proof_assets(),
+            &signed_assets,
+            proving_assets.validator_verifier_assets(),
+        )
+        .unwrap();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(
+            output.accumulator_leaves(),
+            &Some(ledger_info.ledger_info().version() + 1)
+        );
+    }
+
+    #[test]
+    fn test_verify_inclusion_trusted() {
+        let mut fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        // The mock prover only binds the verifying key to Plonk proofs
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let expected_vkey_hash = vk_hash(&vk);
+        let proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .plonk()
+            .run()
+            .unwrap();
+        let output = verify_inclusion_trusted(&client, &proof, expected_vkey_hash).unwrap();
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+        let (epoch_change_pk, _) = crate::epoch_change::generate_keys(&client).unwrap();
+        let epoch_change_proof = client
+            .prove(
+                &epoch_change_pk,
+                crate::epoch_change::generate_stdin(&trusted_state, &epoch_change_proof),
+            )
+            .plonk()
+            .run()
+            .unwrap();
+        assert!(matches!(
+            verify_inclusion_trusted(&client, &epoch_change_proof, expected_vkey_hash),
+            Err(LightClientError::WrongProgram { .. })
+        ));
+
+        // The embedded program is not the expected one
+        assert!(matches!(
+            verify_inclusion_trusted(&client, &proof, [0; 32]),
+            Err(LightClientError::VerifyingKeyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_committed_epoch() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client
+            .execute(INCLUSION_ELF, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        let ledger_info = proving_assets
+            .transaction_proof_assets()
+            .ledger_info()
+            .unwrap();
+        assert_eq!(output.epoch(), &Some(ledger_info.ledger_info().epoch()));
+        assert_eq!(output.validator_verifier_index(), &Some(0));
+        assert_eq!(output.signatures_skipped(), &Some(false));
+        assert_eq!(
+            output.timestamp_usecs(),
+            &Some(ledger_info.ledger_info().timestamp_usecs())
+        );
+    }
+
+    #[cfg(feature = "skip-signatures")]
+    #[test]
+    fn test_skipped_signatures() {
+        let fixture = generate_fixture();
+        let other_fixture = generate_fixture();
+        let other_validator_verifier = other_fixture.validator_verifier().unwrap();
+
+        // The ledger info is not signed by these validators, which goes unnoticed
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets =
+            ValidatorVerifierAssets::new(other_validator_verifier.to_bytes());
+        let proving_assets = ProvingAssets::from_inclusion_data(inclusion_data).unwrap();
+        let mut public_values = run_inclusion_guest(proving_assets.stdin().unwrap()).unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+
+        assert_eq!(output.signatures_skipped(), &Some(true));
+        assert_eq!(
+            output.validator_verifier_hash(),
+            other_validator_verifier.hash().as_ref()
+        );
+        match assert_signatures_verified(&output) {
+            Err(LightClientError::Mismatch { field, .. }) => assert_eq!(field, "signatures"),
+            other => panic!("expected the skipped signatures to be rejected, got {other:?}"),
+        }
+        let expected = proving_assets.expected_inclusion().unwrap();
+        assert!(assert_inclusion_output(&output, &expected).is_err());
+    }
+
+    #[test]
+    fn test_accumulator_root() {
+        let fixture = generate_fixture();
+        let (_, transaction_proof_assets, _) = generate_assets(&fixture);
+        let accumulator_root = transaction_proof_assets.accumulator_root().unwrap();
+
+        // The root the program verifies the transaction proof against
+        let transaction = TransactionInfo::from_bytes(transaction_proof_assets.transaction())
+            .unwrap()
+            .hash();
+        let transaction_proof =
+            TransactionAccumulatorProof::from_bytes(transaction_proof_assets.transaction_proof())
+                .unwrap();
+        transaction_proof
+            .verify(
+                HashValue::new(accumulator_root),
+                transaction,
+                *transaction_proof_assets.transaction_index(),
+            )
+            .unwrap();
+        assert!(transaction_proof
+            .verify(
+                HashValue::new([0; 32]),
+                transaction,
+                *transaction_proof_assets.transaction_index(),
+            )
+            .is_err());
+
+        let proving_assets = generate_proving_assets(&fixture);
+        assert_eq!(
+            proving_assets
+                .transaction_proof_assets()
+                .accumulator_root()
+                .unwrap(),
+            accumulator_root
+        );
+        assert!(run_inclusion_guest(proving_assets.stdin().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_execute_and_read_inclusion() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+        let client = ProverClient::mock();
+
+        let (report, executed_output) =
+            execute_and_read_inclusion(&client, &proving_assets).unwrap();
+        assert!(report.report().total_instruction_count() > 0);
+        // The magic, then the status, the validator verifier hash, the state root, the
+        // block id, the key and the value hash, the leaf kind, the number of accumulator
+        // leaves, the epoch, the validator verifier index, the transaction hash, and an
+        // absent key preimage, with its empty path prefixed by its length
+        assert_eq!(
+            *report.public_values_len(),
+            4 + 1 + 5 * 32 + 1 + 8 + 8 + 1 + 32 + (1 + 32 + 8) + 1 + 8
+        );
+
+        // The mock prover only binds the public values to Plonk proofs
+        let (pk, _) = generate_keys(&client).unwrap();
+        let mut proof = client
+            .prove(&pk, proving_assets.stdin().unwrap())
+            .plonk()
+            .run()
+            .unwrap();
+        let proved_output = InclusionOutput::from_public_values(&mut proof.public_values).unwrap();
+        assert_eq!(executed_output, proved_output);
+    }
+
+    #[test]
+    fn test_run_inclusion_guest() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let mut public_values = run_inclusion_guest(proving_assets.stdin().unwrap()).unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+
+        // The program panics when its stdin runs out before all its inputs are read
+        assert!(matches!(
+            run_inclusion_guest(SP1Stdin::new()),
+            Err(LightClientError::GuestPanic { .. })
+        ));
+    }
+
+    #[test]
+    fn test_try_execute_inclusion() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+        assert!(matches!(
+            try_execute_inclusion(&proving_assets),
+            ExecutionOutcome::Ok(report) if report.report().total_instruction_count() > 0
+        ));
+
+        // The validators of another chain did not sign the ledger info
+        let other_validator_verifier = generate_fixture().validator_verifier().unwrap();
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets = ValidatorVerifierAssets::new_multi(vec![
+            other_validator_verifier.to_bytes(),
+            other_validator_verifier.to_bytes(),
+        ])
+        .unwrap();
+        let proving_assets = ProvingAssets::from_inclusion_data(inclusion_data).unwrap();
+        assert!(matches!(
+            try_execute_inclusion(&proving_assets),
+            ExecutionOutcome::GuestFailure(InclusionStatus::NoMatchingValidatorVerifier)
+        ));
+
+        // An empty asset is rejected before the program is executed
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets = ValidatorVerifierAssets::new(vec![]);
+        let proving_assets = ProvingAssets::from_inclusion_data(inclusion_data).unwrap();
+        assert!(matches!(
+            try_execute_inclusion(&proving_assets),
+            ExecutionOutcome::HostError(LightClientError::InvalidInput { .. })
+        ));
+
+        // The program panics when it can not deserialize the validator verifier
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets = ValidatorVerifierAssets::new(vec![0xff; 4]);
+        let proving_assets = ProvingAssets::from_inclusion_data(inclusion_data).unwrap();
+        assert!(matches!(
+            try_execute_inclusion(&proving_assets),
+            ExecutionOutcome::HostError(LightClientError::GuestPanic { .. })
+        ));
+    }
+
+    #[test]
+    fn test_new_multi_validator_verifier_assets() {
+        let assets = ValidatorVerifierAssets::new_multi(vec![vec![1], vec![2]]).unwrap();
+        assert_eq!(assets.validator_verifier(), &vec![1]);
+        assert_eq!(assets.alternative_validator_verifier(), &Some(vec![2]));
+        assert_eq!(
+            ValidatorVerifierAssets::new_multi(vec![vec![1]]).unwrap(),
+            ValidatorVerifierAssets::new(vec![1])
+        );
+
+        for validator_verifiers in [vec![], vec![vec![1], vec![2], vec![3]]] {
+            assert!(matches!(
+                ValidatorVerifierAssets::new_multi(validator_verifiers),
+                Err(LightClientError::InvalidInput { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_prove_inclusion_under_second_validator_verifier() {
+        let fixture = generate_fixture();
+        // The validators of another chain did not sign any of its ledger infos
+        let other_validator_verifier = generate_fixture().validator_verifier().unwrap();
+        let validator_verifier = fixture.validator_verifier().unwrap();
+        let client = ProverClient::mock();
+
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets = ValidatorVerifierAssets::new_multi(vec![
+            other_validator_verifier.to_bytes(),
+            validator_verifier.to_bytes(),
+        ])
+        .unwrap();
+        let proving_assets = ProvingAssets::from_inclusion_data(inclusion_data).unwrap();
+        validate_inclusion_assets(&proving_assets).unwrap();
+
+        let (mut public_values, _) = client
+            .execute(INCLUSION_ELF, proving_assets.stdin().unwrap())
+            .run()
+            .unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(output.validator_verifier_index(), &Some(1));
+        assert_eq!(
+            output.validator_verifier_hash(),
+            validator_verifier.hash().as_ref()
+        );
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+
+        // Neither candidate signed the ledger info
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets = ValidatorVerifierAssets::new_multi(vec![
+            other_validator_verifier.to_bytes(),
+            other_validator_verifier.to_bytes(),
+        ])
+        .unwrap();
+        let (mut public_values, _) = client
+            .execute(
+                INCLUSION_ELF,
+                generate_stdin(
+                    &inclusion_data.sparse_merkle_proof_assets,
+                    &inclusion_data.transaction_proof_assets,
+                    &inclusion_data.validator_verifier_assets,
+                )
+                .unwrap(),
+            )
+            .run()
+            .unwrap();
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::NoMatchingValidatorVerifier
+            })
+        ));
+    }
+
+    #[test]
+    fn test_prove_inclusion_with_cancel() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let client = ProverClient::mock();
+
+        let cancel = AtomicBool::new(true);
+        assert!(matches!(
+            prove_inclusion_with_cancel(
+                &client,
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &validator_verifier_assets,
+                &cancel,
+            ),
+            Err(LightClientError::Cancelled { .. })
+        ));
+
+        cancel.store(false, Ordering::Relaxed);
+        let (_, output) = prove_inclusion_with_cancel(
+            &client,
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+            &cancel,
+        )
+        .unwrap();
+        assert_eq!(output.key(), sparse_merkle_proof_assets.leaf_key());
+    }
+
+    /// Records the names of the spans created while it is the default subscriber, along with
+    /// the names of their fields.
+    struct SpanNames(Arc<Mutex<Vec<(String, Vec<String>)>>>);
+
+    impl<S: Subscriber> Layer<S> for SpanNames {
+        fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, S>) {
+            let metadata = attrs.metadata();
+            let fields = metadata
+                .fields()
+                .iter()
+                .map(|field| field.name().to_string())
+                .collect();
+            self.0
+                .lock()
+                .unwrap()
+                .push((metadata.name().to_string(), fields));
+        }
+    }
+
+    #[test]
+    fn test_prove_inclusion_spans() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&generate_fixture());
+        let client = ProverClient::mock();
+
+        let span_names = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(SpanNames(span_names.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            prove_inclusion_with_cancel(
+                &client,
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &validator_verifier_assets,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+        });
+
+        let span_names = span_names.lock().unwrap();
+        for name in [
+            "prove_inclusion",
+            "generate_stdin",
+            "generate_keys",
+            "prove",
+        ] {
+            assert!(
+                span_names.iter().any(|(span_name, _)| span_name == name),
+                "missing span {name} in {span_names:?}"
+            );
+        }
+        // The proving spans carry the number of leaves proven
+        for name in ["prove_inclusion", "generate_stdin", "prove"] {
+            assert!(
+                span_names
+                    .iter()
+                    .any(|(span_name, fields)| span_name == name
+                        && fields.iter().any(|field| field == "nbr_leaves")),
+                "missing nbr_leaves in span {name} of {span_names:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_signed_ledger_info() {
+        let mut fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let client = ProverClient::mock();
+
+        // A copy of the ledger info shares its accumulator root
+        let matching_assets = transaction_proof_assets
+            .clone()
+            .with_signed_ledger_info(transaction_proof_assets.latest_li().clone());
+        validate_inclusion_assets(&to_proving_assets(
+            &sparse_merkle_proof_assets,
+            &matching_assets,
+            &validator_verifier_assets,
+        ))
+        .unwrap();
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &matching_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(output.key(), sparse_merkle_proof_assets.leaf_key());
+
+        // The ledger info of a later block does not
+        fixture.generate_traffic().unwrap();
+        let mismatching_assets =
+            transaction_proof_assets.with_signed_ledger_info(fixture.latest_li_bytes().unwrap());
+        assert_eq!(
+            failing_check(validate_inclusion_assets(&to_proving_assets(
+                &sparse_merkle_proof_assets,
+                &mismatching_assets,
+                &validator_verifier_assets
+            ))),
+            "SignedLedgerInfo"
+        );
+        let stdin = generate_stdin(
+            &sparse_merkle_proof_assets,
+            &mismatching_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        assert!(matches!(
+            InclusionOutput::from_public_values(&mut public_values),
+            Err(LightClientError::InclusionFailure {
+                status: InclusionStatus::LedgerInfoMismatch
+            })
+        ));
+    }
+
+    /// Returns a copy of a serialized ledger info moved by the given number of epochs and
+    /// versions, but sharing its transaction accumulator root and signatures.
+    fn shifted_ledger_info(li_bytes: &[u8], epochs: u64, versions: u64) -> Vec<u8> {
+        use aptos_types::block_info::BlockInfo;
+        use aptos_types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+
+        let signed_li: LedgerInfoWithSignatures = bcs::from_bytes(li_bytes).unwrap();
+        let ledger_info = signed_li.ledger_info();
+        let commit_info = ledger_info.commit_info();
+        let shifted_li = LedgerInfo::new(
+            BlockInfo::new(
+                commit_info.epoch() + epochs,
+                commit_info.round(),
+                commit_info.id(),
+                commit_info.executed_state_id(),
+                commit_info.version() + versions,
+                commit_info.timestamp_usecs(),
+                commit_info.next_epoch_state().cloned(),
+            ),
+            ledger_info.consensus_data_hash(),
+        );
+
+        bcs::to_bytes(&LedgerInfoWithSignatures::new(
+            shifted_li,
+            signed_li.signatures().clone(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_signed_ledger_info_position_mismatch() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let client = ProverClient::mock();
+
+        // Sharing the accumulator root is not enough to be the same ledger info
+        for (epochs, versions) in [(0, 1), (1, 0)] {
+            let shifted_li =
+                shifted_ledger_info(transaction_proof_assets.latest_li(), epochs, versions);
+            let mismatching_assets = transaction_proof_assets
+                .clone()
+                .with_signed_ledger_info(shifted_li);
+            assert_eq!(
+                failing_check(validate_inclusion_assets(&to_proving_assets(
+                    &sparse_merkle_proof_assets,
+                    &mismatching_assets,
+                    &validator_verifier_assets
+                ))),
+                "SignedLedgerInfo"
+            );
+            let stdin = generate_stdin(
+                &sparse_merkle_proof_assets,
+                &mismatching_assets,
+                &validator_verifier_assets,
+            )
+            .unwrap();
+            let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+            assert!(matches!(
+                InclusionOutput::from_public_values(&mut public_values),
+                Err(LightClientError::InclusionFailure {
+                    status: InclusionStatus::LedgerInfoMismatch
+                })
+            ));
+        }
+    }
+
+    fn write_v0_values(public_values: &mut SP1PublicValues) {
+        for value in 0..5u8 {
+            public_values.write(&[value; 32]);
+        }
+    }
+
+    fn assert_v0_values(output: &InclusionOutput) {
+        assert_eq!(output.validator_verifier_hash(), &[0; 32]);
+        assert_eq!(output.state_hash(), &[1; 32]);
+        assert_eq!(output.block_hash(), &[2; 32]);
+        assert_eq!(output.key(), &[3; 32]);
+        assert_eq!(output.value(), &[4; 32]);
+    }
+
+    #[test]
+    fn test_read_inclusion_output_v0() {
+        let mut public_values = SP1PublicValues::new();
+        write_v0_values(&mut public_values);
+
+        let output = read_inclusion_output_versioned(&mut public_values, 0).unwrap();
+
+        assert_v0_values(&output);
+        assert_eq!(output.leaf_kind(), &LeafKind::Resource);
+        assert_eq!(output.accumulator_leaves(), &None);
+        assert_eq!(output.timestamp_usecs(), &None);
+    }
+
+    #[test]
+    fn test_read_inclusion_output_v1() {
+        let write_values = |has_key_preimage: u8, path: Vec<u8>| {
+            let mut public_values = SP1PublicValues::new();
+            public_values.write(&INCLUSION_OUTPUT_MAGIC);
+            public_values.write(&InclusionStatus::Included.to_u8());
+            write_v0_values(&mut public_values);
+            public_values.write(&LeafKind::Module.to_u8());
+            public_values.write(&8u64);
+            public_values.write(&2u64);
+            public_values.write(&1u8);
+            public_values.write(&[5u8; 32]);
+            public_values.write(&has_key_preimage);
+            public_values.write(&[6u8; 32]);
+            public_values.write(&path);
+            public_values.write(&0u8);
+            public_values.write(&9u64);
+            public_values
+        };
+
+        let output = read_inclusion_output_versioned(&mut write_values(1, vec![7, 8]), 1).unwrap();
+        assert_v0_values(&output);
+        assert_eq!(output.leaf_kind(), &LeafKind::Module);
+        assert_eq!(output.accumulator_leaves(), &Some(8));
+        assert_accumulator_leaves(&output, 7).unwrap();
+        assert!(matches!(
+            assert_accumulator_leaves(&output, 8),
+            Err(LightClientError::Mismatch { .. })
+        ));
+        assert_eq!(output.epoch(), &Some(2));
+        assert_eq!(output.validator_verifier_index(), &Some(1));
+        assert_eq!(output.transaction_hash(), &Some([5; 32]));
+        assert_eq!(
+            output.key_preimage(),
+            &Some(StateKeyPreimage::new([6; 32], vec![7, 8]))
+        );
+        assert_eq!(output.signatures_skipped(), &Some(false));
+        assert_eq!(output.timestamp_usecs(), &Some(9));
+
+        let output = read_inclusion_output_versioned(&mut write_values(0, vec![]), 1).unwrap();
+        assert_eq!(output.key_preimage(), &None);
+    }
+
+    #[test]
+    fn test_committed_key_preimage() {
+        let fixture = generate_fixture();
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            generate_assets(&fixture);
+        let address = fixture.aptos_wrapper().accounts()[1].address();
+        let key_preimage = StateKeyPreimage::for_account(address);
+        assert_eq!(key_preimage.state_key(), account_state_key(address));
+
+        let execute = |sparse_merkle_proof_assets: &SparseMerkleProofAssets| {
+            let stdin = generate_stdin(
+                sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+                &validator_verifier_assets,
+            )
+            .unwrap();
+            run_inclusion_guest(stdin)
+        };
+
+        let mut public_values = execute(&sparse_merkle_proof_assets).unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(output.key_preimage(), &None);
+
+        let with_preimage = sparse_merkle_proof_assets
+            .clone()
+            .with_key_preimage(key_preimage.clone());
+        let mut public_values = execute(&with_preimage).unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        let committed = output.key_preimage().as_ref().unwrap();
+        assert_eq!(committed, &key_preimage);
+        assert_eq!(
+            access_path_state_key(committed.address(), committed.path()).as_ref(),
+            output.key()
+        );
+
+        // Another account does not hash to the key, which the program refuses
+        let other_preimage = sparse_merkle_proof_assets
+            .with_key_preimage(StateKeyPreimage::for_account(AccountAddress::ONE));
+        assert!(matches!(
+            validate_inclusion_assets(&to_proving_assets(
+                &other_preimage,
+                &transaction_proof_assets,
+                &validator_verifier_assets
+            )),
+            Err(LightClientError::VerificationError { .. })
+        ));
+        assert!(execute(&other_preimage).is_err());
+    }
+
+    #[test]
+    fn test_committed_transaction_hash() {
+        let fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+
+        let mut public_values = run_inclusion_guest(proving_assets.stdin().unwrap()).unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        let transaction = proving_assets.transaction_proof_assets().transaction();
+        let transaction_hash = TransactionInfo::from_bytes(transaction).unwrap().hash();
+        assert_eq!(output.transaction_hash(), &Some(*transaction_hash.as_ref()));
+        assert_transaction_hash(&output, transaction).unwrap();
+
+        let output = InclusionOutput {
+            transaction_hash: Some([0; 32]),
+            ..output
+        };
+        match assert_transaction_hash(&output, transaction) {
+            Err(LightClientError::Mismatch { field, .. }) => assert_eq!(field, "transaction hash"),
+            other => panic!("expected a mismatch of the transaction hash, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_read_inclusion_output_unsupported_version() {
+        let mut public_values = SP1PublicValues::new();
+        write_v0_values(&mut public_values);
+
+        assert!(matches!(
+            read_inclusion_output_versioned(&mut public_values, INCLUSION_OUTPUT_VERSION + 1),
+            Err(LightClientError::UnsupportedOutputVersion { .. })
+        ));
+    }
+
+    fn v0_output() -> InclusionOutput {
+        let mut public_values = SP1PublicValues::new();
+        write_v0_values(&mut public_values);
+
+        read_inclusion_output_versioned(&mut public_values, 0).unwrap()
+    }
+
+    fn v0_expected_inclusion() -> ExpectedInclusion {
+        ExpectedInclusion::new([0; 32], [1; 32], [2; 32], [3; 32], [4; 32])
+    }
+
+    fn assert_mismatch(expected: &ExpectedInclusion, expected_field: &str) {
+        match assert_inclusion_output(&v0_output(), expected) {
+            Err(LightClientError::Mismatch { field, actual, .. }) => {
+                assert_eq!(field, expected_field);
+                assert!(actual.starts_with("0x"));
+            }
+            Err(err) => panic!("expected a mismatch, got {err}"),
+            Ok(()) => panic!("expected a mismatch of the {expected_field}"),
+        }
+    }
+
+    #[test]
+    fn test_assert_inclusion_output() {
+        assert_inclusion_output(&v0_output(), &v0_expected_inclusion()).unwrap();
+    }
+
+    #[test]
+    fn test_assert_fresh() {
+        let max_age = Duration::from_secs(60);
+        let output = InclusionOutput {
+            timestamp_usecs: Some(1_000_000),
+            ..v0_output()
+        };
+        let bound_usecs = 1_000_000 + 60_000_000;
+
+        assert_fresh(&output, max_age, bound_usecs - 1).unwrap();
+        assert_fresh(&output, max_age, bound_usecs).unwrap();
+        assert!(matches!(
+            assert_fresh(&output, max_age, bound_usecs + 1),
+            Err(LightClientError::StaleProof { age_usecs }) if age_usecs == 60_000_001
+        ));
+        // A ledger info ahead of the clock is not stale
+        assert_fresh(&output, max_age, 0).unwrap();
+        assert!(matches!(
+            assert_fresh(&v0_output(), max_age, bound_usecs),
+            Err(LightClientError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_assert_inclusion_output_validator_verifier_hash_mismatch() {
+        let mut expected = v0_expected_inclusion();
+        expected.validator_verifier_hash = [9; 32];
+        assert_mismatch(&expected, "validator verifier hash");
+    }
+
+    #[test]
+    fn test_assert_inclusion_output_state_root_mismatch() {
+        let mut expected = v0_expected_inclusion();
+        expected.state_root = [9; 32];
+        assert_mismatch(&expected, "state root");
+    }
+
+    #[test]
+    fn test_assert_inclusion_output_block_id_mismatch() {
+        let mut expected = v0_expected_inclusion();
+        expected.block_id = [9; 32];
+        assert_mismatch(&expected, "block id");
+    }
+
+    #[test]
+    fn test_assert_inclusion_output_key_mismatch() {
+        let mut expected = v0_expected_inclusion();
+        expected.key = [9; 32];
+        assert_mismatch(&expected, "key");
+    }
+
+    #[test]
+    fn test_assert_inclusion_output_value_mismatch() {
+        let mut expected = v0_expected_inclusion();
+        expected.value = [9; 32];
+        assert_mismatch(&expected, "value");
+    }
+
+    #[test]
+    fn test_assert_inclusion_output_reports_first_mismatch() {
+        let mut expected = v0_expected_inclusion();
+        expected.block_id = [9; 32];
+        expected.value = [9; 32];
+        assert_mismatch(&expected, "block id");
+    }
+
+    #[test]
+    fn test_assert_block_in_checkpoints() {
+        let output = v0_output();
+
+        let checkpoints = HashSet::from([[9; 32], *output.block_hash()]);
+        assert_block_in_checkpoints(&output, &checkpoints).unwrap();
+
+        let checkpoints = HashSet::from([[9; 32]]);
+        match assert_block_in_checkpoints(&output, &checkpoints) {
+            Err(LightClientError::UnknownBlock { block }) => {
+                assert_eq!(block, format!("0x{}", hex::encode(output.block_hash())));
+            }
+            result => panic!("expected an unknown block, got {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,906 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers to persist the inputs of the light client programs, so that a failing
+//! execution or proof can be reproduced without regenerating its assets, their
+//! verifying keys, so that proofs can be verified away from the prover, and their proofs.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use aptos_lc_core::crypto::hash::hash_data;
+use aptos_lc_core::types::trusted_state::TrustedState;
+use clap::ValueEnum;
+use sp1_sdk::{
+    HashableKey, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::error::LightClientError;
+use crate::inclusion::ValidatorVerifierAssets;
+use crate::types::InclusionData;
+
+/// Writes an `SP1Stdin` to a file, serialized with bincode.
+///
+/// # Arguments
+///
+/// * `stdin` - The `SP1Stdin` to write.
+/// * `path` - The path of the file to write to.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the file was written, and `Err` otherwise.
+pub fn dump_stdin(stdin: &SP1Stdin, path: impl AsRef<Path>) -> Result<(), LightClientError> {
+    let path = path.as_ref();
+    let bytes = bincode::serialize(stdin).map_err(|err| LightClientError::SerializationError {
+        structure: "SP1Stdin".to_string(),
+        source: err.into(),
+    })?;
+
+    fs::write(path, bytes).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })
+}
+
+/// Reads an `SP1Stdin` previously written with `dump_stdin`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read from.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1Stdin`, or `Err` if the file could not be read or
+/// does not contain a serialized `SP1Stdin`.
+pub fn load_stdin(path: impl AsRef<Path>) -> Result<SP1Stdin, LightClientError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    bincode::deserialize(&bytes).map_err(|err| LightClientError::DeserializationError {
+        structure: "SP1Stdin".to_string(),
+        source: err.into(),
+    })
+}
+
+/// Writes a `TrustedState` to a file, serialized with bcs, so that a light client can
+/// resume ratcheting from it in a later run.
+///
+/// # Arguments
+///
+/// * `trusted_state` - The `TrustedState` to write.
+/// * `path` - The path of the file to write to.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the file was written, and `Err` otherwise.
+pub fn save_trusted_state(
+    trusted_state: &TrustedState,
+    path: impl AsRef<Path>,
+) -> Result<(), LightClientError> {
+    let path = path.as_ref();
+    fs::write(path, trusted_state.to_bytes()).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })
+}
+
+/// Reads a `TrustedState` previously written with `save_trusted_state`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read from.
+///
+/// # Returns
+///
+/// A `Result` containing the `TrustedState`, or `Err` if the file could not be read or
+/// does not contain a `TrustedState::EpochState`, the only one the epoch change program
+/// can ratchet.
+pub fn load_trusted_state(path: impl AsRef<Path>) -> Result<TrustedState, LightClientError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    let trusted_state =
+        TrustedState::from_bytes(&bytes).map_err(|err| LightClientError::DeserializationError {
+            structure: "TrustedState".to_string(),
+            source: err.into(),
+        })?;
+    if !matches!(trusted_state, TrustedState::EpochState { .. }) {
+        return Err(LightClientError::InvalidInput {
+            input: path.display().to_string(),
+            reason: "expected a trusted state inside an epoch".to_string(),
+        });
+    }
+
+    Ok(trusted_state)
+}
+
+/// Reads the assets of the inclusion program from a JSON `InclusionData`, e.g. piped in
+/// by another tool.
+///
+/// # Arguments
+///
+/// * `reader` - The reader to consume the JSON from.
+///
+/// # Returns
+///
+/// A `Result` containing the `InclusionData`, or `Err` if the reader could not be read to
+/// its end or does not contain a valid JSON `InclusionData`.
+pub fn read_inclusion_data_json(mut reader: impl Read) -> Result<InclusionData, LightClientError> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|err| LightClientError::IoError {
+            path: "<stdin>".to_string(),
+            source: err,
+        })?;
+
+    serde_json::from_slice(&bytes).map_err(|err| LightClientError::DeserializationError {
+        structure: "InclusionData".to_string(),
+        source: err.into(),
+    })
+}
+
+/// Returns the JSON Schema of the `InclusionData` read by `read_inclusion_data_json`, for
+/// tools generating the assets to validate them against.
+///
+/// # Returns
+///
+/// The JSON Schema, as indented JSON.
+pub fn inclusion_data_json_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(InclusionData))
+        .expect("a JSON Schema is serializable")
+}
+
+/// Reads the identifiers of the approved blocks from a JSON array of hex strings, with or
+/// without a `0x` prefix.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read from.
+///
+/// # Returns
+///
+/// A `Result` containing the block identifiers, or `Err` if the file could not be read or
+/// an entry is not the hex of a 32 bytes identifier.
+pub fn load_checkpoints(path: impl AsRef<Path>) -> Result<HashSet<[u8; 32]>, LightClientError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    let block_ids: Vec<String> =
+        serde_json::from_slice(&bytes).map_err(|err| LightClientError::DeserializationError {
+            structure: "checkpoints".to_string(),
+            source: err.into(),
+        })?;
+    block_ids
+        .iter()
+        .map(|block_id| {
+            let mut hash = [0; 32];
+            hex::decode_to_slice(block_id.trim_start_matches("0x"), &mut hash).map_err(|err| {
+                LightClientError::InvalidInput {
+                    input: path.display().to_string(),
+                    reason: format!("invalid block id {block_id}: {err}"),
+                }
+            })?;
+            Ok(hash)
+        })
+        .collect()
+}
+
+/// Reads a trusted validator set, e.g. the one of a given chain, to prove inclusions under
+/// it rather than under the validators of the assets. The bytes are checked with
+/// `ValidatorVerifierAssets::try_new` before any proving, so that a corrupted file fails
+/// early.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file holding the bcs serialization of the `ValidatorVerifier`.
+///
+/// # Returns
+///
+/// A `Result` containing the `ValidatorVerifierAssets` of the validator set, or `Err` if
+/// the file could not be read, does not contain a `ValidatorVerifier` or contains one whose
+/// quorum is unreachable.
+pub fn load_validator_set(
+    path: impl AsRef<Path>,
+) -> Result<ValidatorVerifierAssets, LightClientError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    ValidatorVerifierAssets::try_new(bytes)
+}
+
+/// Encoding of the proofs written with `save_proof`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProofEncoding {
+    /// Compact binary encoding, as used by `SP1ProofWithPublicValues::save`.
+    #[default]
+    Bincode,
+    /// Pretty-printed JSON, to inspect the proof.
+    Json,
+    /// CBOR, a compact binary encoding readable outside of Rust.
+    Cbor,
+}
+
+impl ProofEncoding {
+    /// Guesses the encoding of a serialized `SP1ProofWithPublicValues` from its first byte.
+    ///
+    /// A JSON object starts with `{`, a CBOR map has major type 5, while bincode starts
+    /// with the little-endian index of the `SP1Proof` variant, which is below 4.
+    const fn detect(bytes: &[u8]) -> Self {
+        match bytes.first() {
+            Some(b'{') => ProofEncoding::Json,
+            Some(0xa0..=0xbf) => ProofEncoding::Cbor,
+            _ => ProofEncoding::Bincode,
+        }
+    }
+}
+
+impl FromStr for ProofEncoding {
+    type Err = LightClientError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_value_enum(input, "proof encoding")
+    }
+}
+
+/// Parses a variant of an enum from its name, as clap does for the arguments, ignoring
+/// the case.
+///
+/// # Arguments
+///
+/// * `input` - The name of the variant.
+/// * `name` - The name of the parsed value, used in the errors.
+///
+/// # Returns
+///
+/// A `Result` containing the variant, or an `InvalidInput` error listing the valid values.
+pub(crate) fn parse_value_enum<T: ValueEnum>(
+    input: &str,
+    name: &str,
+) -> Result<T, LightClientError> {
+    <T as ValueEnum>::from_str(input, true).map_err(|_| {
+        let valid_values = T::value_variants()
+            .iter()
+            .filter_map(ValueEnum::to_possible_value)
+            .map(|value| value.get_name().to_string())
+            .collect::<Vec<_>>();
+        LightClientError::InvalidInput {
+            input: name.to_string(),
+            reason: format!(
+                "unknown value '{input}', expected one of: {}",
+                valid_values.join(", ")
+            ),
+        }
+    })
+}
+
+/// Writes a proof to a file with the given encoding.
+///
+/// # Arguments
+///
+/// * `proof` - The proof to write.
+/// * `path` - The path of the file to write to.
+/// * `encoding` - The encoding of the proof in the file.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the file was written, and `Err` otherwise.
+pub fn save_proof(
+    proof: &SP1ProofWithPublicValues,
+    path: impl AsRef<Path>,
+    encoding: ProofEncoding,
+) -> Result<(), LightClientError> {
+    let path = path.as_ref();
+    let serialization_error =
+        |err: Box<dyn std::error::Error + Sync + Send>| LightClientError::SerializationError {
+            structure: "SP1ProofWithPublicValues".to_string(),
+            source: err,
+        };
+
+    let bytes = match encoding {
+        ProofEncoding::Bincode => {
+            bincode::serialize(proof).map_err(|err| serialization_error(err.into()))?
+        }
+        ProofEncoding::Json => {
+            serde_json::to_vec_pretty(proof).map_err(|err| serialization_error(err.into()))?
+        }
+        ProofEncoding::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(proof, &mut bytes)
+                .map_err(|err| serialization_error(err.into()))?;
+            bytes
+        }
+    };
+
+    fs::write(path, bytes).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })
+}
+
+/// Reads a proof previously written with `save_proof`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read from.
+/// * `encoding` - The encoding the proof was written with.
+///
+/// # Returns
+///
+/// A `Result` containing the proof, or `Err` if the file could not be read, seems to be
+/// written with another encoding, or does not contain a proof.
+pub fn load_proof(
+    path: impl AsRef<Path>,
+    encoding: ProofEncoding,
+) -> Result<SP1ProofWithPublicValues, LightClientError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    let detected = ProofEncoding::detect(&bytes);
+    if detected != encoding {
+        return Err(LightClientError::ProofEncodingMismatch {
+            path: path.display().to_string(),
+            expected: encoding,
+            detected,
+        });
+    }
+
+    let deserialization_error =
+        |err: Box<dyn std::error::Error + Sync + Send>| LightClientError::DeserializationError {
+            structure: "SP1ProofWithPublicValues".to_string(),
+            source: err,
+        };
+    match encoding {
+        ProofEncoding::Bincode => {
+            bincode::deserialize(&bytes).map_err(|err| deserialization_error(err.into()))
+        }
+        ProofEncoding::Json => {
+            serde_json::from_slice(&bytes).map_err(|err| deserialization_error(err.into()))
+        }
+        ProofEncoding::Cbor => {
+            ciborium::from_reader(bytes.as_slice()).map_err(|err| deserialization_error(err.into()))
+        }
+    }
+}
+
+/// Reads the ELF of a program from a file, e.g. a freshly built guest to use instead of
+/// the embedded one.
+///
+/// # Arguments
+///
+/// * `path` - The path of the ELF file.
+///
+/// # Returns
+///
+/// A `Result` containing the bytes of the ELF, or `Err` if the file could not be read or
+/// is empty.
+pub fn load_elf(path: impl AsRef<Path>) -> Result<Vec<u8>, LightClientError> {
+    let path = path.as_ref();
+    let elf = fs::read(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    if elf.is_empty() {
+        return Err(LightClientError::InvalidInput {
+            input: path.display().to_string(),
+            reason: "the ELF file is empty".to_string(),
+        });
+    }
+
+    Ok(elf)
+}
+
+/// Target the embedded ELFs of the programs were built for, as checked from their headers
+/// when the crate is built. It also names the ELF files of the `programs` directory.
+pub const GUEST_TARGET: &str = env!("APTOS_LC_GUEST_TARGET");
+
+/// Environment variable pointing to a directory laid out as `programs`, from which the ELFs
+/// of the programs are loaded instead of the embedded ones.
+pub const SP1_ELF_DIR_ENV: &str = "SP1_ELF_DIR";
+
+/// Returns the directory set by `SP1_ELF_DIR`, if any, to be given to `resolve_elf`.
+pub fn elf_dir_from_env() -> Option<PathBuf> {
+    std::env::var_os(SP1_ELF_DIR_ENV).map(PathBuf::from)
+}
+
+/// Returns the ELF of a program, loaded from `elf_dir` when given, or from the `programs`
+/// directory of the crate when the embedded bytes are empty, e.g. when the crate is vendored
+/// without its guests. The source used is logged.
+///
+/// # Arguments
+///
+/// * `embedded` - The ELF embedded in the binary.
+/// * `program` - The directory of the program, e.g. `epoch-change`.
+/// * `elf_dir` - A directory laid out as `programs` to load the ELF from, e.g. the one
+///   returned by `elf_dir_from_env`.
+///
+/// # Returns
+///
+/// A `Result` containing the bytes of the ELF, or `Err` if it had to be loaded and could
+/// not be.
+pub fn resolve_elf(
+    embedded: &'static [u8],
+    program: &str,
+    elf_dir: Option<&Path>,
+) -> Result<Cow<'static, [u8]>, LightClientError> {
+    let elf_dir = match elf_dir {
+        Some(elf_dir) => elf_dir.to_path_buf(),
+        None if !embedded.is_empty() => {
+            tracing::info!(program, "using the embedded ELF");
+            return Ok(Cow::Borrowed(embedded));
+        }
+        // Resolved at compile time, so it does not depend on the working directory
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("../programs"),
+    };
+
+    let path = elf_dir.join(program).join("elf").join(GUEST_TARGET);
+    tracing::info!(program, path = %path.display(), "loading the ELF");
+    load_elf(path).map(Cow::Owned)
+}
+
+/// Returns the keys of a program, loaded from `cache_dir` if the same ELF was set up before,
+/// or set up and written there otherwise. The keys are cached under the SHA3-256 hash of
+/// the ELF, so that a changed ELF is set up again.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program with.
+/// * `elf` - The ELF of the program.
+/// * `cache_dir` - The directory of the cached keys, created if missing.
+///
+/// # Returns
+///
+/// A `Result` containing the proving and verifying keys, or `Err` if the cached keys could
+/// not be read or written.
+pub fn load_or_setup_keys(
+    client: &ProverClient,
+    elf: &[u8],
+    cache_dir: impl AsRef<Path>,
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), LightClientError> {
+    load_or_setup_keys_with(elf, cache_dir.as_ref(), || client.setup(elf))
+}
+
+fn load_or_setup_keys_with(
+    elf: &[u8],
+    cache_dir: &Path,
+    setup: impl FnOnce() -> (SP1ProvingKey, SP1VerifyingKey),
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), LightClientError> {
+    let io_error = |path: &Path, err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    };
+    let path = cache_dir.join(format!("{}.keys", hex::encode(hash_data(&[], vec![elf]))));
+
+    match fs::read(&path) {
+        Ok(bytes) => {
+            tracing::info!(path = %path.display(), "loading the cached keys");
+            return bincode::deserialize(&bytes).map_err(|err| {
+                LightClientError::DeserializationError {
+                    structure: "SP1ProvingKey".to_string(),
+                    source: err.into(),
+                }
+            });
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => (),
+        Err(err) => return Err(io_error(&path, err)),
+    }
+
+    let keys = setup();
+    let bytes = bincode::serialize(&keys).map_err(|err| LightClientError::SerializationError {
+        structure: "SP1ProvingKey".to_string(),
+        source: err.into(),
+    })?;
+    fs::create_dir_all(cache_dir).map_err(|err| io_error(cache_dir, err))?;
+    // Renamed once written, so that an interrupted write is not mistaken for cached keys
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, bytes).map_err(|err| io_error(&tmp_path, err))?;
+    fs::rename(&tmp_path, &path).map_err(|err| io_error(&path, err))?;
+    tracing::info!(path = %path.display(), "cached the keys");
+
+    Ok(keys)
+}
+
+/// Serializes an `SP1VerifyingKey` with bincode.
+///
+/// # Arguments
+///
+/// * `vk` - The `SP1VerifyingKey` to serialize.
+///
+/// # Returns
+///
+/// A `Result` containing the serialized `SP1VerifyingKey`.
+pub fn vk_to_bytes(vk: &SP1VerifyingKey) -> Result<Vec<u8>, LightClientError> {
+    bincode::serialize(vk).map_err(|err| LightClientError::SerializationError {
+        structure: "SP1VerifyingKey".to_string(),
+        source: err.into(),
+    })
+}
+
+/// Reads an `SP1VerifyingKey` exported with `inclusion_vk_bytes` or `epoch_change_vk_bytes`.
+///
+/// # Arguments
+///
+/// * `bytes` - The serialized `SP1VerifyingKey`.
+///
+/// # Returns
+///
+/// A `Result` containing the `SP1VerifyingKey`, or `Err` if the bytes do not contain a
+/// serialized `SP1VerifyingKey`.
+pub fn load_vk(bytes: &[u8]) -> Result<SP1VerifyingKey, LightClientError> {
+    bincode::deserialize(bytes).map_err(|err| LightClientError::DeserializationError {
+        structure: "SP1VerifyingKey".to_string(),
+        source: err.into(),
+    })
+}
+
+/// Checks that a verifying key matches the hash an external verifier is pinned to, e.g.
+/// to detect that a change in a program requires redeploying its on-chain verifier.
+///
+/// # Arguments
+///
+/// * `vk` - The verifying key of the program.
+/// * `expected` - The expected hash, as hex. The `0x` prefix is optional.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the hashes match, and `Err` containing both of them otherwise.
+pub fn check_vk_hash(vk: &SP1VerifyingKey, expected: &str) -> Result<(), LightClientError> {
+    let actual = vk.bytes32();
+    let expected = expected.trim();
+
+    if actual.trim_start_matches("0x") != expected.trim_start_matches("0x").to_lowercase() {
+        return Err(LightClientError::VerifyingKeyMismatch {
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use super::*;
+    use crate::fixture::{generate_fixture, generate_inclusion_stdin, AptosWrapperFixture};
+    use crate::inclusion::{
+        generate_keys, generate_stdin, inclusion_vk_bytes, InclusionOutput, INCLUSION_ELF,
+    };
+    use aptos_lc_core::crypto::hash::CryptoHash;
+    use sp1_sdk::ProverClient;
+
+    #[test]
+    fn test_dump_and_load_stdin() {
+        let stdin = generate_inclusion_stdin();
+
+        let path = std::env::temp_dir().join("aptos-lc-test-dump-and-load-stdin.bin");
+        dump_stdin(&stdin, &path).unwrap();
+        let loaded_stdin = load_stdin(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded_stdin.buffer, stdin.buffer);
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client.prove(&pk, stdin).run().unwrap();
+        let loaded_proof = client.prove(&pk, loaded_stdin).run().unwrap();
+
+        client.verify(&loaded_proof, &vk).unwrap();
+        assert_eq!(
+            loaded_proof.public_values.as_slice(),
+            proof.public_values.as_slice()
+        );
+    }
+
+    #[test]
+    fn test_exported_vk_verifies_proof() {
+        let stdin = generate_inclusion_stdin();
+
+        let client = ProverClient::mock();
+        let vk_bytes = inclusion_vk_bytes(&client).unwrap();
+        let (pk, _) = generate_keys(&client).unwrap();
+        let proof = client.prove(&pk, stdin).run().unwrap();
+
+        client.verify(&proof, &load_vk(&vk_bytes).unwrap()).unwrap();
+        assert!(load_vk(&[]).is_err());
+    }
+
+    #[test]
+    fn test_read_inclusion_data_json() {
+        let fixture = generate_fixture();
+        let json = serde_json::to_vec(&fixture.inclusion_data(1).unwrap()).unwrap();
+
+        let inclusion_data = read_inclusion_data_json(json.as_slice()).unwrap();
+        assert_eq!(serde_json::to_vec(&inclusion_data).unwrap(), json);
+
+        let stdin = generate_stdin(
+            &inclusion_data.sparse_merkle_proof_assets,
+            &inclusion_data.transaction_proof_assets,
+            &inclusion_data.validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (mut public_values, _) = client.execute(INCLUSION_ELF, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        assert_eq!(
+            output.key(),
+            inclusion_data.sparse_merkle_proof_assets.leaf_key()
+        );
+
+        assert!(matches!(
+            read_inclusion_data_json(&json[1..]),
+            Err(LightClientError::DeserializationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_prove_under_loaded_validator_set() {
+        let fixture = generate_fixture();
+        let validator_verifier = fixture.validator_verifier().unwrap();
+        let path = std::env::temp_dir().join("aptos-lc-test-validator-set.bcs");
+        fs::write(&path, validator_verifier.to_bytes()).unwrap();
+        let validator_set = load_validator_set(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut inclusion_data = fixture.inclusion_data(1).unwrap();
+        inclusion_data.validator_verifier_assets = validator_set.unwrap();
+        let stdin = generate_stdin(
+            &inclusion_data.sparse_merkle_proof_assets,
+            &inclusion_data.transaction_proof_assets,
+            &inclusion_data.validator_verifier_assets,
+        )
+        .unwrap();
+        let client = ProverClient::mock();
+        let (pk, _) = generate_keys(&client).unwrap();
+        let mut proof = client.prove(&pk, stdin).run().unwrap();
+        let output = InclusionOutput::from_public_values(&mut proof.public_values).unwrap();
+        assert_eq!(
+            output.validator_verifier_hash(),
+            validator_verifier.hash().as_ref()
+        );
+
+        // The bytes are checked before proving
+        fs::write(&path, [0xff; 4]).unwrap();
+        let result = load_validator_set(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(LightClientError::DeserializationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_inclusion_data_json_schema() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&inclusion_data_json_schema()).unwrap();
+        assert_eq!(schema["title"], "InclusionData");
+
+        let schema = schema.to_string();
+        for field in [
+            "sparse_merkle_proof_assets",
+            "transaction_proof_assets",
+            "validator_verifier_assets",
+            "sparse_merkle_proof",
+            "leaf_key",
+            "leaf_hash",
+            "module_bytecode",
+            "module_metadata",
+            "key_preimage",
+            "address",
+            "path",
+            "transaction",
+            "transaction_index",
+            "transaction_proof",
+            "latest_li",
+            "signed_ledger_info",
+            "validator_verifier",
+            "alternative_validator_verifier",
+        ] {
+            assert!(schema.contains(&format!("\"{field}\"")), "{field}");
+        }
+        // Only the serialized fields are described
+        assert!(!schema.contains("decoded_latest_li"));
+    }
+
+    #[test]
+    fn test_check_vk_hash() {
+        let (_, vk) = generate_keys(&ProverClient::mock()).unwrap();
+        let hash = vk.bytes32();
+
+        check_vk_hash(&vk, &hash).unwrap();
+        check_vk_hash(&vk, hash.trim_start_matches("0x")).unwrap();
+        check_vk_hash(&vk, &hash.to_uppercase().replace("0X", "0x")).unwrap();
+
+        assert!(matches!(
+            check_vk_hash(&vk, &format!("0x{}", "00".repeat(32))),
+            Err(LightClientError::VerifyingKeyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_save_and_load_proof() {
+        let stdin = generate_inclusion_stdin();
+
+        // The mock prover only binds the public values to Plonk proofs
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let proof = client.prove(&pk, stdin).plonk().run().unwrap();
+
+        for encoding in [
+            ProofEncoding::Bincode,
+            ProofEncoding::Json,
+            ProofEncoding::Cbor,
+        ] {
+            let path =
+                std::env::temp_dir().join(format!("aptos-lc-test-save-proof-{encoding:?}.bin"));
+            save_proof(&proof, &path, encoding).unwrap();
+            let loaded_proof = load_proof(&path, encoding).unwrap();
+            let other_encoding = if encoding == ProofEncoding::Json {
+                ProofEncoding::Cbor
+            } else {
+                ProofEncoding::Json
+            };
+            let mismatch = load_proof(&path, other_encoding);
+            fs::remove_file(&path).unwrap();
+
+            client.verify(&loaded_proof, &vk).unwrap();
+            assert_eq!(
+                bincode::serialize(&loaded_proof).unwrap(),
+                bincode::serialize(&proof).unwrap()
+            );
+            match mismatch {
+                Err(LightClientError::ProofEncodingMismatch { detected, .. }) => {
+                    assert_eq!(detected, encoding)
+                }
+                other => panic!("expected an encoding mismatch, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_trusted_state() {
+        let fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let trusted_state = TrustedState::from_bytes(&fixture.trusted_state().unwrap()).unwrap();
+
+        let path = std::env::temp_dir().join("aptos-lc-test-save-and-load-trusted-state.bin");
+        save_trusted_state(&trusted_state, &path).unwrap();
+        let loaded_trusted_state = load_trusted_state(&path).unwrap();
+        assert_eq!(loaded_trusted_state.to_bytes(), trusted_state.to_bytes());
+
+        // Only a state inside an epoch can be ratcheted
+        let waypoint = TrustedState::EpochWaypoint(trusted_state.waypoint());
+        save_trusted_state(&waypoint, &path).unwrap();
+        let loaded_waypoint = load_trusted_state(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            loaded_waypoint,
+            Err(LightClientError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_elf() {
+        let path = std::env::temp_dir().join("aptos-lc-test-load-elf");
+        fs::write(&path, INCLUSION_ELF).unwrap();
+        let elf = load_elf(&path).unwrap();
+
+        let client = ProverClient::mock();
+        assert_eq!(
+            client.setup(&elf).1.bytes32(),
+            generate_keys(&client).unwrap().1.bytes32()
+        );
+
+        fs::write(&path, []).unwrap();
+        let empty_elf = load_elf(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            empty_elf,
+            Err(LightClientError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_embedded_elf_targets_risc_v() {
+        assert_eq!(&INCLUSION_ELF[..4], b"\x7fELF");
+        // 32 bits class, then the `e_machine` of RISC-V
+        assert_eq!(INCLUSION_ELF[4], 1);
+        assert_eq!(
+            u16::from_le_bytes([INCLUSION_ELF[18], INCLUSION_ELF[19]]),
+            0xf3
+        );
+        assert_eq!(GUEST_TARGET, "riscv32im-succinct-zkvm-elf");
+    }
+
+    #[test]
+    fn test_resolve_elf_from_dir() {
+        let elf_dir = std::env::temp_dir().join("aptos-lc-test-elf-dir");
+        let path = elf_dir.join("inclusion/elf/riscv32im-succinct-zkvm-elf");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, b"elf").unwrap();
+
+        assert_eq!(
+            resolve_elf(INCLUSION_ELF, "inclusion", None).unwrap(),
+            INCLUSION_ELF
+        );
+        let elf = resolve_elf(INCLUSION_ELF, "inclusion", Some(&elf_dir));
+        let missing = resolve_elf(INCLUSION_ELF, "epoch-change", Some(&elf_dir));
+        fs::remove_dir_all(&elf_dir).unwrap();
+
+        assert_eq!(elf.unwrap(), &b"elf"[..]);
+        // The directory is used even when the embedded ELF is not empty
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_load_checkpoints() {
+        let path = std::env::temp_dir().join("aptos-lc-test-load-checkpoints.json");
+        let block_id = format!("0x{}", hex::encode([1; 32]));
+        fs::write(
+            &path,
+            format!(r#"["{block_id}", "{}"]"#, hex::encode([2; 32])),
+        )
+        .unwrap();
+        assert_eq!(
+            load_checkpoints(&path).unwrap(),
+            HashSet::from([[1; 32], [2; 32]])
+        );
+
+        fs::write(&path, r#"["0x01"]"#).unwrap();
+        let short_block_id = load_checkpoints(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            short_block_id,
+            Err(LightClientError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_or_setup_keys_from_cache() {
+        let cache_dir = std::env::temp_dir().join("aptos-lc-test-key-cache");
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let client = ProverClient::mock();
+        let setups = std::cell::Cell::new(0);
+        let setup = || {
+            setups.set(setups.get() + 1);
+            client.setup(INCLUSION_ELF)
+        };
+
+        let (_, vk) = load_or_setup_keys_with(INCLUSION_ELF, &cache_dir, &setup).unwrap();
+        let (_, cached_vk) = load_or_setup_keys_with(INCLUSION_ELF, &cache_dir, &setup).unwrap();
+        assert_eq!(setups.get(), 1);
+        assert_eq!(cached_vk.bytes32(), vk.bytes32());
+
+        // Another ELF is set up again
+        load_or_setup_keys_with(b"elf", &cache_dir, &setup).unwrap();
+        fs::remove_dir_all(&cache_dir).unwrap();
+        assert_eq!(setups.get(), 2);
+    }
+
+    #[test]
+    fn test_load_missing_stdin() {
+        assert!(matches!(
+            load_stdin(std::env::temp_dir().join("aptos-lc-test-missing-stdin.bin")),
+            Err(LightClientError::IoError { .. })
+        ));
+    }
+}
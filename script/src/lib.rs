@@ -1,5 +1,21 @@
 pub mod inclusion;
 pub mod error;
 pub mod epoch_change;
+pub mod signature;
 pub mod types;
-pub mod aptos;
\ No newline at end of file
+pub mod aptos;
+pub mod bridge;
+pub mod bundle;
+pub mod client;
+pub mod io;
+pub mod light_client;
+pub mod logger;
+pub mod metrics;
+pub mod output;
+pub mod prelude;
+pub mod sink;
+pub mod telemetry;
+#[cfg(feature = "test-utils")]
+pub mod fixture;
+
+pub use prelude::*;
@@ -0,0 +1,443 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A stateful front-end over the light client programs, for servers proving on demand.
+//!
+//! Setting up the keys of a program is expensive, so `LightClient` does it once for both
+//! programs when it is initialized, and reuses them for every proof.
+
+use std::time::Instant;
+
+use aptos_lc_core::types::trusted_state::TrustedState;
+use aptos_types::state_proof::StateProof;
+use getset::Getters;
+use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+
+use crate::client::{verify_timed, ProofType};
+use crate::epoch_change::{
+    epoch_change_assets_from_state_proof, next_validator_verifier, EpochChangeInputs,
+    EpochChangeOutput,
+};
+use crate::error::LightClientError;
+use crate::inclusion::{
+    InclusionOutput, ProvingAssets, SparseMerkleProofAssets, TransactionProofAssets,
+    ValidatorVerifierAssets,
+};
+use crate::telemetry::record_proof;
+use crate::{epoch_change, inclusion};
+
+/// A `ProverClient` along with the keys of the inclusion and epoch change programs.
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct LightClient {
+    /// The proving and verifying keys of the inclusion program.
+    inclusion_keys: (SP1ProvingKey, SP1VerifyingKey),
+    /// The proving and verifying keys of the epoch change program.
+    epoch_keys: (SP1ProvingKey, SP1VerifyingKey),
+    /// The client proving with those keys.
+    client: ProverClient,
+}
+
+impl LightClient {
+    /// Sets up the keys of both programs on the `ProverClient` selected by the
+    /// environment, as `ProverClient::new` does.
+    ///
+    /// The keys only depend on the programs, so initializing another `LightClient` sets
+    /// up the same keys.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `LightClient`, or `MissingElf` if a program was not built.
+    pub fn init() -> Result<Self, LightClientError> {
+        Self::with_client(ProverClient::new())
+    }
+
+    /// Sets up the keys of both programs on a given `ProverClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The `ProverClient` to set up the programs and prove with.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `LightClient`, or `MissingElf` if a program was not built.
+    pub fn with_client(client: ProverClient) -> Result<Self, LightClientError> {
+        Ok(Self {
+            inclusion_keys: inclusion::generate_keys(&client)?,
+            epoch_keys: epoch_change::generate_keys(&client)?,
+            client,
+        })
+    }
+
+    /// Proves the inclusion of an account with the keys set up at initialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `proving_assets` - The assets of the inclusion program.
+    /// * `proof_type` - The kind of proof to generate.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the proof and the `InclusionOutput`, or `Err` if the proving
+    /// failed or the program did not prove the inclusion.
+    pub fn prove_inclusion(
+        &self,
+        proving_assets: &ProvingAssets,
+        proof_type: ProofType,
+    ) -> Result<(SP1ProofWithPublicValues, InclusionOutput), LightClientError> {
+        let proof = self.prove(
+            &self.inclusion_keys.0,
+            proving_assets.stdin()?,
+            proof_type,
+            "inclusion",
+        )?;
+        let output = InclusionOutput::from_public_values(&mut proof.public_values.clone())?;
+
+        Ok((proof, output))
+    }
+
+    /// Proves an epoch change with the keys set up at initialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The inputs of the epoch change program.
+    /// * `proof_type` - The kind of proof to generate.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the proof and the `EpochChangeOutput`, or `Err` if the proving
+    /// failed or the program did not ratchet the trusted state.
+    pub fn prove_epoch_change(
+        &self,
+        inputs: &EpochChangeInputs,
+        proof_type: ProofType,
+    ) -> Result<(SP1ProofWithPublicValues, EpochChangeOutput), LightClientError> {
+        let proof = self.prove(
+            &self.epoch_keys.0,
+            inputs.stdin(),
+            proof_type,
+            "epoch change",
+        )?;
+        let output = EpochChangeOutput::from_public_values(&mut proof.public_values.clone())?;
+
+        Ok((proof, output))
+    }
+
+    /// Verifies an epoch change proof followed by an inclusion proof with the keys set up
+    /// at initialization, as `verify_chain` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch_proof` - The proof of the epoch change.
+    /// * `inclusion_proof` - The proof of inclusion in the new epoch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if both proofs were verified and the inclusion proof is
+    /// signed by the validator verifier of the new epoch in that epoch, and `Err` otherwise.
+    pub fn verify_chain(
+        &self,
+        epoch_proof: &SP1ProofWithPublicValues,
+        inclusion_proof: &SP1ProofWithPublicValues,
+    ) -> Result<(), LightClientError> {
+        verify_chain(
+            &self.client,
+            epoch_proof,
+            &self.epoch_keys.1,
+            inclusion_proof,
+            &self.inclusion_keys.1,
+        )
+    }
+
+    fn prove(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        proof_type: ProofType,
+        program: &str,
+    ) -> Result<SP1ProofWithPublicValues, LightClientError> {
+        let _span = tracing::info_span!("prove", program).entered();
+        let start_proving = Instant::now();
+        let proof = proof_type
+            .apply(self.client.prove(pk, stdin))
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: program.to_string(),
+                source: err.into(),
+            })?;
+        record_proof(program, start_proving.elapsed());
+
+        Ok(proof)
+    }
+}
+
+/// Verifies an epoch change proof followed by an inclusion proof, for consumers chaining
+/// them off-chain. The inclusion can only be trusted if it is signed by the validator
+/// verifier the epoch change ratcheted to.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to verify with.
+/// * `epoch_proof` - The proof of the epoch change.
+/// * `epoch_vk` - The verifying key of the epoch change program.
+/// * `inclusion_proof` - The proof of inclusion in the new epoch.
+/// * `inclusion_vk` - The verifying key of the inclusion program.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if both proofs were verified and the inclusion proof is signed
+/// by the validator verifier of the new epoch in that epoch, `HashContinuityError` if it is
+/// signed by another one, `Mismatch` if it is signed in another epoch, and `Err` if a proof
+/// could not be verified or did not prove its statement.
+pub fn verify_chain(
+    client: &ProverClient,
+    epoch_proof: &SP1ProofWithPublicValues,
+    epoch_vk: &SP1VerifyingKey,
+    inclusion_proof: &SP1ProofWithPublicValues,
+    inclusion_vk: &SP1VerifyingKey,
+) -> Result<(), LightClientError> {
+    verify_timed(client, epoch_proof, epoch_vk, "epoch change")?;
+    verify_timed(client, inclusion_proof, inclusion_vk, "inclusion")?;
+
+    let epoch_output =
+        EpochChangeOutput::from_public_values(&mut epoch_proof.public_values.clone())?;
+    let inclusion_output =
+        InclusionOutput::from_public_values(&mut inclusion_proof.public_values.clone())?;
+
+    check_chain(&epoch_output, &inclusion_output)
+}
+
+/// Checks that an inclusion is signed by the validator verifier an epoch change ratcheted
+/// to, in the epoch it ratcheted to. A validator set may be kept across epochs, so its
+/// hash alone does not tell the new epoch from a later one.
+fn check_chain(
+    epoch_output: &EpochChangeOutput,
+    inclusion_output: &InclusionOutput,
+) -> Result<(), LightClientError> {
+    if epoch_output.validator_verifier_hash() != inclusion_output.validator_verifier_hash() {
+        return Err(LightClientError::HashContinuityError {
+            expected: format!("0x{}", hex::encode(epoch_output.validator_verifier_hash())),
+            actual: format!(
+                "0x{}",
+                hex::encode(inclusion_output.validator_verifier_hash())
+            ),
+        });
+    }
+    if inclusion_output.epoch() != &Some(*epoch_output.end_epoch()) {
+        return Err(LightClientError::Mismatch {
+            field: "epoch".to_string(),
+            expected: epoch_output.end_epoch().to_string(),
+            actual: inclusion_output
+                .epoch()
+                .map_or_else(|| "none".to_string(), |epoch| epoch.to_string()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the stdins of the epoch change and inclusion programs for a single `StateProof`,
+/// as returned by an Aptos node to a relayer following the chain.
+///
+/// The fields of the `StateProof` feed the programs as follows:
+///
+/// * `epoch_changes` is the epoch change proof ratcheting `trusted_state`, as extracted by
+///   `epoch_change_assets_from_state_proof`, and is only read by the epoch change program.
+/// * `latest_li_w_sigs` is the ledger info whose signatures the inclusion program verifies,
+///   which must share the transaction accumulator root of the `latest_li` of
+///   `transaction_proof_assets`.
+///
+/// The latest ledger info may be the one ending the epoch, signed by the validators of the
+/// trusted state, or one of the new epoch. Both validator verifiers are then given to the
+/// inclusion program as candidates, and the one it commits tells which of them signed.
+///
+/// # Arguments
+///
+/// * `state_proof` - The state proof starting from the version of the trusted state.
+/// * `trusted_state` - The trusted state to ratchet, serialized with bcs.
+/// * `inclusion_assets` - The assets for the account inclusion in the state tree.
+/// * `transaction_proof_assets` - The assets for the transaction inclusion in the
+///   accumulator, which a `StateProof` does not carry.
+///
+/// # Returns
+///
+/// A `Result` containing the epoch change and inclusion stdins, in that order, or `Err` if
+/// the trusted state can not be ratcheted with the epoch changes, or if an asset could not
+/// be serialized or is empty.
+pub fn stdins_from_state_proof(
+    state_proof: &StateProof,
+    trusted_state: &[u8],
+    inclusion_assets: &SparseMerkleProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+) -> Result<(SP1Stdin, SP1Stdin), LightClientError> {
+    let epoch_change_proof = epoch_change_assets_from_state_proof(state_proof)?;
+    let next_verifier = next_validator_verifier(trusted_state, &epoch_change_proof)?;
+    let validator_verifiers = match TrustedState::from_bytes(trusted_state) {
+        Ok(TrustedState::EpochState { epoch_state, .. }) => {
+            vec![epoch_state.verifier.to_bytes(), next_verifier.to_bytes()]
+        }
+        // Ratcheted, so the trusted state was deserialized and carries an epoch
+        _ => unreachable!("ratcheted from a trusted state without an epoch"),
+    };
+
+    let signed_ledger_info =
+        bcs::to_bytes(state_proof.latest_ledger_info_w_sigs()).map_err(|err| {
+            LightClientError::SerializationError {
+                structure: "LedgerInfoWithSignatures".to_string(),
+                source: err.into(),
+            }
+        })?;
+    let inclusion_stdin = inclusion::generate_stdin(
+        inclusion_assets,
+        &transaction_proof_assets
+            .clone()
+            .with_signed_ledger_info(signed_ledger_info),
+        &ValidatorVerifierAssets::new_multi(validator_verifiers)?,
+    )?;
+
+    Ok((
+        epoch_change::generate_stdin(trusted_state, &epoch_change_proof),
+        inclusion_stdin,
+    ))
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use super::*;
+    use crate::fixture::{generate_fixture, generate_proving_assets, AptosWrapperFixture};
+    use crate::inclusion::assert_inclusion_output;
+    use crate::types::{EpochChangeData, InclusionData};
+    use sp1_sdk::SP1PublicValues;
+
+    #[test]
+    fn test_prove_through_light_client() {
+        // The mock prover only binds the public values to Plonk proofs
+        let light_client = LightClient::with_client(ProverClient::mock()).unwrap();
+
+        let mut fixture = generate_fixture();
+        let proving_assets = generate_proving_assets(&fixture);
+        let (proof, output) = light_client
+            .prove_inclusion(&proving_assets, ProofType::Plonk)
+            .unwrap();
+        assert_inclusion_output(&output, &proving_assets.expected_inclusion().unwrap()).unwrap();
+        light_client
+            .client()
+            .verify(&proof, &light_client.inclusion_keys().1)
+            .unwrap();
+
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.epoch_change_data().unwrap();
+        let inputs = EpochChangeInputs::new(trusted_state, epoch_change_proof);
+        let (proof, output) = light_client
+            .prove_epoch_change(&inputs, ProofType::Plonk)
+            .unwrap();
+        assert_eq!(output.end_epoch(), &(output.start_epoch() + 1));
+        light_client
+            .client()
+            .verify(&proof, &light_client.epoch_keys().1)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let light_client = LightClient::with_client(ProverClient::mock()).unwrap();
+
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let EpochChangeData {
+            trusted_state,
+            epoch_change_proof,
+        } = fixture.next_epoch_change_data().unwrap();
+        let (epoch_proof, _) = light_client
+            .prove_epoch_change(
+                &EpochChangeInputs::new(trusted_state, epoch_change_proof),
+                ProofType::Plonk,
+            )
+            .unwrap();
+
+        // An inclusion in the new epoch is signed by the validators it ratcheted to
+        fixture.generate_traffic().unwrap();
+        let proving_assets = generate_proving_assets(&fixture);
+        let (inclusion_proof, inclusion_output) = light_client
+            .prove_inclusion(&proving_assets, ProofType::Plonk)
+            .unwrap();
+        light_client
+            .verify_chain(&epoch_proof, &inclusion_proof)
+            .unwrap();
+
+        // The same validators ratcheted to in another epoch
+        let mut public_values = epoch_proof.public_values.to_vec();
+        let end_epoch = public_values.len() - 8;
+        let other_epoch = inclusion_output.epoch().unwrap() + 1;
+        public_values[end_epoch..].copy_from_slice(&other_epoch.to_le_bytes());
+        let epoch_output =
+            EpochChangeOutput::from_public_values(&mut SP1PublicValues::from(&public_values))
+                .unwrap();
+        match check_chain(&epoch_output, &inclusion_output) {
+            Err(LightClientError::Mismatch { field, .. }) => assert_eq!(field, "epoch"),
+            other => panic!("expected an epoch mismatch, got {other:?}"),
+        }
+
+        // Another chain has other validators
+        let mut other_fixture = AptosWrapperFixture::new(2, 5, 4).unwrap();
+        other_fixture.generate_traffic().unwrap();
+        let proving_assets = generate_proving_assets(&other_fixture);
+        let (other_inclusion_proof, _) = light_client
+            .prove_inclusion(&proving_assets, ProofType::Plonk)
+            .unwrap();
+        assert!(matches!(
+            light_client.verify_chain(&epoch_proof, &other_inclusion_proof),
+            Err(LightClientError::HashContinuityError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stdins_from_state_proof() {
+        let light_client = LightClient::with_client(ProverClient::mock()).unwrap();
+
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3).unwrap();
+        let start_epoch = *fixture.aptos_wrapper().current_epoch();
+        let trusted_state = fixture.trusted_state().unwrap();
+        let trusted_state_version = *fixture.aptos_wrapper().current_version();
+        fixture.generate_traffic().unwrap();
+        let state_proof = fixture.new_state_proof(trusted_state_version).unwrap();
+        let InclusionData {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            ..
+        } = fixture.inclusion_data(1).unwrap();
+
+        let (epoch_stdin, inclusion_stdin) = stdins_from_state_proof(
+            &state_proof,
+            &trusted_state,
+            &sparse_merkle_proof_assets,
+            &transaction_proof_assets,
+        )
+        .unwrap();
+
+        let client = light_client.client();
+        let mut epoch_proof = client
+            .prove(&light_client.epoch_keys().0, epoch_stdin)
+            .plonk()
+            .run()
+            .unwrap();
+        client
+            .verify(&epoch_proof, &light_client.epoch_keys().1)
+            .unwrap();
+        let output = EpochChangeOutput::from_public_values(&mut epoch_proof.public_values).unwrap();
+        assert_eq!(*output.end_epoch(), start_epoch + 1);
+
+        let mut inclusion_proof = client
+            .prove(&light_client.inclusion_keys().0, inclusion_stdin)
+            .plonk()
+            .run()
+            .unwrap();
+        client
+            .verify(&inclusion_proof, &light_client.inclusion_keys().1)
+            .unwrap();
+        let output =
+            InclusionOutput::from_public_values(&mut inclusion_proof.public_values).unwrap();
+        assert_eq!(output.key(), sparse_merkle_proof_assets.leaf_key());
+    }
+}
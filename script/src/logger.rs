@@ -0,0 +1,146 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in logging setup for the binaries.
+//!
+//! The library never installs a global logger itself, so that its callers stay in
+//! control of their own.
+
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::ValueEnum;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Verbosity of the logs emitted while executing or proving the programs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    /// No logger is installed.
+    Off,
+    /// Only errors are printed.
+    Error,
+    /// Errors and progress information are printed.
+    #[default]
+    Info,
+    /// Everything down to debugging information is printed.
+    Debug,
+}
+
+impl LogLevel {
+    const fn as_directive(self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+/// Whether the binaries only print their machine-readable results to stdout.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether the binaries only print their machine-readable results to stdout. Must be
+/// called before `setup_logger`, so that the logs are then printed to stderr.
+///
+/// # Arguments
+///
+/// * `quiet` - Whether to route the informational messages through the logs.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Prints an informational message of the binaries to stdout, or logs it in quiet mode.
+///
+/// # Arguments
+///
+/// * `message` - The message to print.
+pub fn print_info(message: impl Display) {
+    let quiet = QUIET.load(Ordering::Relaxed);
+    write_info(&mut std::io::stdout(), quiet, message);
+}
+
+fn write_info(out: &mut impl Write, quiet: bool, message: impl Display) {
+    if quiet {
+        tracing::info!("{message}");
+    } else {
+        // Printing is best effort, as for `println!` on a closed stdout
+        let _ = writeln!(out, "{message}");
+    }
+}
+
+/// Installs a global logger printing the logs up to the given level, to stdout or to
+/// stderr in quiet mode.
+///
+/// Nothing is done if the level is `LogLevel::Off` or if a global logger has already been
+/// installed, e.g. by the caller of the library.
+///
+/// # Arguments
+///
+/// * `level` - The maximum level of the logs to print.
+pub fn setup_logger(level: LogLevel) {
+    if level == LogLevel::Off || tracing::dispatcher::has_been_set() {
+        return;
+    }
+
+    // Mirror the filter of `sp1_sdk::utils::setup_logger`, which silences the noisiest
+    // dependencies of the prover.
+    let env_filter = ["hyper", "p3_keccak_air", "p3_fri", "p3_dft", "p3_challenger"]
+        .into_iter()
+        .fold(EnvFilter::new(level.as_directive()), |filter, target| {
+            filter.add_directive(format!("{target}=off").parse().unwrap())
+        });
+
+    let subscriber = tracing_subscriber::fmt()
+        .compact()
+        .with_file(false)
+        .with_target(false)
+        .with_thread_names(false)
+        .with_env_filter(env_filter)
+        .with_span_events(FmtSpan::CLOSE);
+
+    // Another thread may have installed a logger in the meantime, which is fine.
+    let _ = if QUIET.load(Ordering::Relaxed) {
+        subscriber.with_writer(std::io::stderr).try_init()
+    } else {
+        subscriber.try_init()
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_setup_logger_off_installs_nothing() {
+        setup_logger(LogLevel::Off);
+
+        assert!(!tracing::dispatcher::has_been_set());
+    }
+
+    #[test]
+    fn test_quiet_only_prints_results() {
+        let json_output = r#"{"nbr_leaves":32,"proving_time":1000,"verifying_time":30}"#;
+
+        let mut stdout = vec![];
+        write_info(&mut stdout, true, "Successfully generated proof!");
+        writeln!(stdout, "{json_output}").unwrap();
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!("{json_output}\n")
+        );
+
+        let mut stdout = vec![];
+        write_info(&mut stdout, false, "Successfully generated proof!");
+        writeln!(stdout, "{json_output}").unwrap();
+        assert_eq!(
+            String::from_utf8(stdout)
+                .unwrap()
+                .lines()
+                .collect::<Vec<_>>(),
+            ["Successfully generated proof!", json_output]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmark metrics, written by the inclusion benchmark with `--metrics-out` and compared
+//! between two runs by the `compare` binary, e.g. before and after a change.
+
+use std::fs;
+use std::path::Path;
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::error::LightClientError;
+
+/// Metrics of the proofs over a fixture with a given number of leaves. Times are in
+/// milliseconds, and are medians over repeated proofs.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct BenchMetrics {
+    /// Number of leaves of the fixture.
+    nbr_leaves: usize,
+    /// Time spent proving.
+    proving_time: u128,
+    /// Time spent verifying.
+    verifying_time: u128,
+    /// Number of cycles of an execution of the program.
+    cycles: u64,
+    /// Size of a proof, as returned by `proof_size_bytes`. Absent from the metrics written
+    /// before it was measured.
+    #[serde(default)]
+    proof_bytes: usize,
+}
+
+impl BenchMetrics {
+    pub const fn new(
+        nbr_leaves: usize,
+        proving_time: u128,
+        verifying_time: u128,
+        cycles: u64,
+        proof_bytes: usize,
+    ) -> Self {
+        Self {
+            nbr_leaves,
+            proving_time,
+            verifying_time,
+            cycles,
+            proof_bytes,
+        }
+    }
+}
+
+/// Writes metrics to a file, one JSON object per line.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to write to.
+/// * `metrics` - The metrics to write, one line each.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the file was written, and `Err` otherwise.
+pub fn write_metrics(
+    path: impl AsRef<Path>,
+    metrics: &[BenchMetrics],
+) -> Result<(), LightClientError> {
+    let path = path.as_ref();
+    let mut lines = String::new();
+    for metrics in metrics {
+        let line =
+            serde_json::to_string(metrics).map_err(|err| LightClientError::SerializationError {
+                structure: "BenchMetrics".to_string(),
+                source: err.into(),
+            })?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+
+    fs::write(path, lines).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })
+}
+
+/// Reads metrics previously written with `write_metrics`. Blank lines are skipped.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read from.
+///
+/// # Returns
+///
+/// A `Result` containing the metrics in the order of the file, or `Err` if the file could
+/// not be read or if a line is not a JSON object of metrics.
+pub fn read_metrics(path: impl AsRef<Path>) -> Result<Vec<BenchMetrics>, LightClientError> {
+    let path = path.as_ref();
+    let lines = fs::read_to_string(path).map_err(|err| LightClientError::IoError {
+        path: path.display().to_string(),
+        source: err,
+    })?;
+
+    lines
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|err| LightClientError::DeserializationError {
+                structure: "BenchMetrics".to_string(),
+                source: err.into(),
+            })
+        })
+        .collect()
+}
+
+/// Relative changes, in percent, of the metrics of a number of leaves between two runs.
+/// A positive change means the second run is slower, or uses more cycles.
+#[derive(Clone, Debug, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct MetricsDelta {
+    /// Number of leaves of the fixture.
+    nbr_leaves: usize,
+    /// Change of the proving time.
+    proving_time: f64,
+    /// Change of the verifying time.
+    verifying_time: f64,
+    /// Change of the number of cycles.
+    cycles: f64,
+}
+
+impl MetricsDelta {
+    /// Returns whether any of the metrics increased by more than a threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The largest accepted increase, in percent.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the proving time, the verifying time or the cycles increased by more than
+    /// `threshold` percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        [self.proving_time, self.verifying_time, self.cycles]
+            .iter()
+            .any(|change| *change > threshold)
+    }
+}
+
+/// Comparison of the metrics of two runs, matched by number of leaves.
+#[derive(Clone, Debug, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct MetricsComparison {
+    /// Changes for the numbers of leaves measured by both runs, in the order of the first.
+    deltas: Vec<MetricsDelta>,
+    /// Numbers of leaves only measured by the first run.
+    only_before: Vec<usize>,
+    /// Numbers of leaves only measured by the second run.
+    only_after: Vec<usize>,
+}
+
+/// Compares the metrics of two runs. Numbers of leaves measured by a single run can not be
+/// compared, so they are listed apart rather than failing the comparison.
+///
+/// # Arguments
+///
+/// * `before` - The metrics of the reference run.
+/// * `after` - The metrics of the run to compare to the reference.
+///
+/// # Returns
+///
+/// The `MetricsComparison` of the two runs.
+pub fn compare_metrics(before: &[BenchMetrics], after: &[BenchMetrics]) -> MetricsComparison {
+    let find = |metrics: &[BenchMetrics], nbr_leaves| {
+        metrics
+            .iter()
+            .find(|metrics| metrics.nbr_leaves == nbr_leaves)
+            .cloned()
+    };
+
+    let mut deltas = vec![];
+    let mut only_before = vec![];
+    for before in before {
+        match find(after, before.nbr_leaves) {
+            Some(after) => deltas.push(MetricsDelta {
+                nbr_leaves: before.nbr_leaves,
+                proving_time: percent_change(before.proving_time as f64, after.proving_time as f64),
+                verifying_time: percent_change(
+                    before.verifying_time as f64,
+                    after.verifying_time as f64,
+                ),
+                cycles: percent_change(before.cycles as f64, after.cycles as f64),
+            }),
+            None => only_before.push(before.nbr_leaves),
+        }
+    }
+    let only_after = after
+        .iter()
+        .filter(|after| find(before, after.nbr_leaves).is_none())
+        .map(|after| after.nbr_leaves)
+        .collect();
+
+    MetricsComparison {
+        deltas,
+        only_before,
+        only_after,
+    }
+}
+
+/// Returns the change from `before` to `after`, in percent of `before`.
+fn percent_change(before: f64, after: f64) -> f64 {
+    // Below the resolution of the measure, any increase is unbounded
+    if before == 0.0 {
+        return if after == 0.0 { 0.0 } else { f64::INFINITY };
+    }
+
+    (after - before) / before * 100.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compare_metrics_files() {
+        let before_path = std::env::temp_dir().join("aptos-lc-test-metrics-before.jsonl");
+        let after_path = std::env::temp_dir().join("aptos-lc-test-metrics-after.jsonl");
+        write_metrics(
+            &before_path,
+            &[
+                BenchMetrics::new(32, 1000, 100, 2000, 1024),
+                BenchMetrics::new(128, 2000, 100, 4000, 1024),
+                BenchMetrics::new(2048, 4000, 100, 8000, 1024),
+            ],
+        )
+        .unwrap();
+        write_metrics(
+            &after_path,
+            &[
+                BenchMetrics::new(128, 3000, 90, 4000, 1024),
+                BenchMetrics::new(32, 1050, 100, 1000, 1024),
+                BenchMetrics::new(8192, 8000, 100, 16000, 1024),
+            ],
+        )
+        .unwrap();
+
+        let before = read_metrics(&before_path).unwrap();
+        let after = read_metrics(&after_path).unwrap();
+        fs::remove_file(&before_path).unwrap();
+        fs::remove_file(&after_path).unwrap();
+
+        let comparison = compare_metrics(&before, &after);
+        assert_eq!(
+            comparison.deltas(),
+            &[
+                MetricsDelta {
+                    nbr_leaves: 32,
+                    proving_time: 5.0,
+                    verifying_time: 0.0,
+                    cycles: -50.0,
+                },
+                MetricsDelta {
+                    nbr_leaves: 128,
+                    proving_time: 50.0,
+                    verifying_time: -10.0,
+                    cycles: 0.0,
+                },
+            ]
+        );
+        assert_eq!(comparison.only_before(), &[2048]);
+        assert_eq!(comparison.only_after(), &[8192]);
+
+        assert!(!comparison.deltas()[0].is_regression(10.0));
+        assert!(comparison.deltas()[0].is_regression(1.0));
+        assert!(comparison.deltas()[1].is_regression(10.0));
+    }
+
+    #[test]
+    fn test_percent_change_from_zero() {
+        assert_eq!(percent_change(0.0, 0.0), 0.0);
+        assert_eq!(percent_change(0.0, 1.0), f64::INFINITY);
+        assert_eq!(percent_change(4.0, 3.0), -25.0);
+    }
+
+    #[test]
+    fn test_read_invalid_metrics() {
+        let path = std::env::temp_dir().join("aptos-lc-test-metrics-invalid.jsonl");
+        fs::write(&path, "{\"nbr_leaves\": 32}\n").unwrap();
+        let result = read_metrics(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(LightClientError::DeserializationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_metrics_without_proof_bytes() {
+        let path = std::env::temp_dir().join("aptos-lc-test-metrics-without-proof-bytes.jsonl");
+        fs::write(
+            &path,
+            "{\"nbr_leaves\":32,\"proving_time\":1000,\"verifying_time\":100,\"cycles\":2000}\n",
+        )
+        .unwrap();
+        let metrics = read_metrics(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(metrics, vec![BenchMetrics::new(32, 1000, 100, 2000, 0)]);
+    }
+}
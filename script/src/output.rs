@@ -0,0 +1,568 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers to read the public values committed by the light client programs.
+
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use aptos_lc_core::types::output::{
+    EPOCH_CHANGE_OUTPUT_MAGIC, INCLUSION_OUTPUT_MAGIC, OUTPUT_MAGIC_LEN, SIGNATURE_OUTPUT_MAGIC,
+};
+use getset::Getters;
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer};
+use sp1_sdk::SP1PublicValues;
+
+use crate::error::LightClientError;
+
+/// Reads a hash from the public values, failing rather than panicking when fewer bytes
+/// remain, e.g. when the program took a failure path committing fewer values.
+///
+/// # Arguments
+///
+/// * `public_values` - The public values committed by the program.
+///
+/// # Returns
+///
+/// A `Result` containing the hash, or `TruncatedOutput` if fewer than 32 bytes remain.
+pub fn try_read_hash(public_values: &mut SP1PublicValues) -> Result<[u8; 32], LightClientError> {
+    try_read(public_values)
+}
+
+/// Reads a fixed-size value from the public values, failing rather than panicking when
+/// fewer bytes remain than its size.
+pub(crate) fn try_read<T: Serialize + DeserializeOwned>(
+    public_values: &mut SP1PublicValues,
+) -> Result<T, LightClientError> {
+    // `SP1PublicValues` does not expose its read position, so the remaining length can only
+    // be checked by the read itself. It panics before moving the position, leaving the
+    // public values as they were.
+    catch_unwind(AssertUnwindSafe(|| public_values.read())).map_err(|_| {
+        LightClientError::TruncatedOutput {
+            expected: std::mem::size_of::<T>(),
+        }
+    })
+}
+
+/// Reader of the values committed by a program, in the order they were committed.
+///
+/// `SP1PublicValues` does not expose its read position, so the reader starts from the
+/// first committed byte and keeps its own position. A read past the committed bytes fails
+/// with `TruncatedOutput` and leaves the position where it was.
+#[derive(Clone, Debug)]
+pub struct PublicValuesReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PublicValuesReader<'a> {
+    /// Creates a reader positioned at the first value committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values committed by the program.
+    ///
+    /// # Returns
+    ///
+    /// A new `PublicValuesReader`.
+    pub fn new(public_values: &'a SP1PublicValues) -> Self {
+        Self {
+            bytes: public_values.as_slice(),
+            position: 0,
+        }
+    }
+
+    /// Returns the number of bytes read so far.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes left to read.
+    pub const fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Reads the next `N` bytes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the bytes, or `TruncatedOutput` if fewer than `N` remain.
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], LightClientError> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + N)
+            .ok_or(LightClientError::TruncatedOutput { expected: N })?;
+        self.position += N;
+
+        Ok(bytes.try_into().expect("Slice has the length of the array"))
+    }
+
+    /// Reads a hash.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the hash, or `TruncatedOutput` if fewer than 32 bytes remain.
+    pub fn read_hash(&mut self) -> Result<[u8; 32], LightClientError> {
+        self.read_array()
+    }
+
+    /// Reads an integer committed as 8 little-endian bytes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the integer, or `TruncatedOutput` if fewer than 8 bytes remain.
+    pub fn read_u64(&mut self) -> Result<u64, LightClientError> {
+        self.read_array().map(u64::from_le_bytes)
+    }
+
+    /// Reads a single byte, as committed for the statuses and the enums.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the byte, or `TruncatedOutput` if no byte remains.
+    pub fn read_byte(&mut self) -> Result<u8, LightClientError> {
+        self.read_array().map(|[byte]| byte)
+    }
+
+    /// Reads a list of hashes, committed as its length followed by the hashes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the hashes, or `TruncatedOutput` if fewer bytes remain than
+    /// the length announces. No byte is consumed on failure.
+    pub fn read_hashes(&mut self) -> Result<Vec<[u8; 32]>, LightClientError> {
+        let start = self.position;
+        let len = self.read_u64()?;
+        if len > (self.remaining() / 32) as u64 {
+            self.position = start;
+            return Err(LightClientError::TruncatedOutput {
+                expected: (len as usize).saturating_mul(32).saturating_add(8),
+            });
+        }
+
+        (0..len).map(|_| self.read_hash()).collect()
+    }
+
+    /// Reads a list of bytes, committed as its length followed by the bytes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the bytes, or `TruncatedOutput` if fewer bytes remain than
+    /// the length announces. No byte is consumed on failure.
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, LightClientError> {
+        let start = self.position;
+        let len = self.read_u64()?;
+        if len > self.remaining() as u64 {
+            self.position = start;
+            return Err(LightClientError::TruncatedOutput {
+                expected: (len as usize).saturating_add(8),
+            });
+        }
+
+        let bytes = self.bytes[self.position..self.position + len as usize].to_vec();
+        self.position += len as usize;
+        Ok(bytes)
+    }
+
+    /// Reads the magic committed first by a program and checks it against the expected one.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - The magic of the program the public values should come from.
+    /// * `program` - The name of that program, used in the error.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the magic matches, and `Err` otherwise.
+    pub fn read_magic(
+        &mut self,
+        expected: [u8; OUTPUT_MAGIC_LEN],
+        program: &str,
+    ) -> Result<(), LightClientError> {
+        let actual = self.read_array()?;
+
+        if actual != expected {
+            return Err(LightClientError::WrongProgramOutput {
+                program: program.to_string(),
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes a hash as a `0x`-prefixed hex string, so that the outputs of the programs
+/// read as they are printed elsewhere rather than as arrays of bytes.
+pub(crate) fn serialize_hex<S: Serializer>(
+    hash: &[u8; 32],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(hash)))
+}
+
+/// Serializes an optional hash as `serialize_hex` does, or as `null` if absent.
+pub(crate) fn serialize_hex_option<S: Serializer>(
+    hash: &Option<[u8; 32]>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match hash {
+        Some(hash) => serialize_hex(hash, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Formats the output of a program as JSON, for the binaries to print.
+///
+/// # Arguments
+///
+/// * `output` - The output read from the public values, e.g. an `InclusionOutput`.
+/// * `pretty` - Whether to indent the JSON over several lines, rather than on a single one.
+///
+/// # Returns
+///
+/// The JSON of the output.
+pub fn output_json(output: &impl Serialize, pretty: bool) -> String {
+    // The outputs only hold hashes, integers and enums, which always serialize
+    if pretty {
+        serde_json::to_string_pretty(output)
+    } else {
+        serde_json::to_string(output)
+    }
+    .expect("Program outputs serialize to JSON")
+}
+
+/// Labels and lengths in bytes of the values committed by the inclusion program, in order.
+const INCLUSION_OUTPUT_SCHEMA: &[(&str, usize)] = &[
+    ("magic", OUTPUT_MAGIC_LEN),
+    ("status", 1),
+    ("validator verifier hash", 32),
+    ("state root", 32),
+    ("block id", 32),
+    ("key", 32),
+    ("value", 32),
+    ("leaf kind", 1),
+    ("accumulator leaves", 8),
+    ("epoch", 8),
+    ("validator verifier index", 1),
+    ("transaction hash", 32),
+];
+
+/// Labels and lengths in bytes of the values committed by the epoch change program, in order.
+const EPOCH_CHANGE_OUTPUT_SCHEMA: &[(&str, usize)] = &[
+    ("magic", OUTPUT_MAGIC_LEN),
+    ("status", 1),
+    ("previous validator verifier hash", 32),
+    ("validator verifier hash", 32),
+    ("start epoch", 8),
+    ("end epoch", 8),
+];
+
+/// Labels and lengths in bytes of the values committed by the signature program, in order.
+const SIGNATURE_OUTPUT_SCHEMA: &[(&str, usize)] = &[
+    ("magic", OUTPUT_MAGIC_LEN),
+    ("status", 1),
+    ("validator verifier hash", 32),
+    ("block hash", 32),
+];
+
+/// A value differing between two sets of public values.
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct FieldDiff {
+    /// Label of the value in the layout of the program.
+    field: String,
+    /// Hex of the value in the first public values, empty if missing.
+    left: String,
+    /// Hex of the value in the second public values, empty if missing.
+    right: String,
+}
+
+impl FieldDiff {
+    fn new(field: &str, left: &[u8], right: &[u8]) -> Self {
+        let to_hex = |bytes: &[u8]| {
+            if bytes.is_empty() {
+                String::new()
+            } else {
+                format!("0x{}", hex::encode(bytes))
+            }
+        };
+
+        Self {
+            field: field.to_string(),
+            left: to_hex(left),
+            right: to_hex(right),
+        }
+    }
+}
+
+impl Display for FieldDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let or_missing = |value: &str| {
+            if value.is_empty() {
+                "<missing>".to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        write!(
+            f,
+            "{}: {} != {}",
+            self.field,
+            or_missing(&self.left),
+            or_missing(&self.right)
+        )
+    }
+}
+
+/// Returns the layout of the public values of the program whose magic they start with, or
+/// an empty one if no program matches.
+fn output_schema(bytes: &[u8]) -> &'static [(&'static str, usize)] {
+    match bytes.get(..OUTPUT_MAGIC_LEN) {
+        Some(magic) if magic == INCLUSION_OUTPUT_MAGIC => INCLUSION_OUTPUT_SCHEMA,
+        Some(magic) if magic == EPOCH_CHANGE_OUTPUT_MAGIC => EPOCH_CHANGE_OUTPUT_SCHEMA,
+        Some(magic) if magic == SIGNATURE_OUTPUT_MAGIC => SIGNATURE_OUTPUT_SCHEMA,
+        _ => &[],
+    }
+}
+
+/// Returns the bytes of `bytes` in `start..start + len`, truncated to those available.
+fn field_bytes(bytes: &[u8], start: usize, len: usize) -> &[u8] {
+    let end = (start + len).min(bytes.len());
+    &bytes[start.min(end)..end]
+}
+
+/// Compares two sets of public values value by value, e.g. those committed by a local
+/// execution and those of a proof generated on the network.
+///
+/// The values are labelled after the layout of the program told by the magic. Values
+/// missing on one side, such as those skipped by a failure path, are reported as differing,
+/// and any bytes beyond the layout are compared as a whole.
+///
+/// # Arguments
+///
+/// * `a` - The first public values.
+/// * `b` - The second public values.
+///
+/// # Returns
+///
+/// The values that differ, in the order they were committed. Empty if the public values
+/// are equal.
+pub fn diff_public_values(a: &SP1PublicValues, b: &SP1PublicValues) -> Vec<FieldDiff> {
+    let (a, b) = (a.as_slice(), b.as_slice());
+    // Either side may carry a corrupted magic, so the layout is taken from the other one
+    let schema = match output_schema(a) {
+        [] => output_schema(b),
+        schema => schema,
+    };
+
+    let mut diffs = vec![];
+    let mut offset = 0;
+    for (field, len) in schema {
+        let (left, right) = (field_bytes(a, offset, *len), field_bytes(b, offset, *len));
+        if left != right {
+            diffs.push(FieldDiff::new(field, left, right));
+        }
+        offset += len;
+    }
+
+    let (left, right) = (
+        field_bytes(a, offset, a.len()),
+        field_bytes(b, offset, b.len()),
+    );
+    if left != right {
+        let field = if schema.is_empty() {
+            "public values"
+        } else {
+            "trailing bytes"
+        };
+        diffs.push(FieldDiff::new(field, left, right));
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::inclusion::InclusionOutput;
+    use crate::signature::SignatureOutput;
+
+    #[test]
+    fn test_try_read_hash_truncated() {
+        let mut public_values = SP1PublicValues::from(&[7; 40]);
+
+        assert_eq!(try_read_hash(&mut public_values).unwrap(), [7; 32]);
+        // Only 8 bytes remain, which is not enough for a second hash
+        assert!(matches!(
+            try_read_hash(&mut public_values),
+            Err(LightClientError::TruncatedOutput { expected: 32 })
+        ));
+        // The failed read did not consume the remaining bytes
+        assert_eq!(
+            try_read::<u64>(&mut public_values).unwrap(),
+            u64::from_le_bytes([7; 8])
+        );
+    }
+
+    #[test]
+    fn test_read_magic_truncated() {
+        let public_values = SP1PublicValues::from(b"IN");
+
+        assert!(matches!(
+            PublicValuesReader::new(&public_values).read_magic(INCLUSION_OUTPUT_MAGIC, "inclusion"),
+            Err(LightClientError::TruncatedOutput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reader_mixed_reads() {
+        let mut bytes = vec![3];
+        bytes.extend([7; 32]);
+        bytes.extend(42u64.to_le_bytes());
+        bytes.extend(2u64.to_le_bytes());
+        bytes.extend([[1; 32], [2; 32]].concat());
+        bytes.push(9);
+        let public_values = SP1PublicValues::from(&bytes);
+
+        let mut reader = PublicValuesReader::new(&public_values);
+        assert_eq!(reader.read_byte().unwrap(), 3);
+        assert_eq!(reader.read_hash().unwrap(), [7; 32]);
+        assert_eq!(reader.read_u64().unwrap(), 42);
+        assert_eq!(reader.read_hashes().unwrap(), vec![[1; 32], [2; 32]]);
+        assert_eq!(reader.position(), 1 + 32 + 8 + 8 + 64);
+        assert_eq!(reader.read_byte().unwrap(), 9);
+        assert_eq!(reader.remaining(), 0);
+    }
+
+    #[test]
+    fn test_reader_over_read() {
+        let public_values = SP1PublicValues::from(&[5; 12]);
+
+        let mut reader = PublicValuesReader::new(&public_values);
+        assert!(matches!(
+            reader.read_hash(),
+            Err(LightClientError::TruncatedOutput { expected: 32 })
+        ));
+        // The length read first announces more hashes than committed
+        assert!(matches!(
+            reader.read_hashes(),
+            Err(LightClientError::TruncatedOutput { .. })
+        ));
+        // The failed reads did not move the position
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read_u64().unwrap(), u64::from_le_bytes([5; 8]));
+        assert!(matches!(
+            reader.read_u64(),
+            Err(LightClientError::TruncatedOutput { expected: 8 })
+        ));
+        assert_eq!(reader.remaining(), 4);
+    }
+
+    #[test]
+    fn test_typed_reader_truncated() {
+        // The magic, the status and a single hash, where the signature program commits two
+        let mut bytes = SIGNATURE_OUTPUT_MAGIC.to_vec();
+        bytes.push(0);
+        bytes.extend([0; 32]);
+        let mut public_values = SP1PublicValues::from(&bytes);
+
+        assert!(matches!(
+            SignatureOutput::from_public_values(&mut public_values),
+            Err(LightClientError::TruncatedOutput { expected: 32 })
+        ));
+    }
+
+    fn inclusion_public_values() -> Vec<u8> {
+        let mut bytes = INCLUSION_OUTPUT_MAGIC.to_vec();
+        bytes.push(0);
+        for hash in 1..=5 {
+            bytes.extend([hash; 32]);
+        }
+        bytes.push(0);
+        bytes.extend(8u64.to_le_bytes());
+        bytes.extend(1u64.to_le_bytes());
+        bytes.push(0);
+        bytes.extend([6; 32]);
+        // No key preimage, and the signatures were verified
+        bytes.push(0);
+        bytes.extend([0; 32]);
+        bytes.extend(0u64.to_le_bytes());
+        bytes.push(0);
+        bytes.extend(7u64.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_output_json() {
+        let mut public_values = SP1PublicValues::from(&inclusion_public_values());
+        let output = InclusionOutput::from_public_values(&mut public_values).unwrap();
+        let hex_field =
+            |field: &str, byte: u8| format!("\"{field}\":\"0x{}\"", hex::encode([byte; 32]));
+
+        let compact = output_json(&output, false);
+        assert!(!compact.contains('\n'));
+        assert!(compact.contains(&hex_field("state_hash", 2)));
+        assert!(compact.contains(&hex_field("transaction_hash", 6)));
+
+        let pretty = output_json(&output, true);
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_diff_public_values() {
+        let left = SP1PublicValues::from(&inclusion_public_values());
+        assert!(diff_public_values(&left, &left).is_empty());
+
+        let mut bytes = inclusion_public_values();
+        // Last byte of the state root
+        bytes[OUTPUT_MAGIC_LEN + 1 + 2 * 32 - 1] = 0xff;
+        let right = SP1PublicValues::from(&bytes);
+
+        let diffs = diff_public_values(&left, &right);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].field(), "state root");
+        assert_eq!(diffs[0].left(), &format!("0x{}", hex::encode([2; 32])));
+        assert!(diffs[0].right().ends_with("ff"));
+    }
+
+    #[test]
+    fn test_diff_public_values_failure_path() {
+        let left = SP1PublicValues::from(&inclusion_public_values());
+        // A failure path only commits the magic and a status
+        let mut bytes = INCLUSION_OUTPUT_MAGIC.to_vec();
+        bytes.push(1);
+        let right = SP1PublicValues::from(&bytes);
+
+        let fields = diff_public_values(&left, &right)
+            .iter()
+            .map(|diff| diff.field().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                "status",
+                "validator verifier hash",
+                "state root",
+                "block id",
+                "key",
+                "value",
+                "leaf kind",
+                "accumulator leaves",
+                "epoch",
+                "validator verifier index",
+                "transaction hash",
+                "trailing bytes"
+            ]
+        );
+        assert!(diff_public_values(&left, &right)[2]
+            .to_string()
+            .ends_with("<missing>"));
+    }
+}
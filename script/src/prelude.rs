@@ -0,0 +1,37 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-exports of the types and functions most commonly needed to prove and verify with the
+//! light client programs. They are also available from the crate root.
+//!
+//! ```
+//! use aptos_lc_script::prelude::*;
+//!
+//! let validator_verifier_assets = ValidatorVerifierAssets::new(vec![1, 2, 3]);
+//! assert_eq!(validator_verifier_assets.validator_verifier(), &vec![1, 2, 3]);
+//! ```
+
+pub use crate::bridge::{prove_for_bridge, BridgeProof};
+pub use crate::bundle::{ProofBundle, ProofMeta};
+pub use crate::client::{
+    prove_with_timeout, verify_many, verify_timed, verify_with_vk_bytes, ProofType,
+};
+pub use crate::epoch_change::{
+    diff_validator_verifiers, ensure_epoch_state, next_validator_verifier, EpochChangeInputs,
+    EpochChangeOutput, ValidatorChange, ValidatorSetDiff,
+};
+pub use crate::error::LightClientError;
+pub use crate::inclusion::{
+    assert_accumulator_leaves, assert_block_in_checkpoints, assert_fresh, assert_inclusion_output,
+    assert_self_consistent, assert_signatures_verified, assert_transaction_hash,
+    execute_and_read_inclusion, expected_from_proof, generate_batch_stdin, generate_event_stdin,
+    prove_inclusion_with_cancel, run_inclusion_guest, try_execute_inclusion,
+    AccountStateProofAssets, BatchInclusionOutput, BatchInclusionProofAssets, EventOutput,
+    EventProofAssets, ExecutionOutcome, ExpectedInclusion, InclusionExecutionReport,
+    InclusionOutput, MultiTransactionOutput, MultiTransactionProofAssets, ProvingAssets,
+    SparseMerkleProofAssets, StateKeyPreimage, TransactionProofAssets, ValidatorVerifierAssets,
+};
+pub use crate::light_client::{stdins_from_state_proof, verify_chain, LightClient};
+pub use crate::output::{diff_public_values, try_read_hash, FieldDiff, PublicValuesReader};
+pub use crate::signature::SignatureOutput;
+pub use crate::types::{EpochChangeData, InclusionData};
@@ -0,0 +1,155 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Host side of the signature program, which only attests that a `LedgerInfoWithSignatures`
+//! is signed by a known validator set, without proving any account inclusion.
+
+use getset::Getters;
+use sp1_sdk::{ProverClient, SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerifyingKey};
+
+use aptos_lc_core::types::output::SIGNATURE_OUTPUT_MAGIC;
+use aptos_lc_core::types::status::SignatureStatus;
+
+use crate::client::setup_program;
+use crate::error::LightClientError;
+use crate::output::PublicValuesReader;
+
+pub const SIGNATURE_ELF: &[u8] =
+    include_bytes!("../../programs/signature/elf/riscv32im-succinct-zkvm-elf");
+
+/// Values committed by the signature program.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct SignatureOutput {
+    /// Hash of the validator verifier that signed the ledger info.
+    validator_verifier_hash: [u8; 32],
+    /// Identifier of the block of the ledger info.
+    block_hash: [u8; 32],
+}
+
+impl SignatureOutput {
+    /// Reads the `SignatureOutput` from the public values committed by the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_values` - The public values of an execution or a proof of the program.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SignatureOutput`, or `Err` if the public values were
+    /// not committed by the signature program or if it did not verify the signatures.
+    pub fn from_public_values(
+        public_values: &mut SP1PublicValues,
+    ) -> Result<Self, LightClientError> {
+        let mut reader = PublicValuesReader::new(public_values);
+        reader.read_magic(SIGNATURE_OUTPUT_MAGIC, "signature")?;
+
+        let status = SignatureStatus::from_u8(reader.read_byte()?).map_err(|err| {
+            LightClientError::DeserializationError {
+                structure: "SignatureStatus".to_string(),
+                source: err.into(),
+            }
+        })?;
+        if status != SignatureStatus::Verified {
+            return Err(LightClientError::SignatureFailure { status });
+        }
+
+        Ok(Self {
+            validator_verifier_hash: reader.read_hash()?,
+            block_hash: reader.read_hash()?,
+        })
+    }
+}
+
+/// Sets up the keys of the signature program.
+///
+/// # Arguments
+///
+/// * `client` - The `ProverClient` to set up the program with.
+///
+/// # Returns
+///
+/// A `Result` containing the proving and the verifying keys, or `MissingElf` if the
+/// program was not built before the crate.
+#[inline]
+pub fn generate_keys(
+    client: &ProverClient,
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), LightClientError> {
+    let _span = tracing::info_span!("generate_keys", program = "signature").entered();
+    setup_program(client, "signature", SIGNATURE_ELF)
+}
+
+/// Writes the inputs of the signature program to an `SP1Stdin`.
+///
+/// # Arguments
+///
+/// * `ledger_info` - The `LedgerInfoWithSignatures` to verify, serialized with bcs.
+/// * `validator_verifier` - The `ValidatorVerifier` expected to have signed it, serialized
+///   with bcs.
+///
+/// # Returns
+///
+/// The `SP1Stdin` for the signature program.
+pub fn generate_stdin(ledger_info: &[u8], validator_verifier: &[u8]) -> SP1Stdin {
+    let _span = tracing::info_span!("generate_stdin", program = "signature").entered();
+    let mut stdin = SP1Stdin::new();
+    stdin.write_vec(ledger_info.to_vec());
+    stdin.write_vec(validator_verifier.to_vec());
+    stdin
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod test {
+    use super::*;
+    use crate::fixture::{generate_fixture, AptosWrapperFixture};
+    use aptos_lc_core::crypto::hash::CryptoHash;
+    use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+
+    #[test]
+    fn test_prove_signature() {
+        let fixture = generate_fixture();
+        let ledger_info_bytes = fixture.latest_li_bytes().unwrap();
+        let validator_verifier = fixture.validator_verifier().unwrap();
+
+        let client = ProverClient::mock();
+        let (pk, vk) = generate_keys(&client).unwrap();
+        let stdin = generate_stdin(&ledger_info_bytes, &validator_verifier.to_bytes());
+        let mut proof = client.prove(&pk, stdin).run().unwrap();
+        client.verify(&proof, &vk).unwrap();
+
+        let output = SignatureOutput::from_public_values(&mut proof.public_values).unwrap();
+        let ledger_info = LedgerInfoWithSignatures::from_bytes(&ledger_info_bytes).unwrap();
+        assert_eq!(
+            output.validator_verifier_hash(),
+            validator_verifier.hash().as_ref()
+        );
+        assert_eq!(
+            output.block_hash(),
+            ledger_info.ledger_info().block_id().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_insufficient_signatures() {
+        // The quorum of 4 validators of equal voting power is 3 of them
+        let mut fixture = AptosWrapperFixture::new(2, 4, 3)
+            .unwrap()
+            .with_ledger_info_signers(2);
+        fixture.generate_traffic().unwrap();
+        let ledger_info_bytes = fixture.latest_li_bytes().unwrap();
+        let validator_verifier = fixture.validator_verifier().unwrap();
+
+        let stdin = generate_stdin(&ledger_info_bytes, &validator_verifier.to_bytes());
+        let (mut public_values, _) = ProverClient::mock()
+            .execute(SIGNATURE_ELF, stdin)
+            .run()
+            .unwrap();
+
+        assert!(matches!(
+            SignatureOutput::from_public_values(&mut public_values),
+            Err(LightClientError::SignatureFailure {
+                status: SignatureStatus::InvalidSignatures
+            })
+        ));
+    }
+}
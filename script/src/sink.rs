@@ -0,0 +1,144 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Destination of the machine-readable results of the binaries, such as the benchmark JSON.
+//!
+//! The results are written one per line, to stdout, to a file, or to both. The
+//! informational messages are not results, and keep going through `logger::print_info`.
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::error::LightClientError;
+
+/// Writers the results are emitted to, each along with its name for the errors.
+pub struct OutputSink {
+    writers: Vec<(String, Box<dyn Write>)>,
+}
+
+impl OutputSink {
+    /// Creates a sink emitting to the given writers.
+    ///
+    /// # Arguments
+    ///
+    /// * `writers` - The writers, each along with its name for the errors.
+    ///
+    /// # Returns
+    ///
+    /// A new `OutputSink`.
+    pub fn new(writers: Vec<(String, Box<dyn Write>)>) -> Self {
+        Self { writers }
+    }
+
+    /// Creates the sink selected by the `--out` and `--stdout` flags of the binaries. The
+    /// results go to stdout by default, and only to the file when one is given, unless
+    /// stdout is also requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The file to write the results to, created or truncated, if any.
+    /// * `stdout` - Whether to also write the results to stdout along with `out`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `OutputSink`, or `Err` if the file could not be created.
+    pub fn from_flags(out: Option<&Path>, stdout: bool) -> Result<Self, LightClientError> {
+        let mut writers: Vec<(String, Box<dyn Write>)> = vec![];
+        if stdout || out.is_none() {
+            writers.push(("<stdout>".to_string(), Box::new(std::io::stdout())));
+        }
+        if let Some(path) = out {
+            let file = File::create(path).map_err(|err| LightClientError::IoError {
+                path: path.display().to_string(),
+                source: err,
+            })?;
+            writers.push((path.display().to_string(), Box::new(BufWriter::new(file))));
+        }
+
+        Ok(Self::new(writers))
+    }
+
+    /// Emits a result on its own line to every writer, and flushes them, so that the
+    /// results emitted before an interrupt are not lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result to emit, e.g. a JSON object on a single line.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the result was written to every writer, and `Err`
+    /// otherwise.
+    pub fn emit(&mut self, result: impl Display) -> Result<(), LightClientError> {
+        for (name, writer) in &mut self.writers {
+            writeln!(writer, "{result}")
+                .and_then(|_| writer.flush())
+                .map_err(|err| LightClientError::IoError {
+                    path: name.clone(),
+                    source: err,
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A writer whose bytes can still be read once it is owned by a sink.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_emit_to_memory() {
+        let first = SharedBuffer::default();
+        let second = SharedBuffer::default();
+        let mut sink = OutputSink::new(vec![
+            ("first".to_string(), Box::new(first.clone())),
+            ("second".to_string(), Box::new(second.clone())),
+        ]);
+
+        sink.emit(r#"{"nbr_leaves":32}"#).unwrap();
+        sink.emit(serde_json::json!({ "nbr_leaves": 128 })).unwrap();
+
+        let expected = b"{\"nbr_leaves\":32}\n{\"nbr_leaves\":128}\n";
+        assert_eq!(first.0.borrow().as_slice(), expected);
+        assert_eq!(second.0.borrow().as_slice(), expected);
+    }
+
+    #[test]
+    fn test_emit_to_file() {
+        let path = std::env::temp_dir().join("aptos-lc-test-sink.jsonl");
+        let mut sink = OutputSink::from_flags(Some(&path), false).unwrap();
+        assert_eq!(sink.writers.len(), 1);
+
+        sink.emit("{}").unwrap();
+        drop(sink);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, "{}\n");
+
+        let sink = OutputSink::from_flags(None, false).unwrap();
+        assert_eq!(sink.writers.len(), 1);
+        assert!(matches!(
+            OutputSink::from_flags(Some(&std::env::temp_dir().join("missing/sink.jsonl")), true),
+            Err(LightClientError::IoError { .. })
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metrics of the proofs exported with OpenTelemetry, with the `otel` feature.
+//!
+//! The proving and executing functions of the library record the number of proofs
+//! generated, the proving and verifying durations and the cycles of the executions, under
+//! the names below and with the name of the program as the `program` attribute. They are
+//! recorded to the global meter provider, e.g. the one installed by `init_metrics`, and
+//! are no-ops without the feature.
+
+use std::time::Duration;
+
+#[cfg(feature = "otel")]
+use opentelemetry::{global, metrics::Meter, KeyValue};
+#[cfg(feature = "otel")]
+use opentelemetry_sdk::{metrics::SdkMeterProvider, runtime};
+
+#[cfg(feature = "otel")]
+use crate::error::LightClientError;
+
+/// Counter of the proofs generated.
+pub const PROOFS_GENERATED: &str = "aptos_lc.proofs_generated";
+
+/// Histogram of the proving durations, in milliseconds.
+pub const PROVING_DURATION: &str = "aptos_lc.proving_duration";
+
+/// Histogram of the verifying durations, in milliseconds.
+pub const VERIFICATION_DURATION: &str = "aptos_lc.verification_duration";
+
+/// Histogram of the cycles of the executions.
+pub const CYCLES: &str = "aptos_lc.cycles";
+
+/// Installs a global meter provider exporting the metrics over OTLP. Must be called from
+/// within a Tokio runtime, which the metrics are periodically exported on.
+///
+/// # Arguments
+///
+/// * `endpoint` - The endpoint of the OTLP collector, e.g. `http://localhost:4317`.
+///
+/// # Returns
+///
+/// A `Result` containing the installed `SdkMeterProvider`, to shut down before exiting so
+/// that the last metrics are exported, or `Err` if the exporter could not be built.
+#[cfg(feature = "otel")]
+pub fn init_metrics(endpoint: &str) -> Result<SdkMeterProvider, LightClientError> {
+    let provider = opentelemetry_otlp::new_pipeline()
+        .metrics(runtime::Tokio)
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .build()
+        .map_err(|err| LightClientError::Internal { source: err.into() })?;
+    global::set_meter_provider(provider.clone());
+
+    Ok(provider)
+}
+
+#[cfg(feature = "otel")]
+fn meter() -> Meter {
+    global::meter("aptos-lc")
+}
+
+/// Records a generated proof along with the time spent proving it.
+///
+/// # Arguments
+///
+/// * `program` - The name of the proven program.
+/// * `proving_duration` - The time spent proving.
+pub fn record_proof(program: &str, proving_duration: Duration) {
+    #[cfg(feature = "otel")]
+    {
+        let attributes = [KeyValue::new("program", program.to_string())];
+        meter()
+            .u64_counter(PROOFS_GENERATED)
+            .init()
+            .add(1, &attributes);
+        meter()
+            .f64_histogram(PROVING_DURATION)
+            .with_unit("ms")
+            .init()
+            .record(proving_duration.as_secs_f64() * 1000.0, &attributes);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (program, proving_duration);
+}
+
+/// Records the time spent verifying a proof.
+///
+/// # Arguments
+///
+/// * `program` - The name of the program of the proof.
+/// * `verifying_duration` - The time spent verifying.
+pub fn record_verification(program: &str, verifying_duration: Duration) {
+    #[cfg(feature = "otel")]
+    meter()
+        .f64_histogram(VERIFICATION_DURATION)
+        .with_unit("ms")
+        .init()
+        .record(
+            verifying_duration.as_secs_f64() * 1000.0,
+            &[KeyValue::new("program", program.to_string())],
+        );
+    #[cfg(not(feature = "otel"))]
+    let _ = (program, verifying_duration);
+}
+
+/// Records the cycles of an execution.
+///
+/// # Arguments
+///
+/// * `program` - The name of the executed program.
+/// * `cycles` - The number of cycles the program ran for.
+pub fn record_cycles(program: &str, cycles: u64) {
+    #[cfg(feature = "otel")]
+    meter()
+        .u64_histogram(CYCLES)
+        .init()
+        .record(cycles, &[KeyValue::new("program", program.to_string())]);
+    #[cfg(not(feature = "otel"))]
+    let _ = (program, cycles);
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod test {
+    use super::*;
+    use opentelemetry_sdk::metrics::PeriodicReader;
+    use opentelemetry_sdk::testing::metrics::InMemoryMetricsExporter;
+    use std::collections::HashSet;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_recorded_metric_names() {
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let provider = SdkMeterProvider::builder().with_reader(reader).build();
+        global::set_meter_provider(provider.clone());
+
+        record_proof("inclusion", Duration::from_millis(1200));
+        record_verification("inclusion", Duration::from_millis(30));
+        record_cycles("inclusion", 4_000_000);
+        provider.force_flush().unwrap();
+
+        let names = exporter
+            .get_finished_metrics()
+            .unwrap()
+            .iter()
+            .flat_map(|resource_metrics| &resource_metrics.scope_metrics)
+            .flat_map(|scope_metrics| &scope_metrics.metrics)
+            .map(|metric| metric.name.to_string())
+            .collect::<HashSet<_>>();
+        for name in [
+            PROOFS_GENERATED,
+            PROVING_DURATION,
+            VERIFICATION_DURATION,
+            CYCLES,
+        ] {
+            assert!(names.contains(name), "{name} was not recorded");
+        }
+    }
+}
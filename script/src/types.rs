@@ -1,9 +1,30 @@
+//! Types of the payloads of the light client, along with re-exports of the types of
+//! `aptos-lc-core` and `aptos-types` appearing in the public API of this crate, so that they
+//! can be named without depending on those crates.
+//!
+//! ```
+//! use aptos_lc_script::inclusion::account_state_key;
+//! use aptos_lc_script::types::{AccountAddress, HashValue};
+//!
+//! let key = HashValue::new(account_state_key(AccountAddress::ONE));
+//! assert_ne!(key, HashValue::new([0; 32]));
+//! ```
+
 use std::fmt::Display;
 use anyhow::anyhow;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sp1_sdk::SP1ProofWithPublicValues;
 use crate::inclusion::{SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets};
 
+pub use aptos_lc_core::crypto::hash::HashValue;
+pub use aptos_lc_core::types::leaf::LeafKind;
+pub use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+pub use aptos_lc_core::types::status::{EpochChangeStatus, InclusionStatus};
+pub use aptos_lc_core::types::trusted_state::TrustedState;
+pub use aptos_lc_core::types::validator::ValidatorVerifier;
+pub use aptos_types::account_address::AccountAddress;
+
 #[derive(Serialize, Deserialize)]
 pub struct EpochChangeData {
     pub trusted_state: Vec<u8>,
@@ -11,7 +32,7 @@ pub struct EpochChangeData {
 }
 
 /// Data structure used as a payload to request an inclusion proof generation from the proof server.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InclusionData {
     pub sparse_merkle_proof_assets: SparseMerkleProofAssets,
     pub transaction_proof_assets: TransactionProofAssets,
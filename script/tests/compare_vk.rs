@@ -0,0 +1,40 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+// The binaries are only built with the `test-utils` feature
+#![cfg(feature = "test-utils")]
+
+use std::process::Command;
+
+use aptos_lc_script::{epoch_change, inclusion};
+use sp1_sdk::{HashableKey, ProverClient};
+
+fn compare_vk(bin: &str, expected: &str) -> bool {
+    Command::new(bin)
+        .args(["--compare-vk", expected])
+        .status()
+        .unwrap()
+        .success()
+}
+
+#[test]
+fn test_compare_vk_inclusion() {
+    let (_, vk) = inclusion::generate_keys(&ProverClient::mock()).unwrap();
+
+    assert!(compare_vk(env!("CARGO_BIN_EXE_inclusion"), &vk.bytes32()));
+    assert!(!compare_vk(
+        env!("CARGO_BIN_EXE_inclusion"),
+        &format!("0x{}", "00".repeat(32))
+    ));
+}
+
+#[test]
+fn test_compare_vk_epoch_change() {
+    let (_, vk) = epoch_change::generate_keys(&ProverClient::mock()).unwrap();
+
+    assert!(compare_vk(env!("CARGO_BIN_EXE_epoch_change"), &vk.bytes32()));
+    assert!(!compare_vk(
+        env!("CARGO_BIN_EXE_epoch_change"),
+        &format!("0x{}", "00".repeat(32))
+    ));
+}